@@ -1,9 +1,17 @@
 use clap::{App, Arg};
-use easy_fs::{BlockDevice, EasyFileSystem};
+use easy_fs::{crc32, set_clock, BlockDevice, EasyFileSystem};
+#[cfg(test)]
+use easy_fs::{
+    block_cache_run_prefetch, block_cache_stats, Fat32FileSystem, FsError, FsckProblem,
+    BLOCK_CACHE_SIZE,
+};
 use std::fs::{read_dir, File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(test)]
+use std::sync::atomic::{AtomicU32, Ordering};
 
 const BLOCK_SZ: usize = 512;
 
@@ -23,12 +31,24 @@ impl BlockDevice for BlockFile {
             .expect("Error when seeking!");
         assert_eq!(file.write(buf).unwrap(), BLOCK_SZ, "Not a complete block!");
     }
+
+    fn num_blocks(&self) -> Option<usize> {
+        let file = self.0.lock().unwrap();
+        file.metadata().ok().map(|metadata| metadata.len() as usize / BLOCK_SZ)
+    }
 }
 
 fn main() {
     easy_fs_pack().expect("Error when packing easy-fs!");
 }
 
+/// Stamp the packed files with the time of the host
+fn host_time() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs() as u32)
+}
+
 fn easy_fs_pack() -> std::io::Result<()> {
     let matches = App::new("EasyFileSystem packer")
         .arg(
@@ -45,9 +65,30 @@ fn easy_fs_pack() -> std::io::Result<()> {
                 .takes_value(true)
                 .help("Executable target dir(with backslash)"),
         )
+        .arg(
+            Arg::with_name("label")
+                .short("l")
+                .long("label")
+                .takes_value(true)
+                .help("Volume label of the image"),
+        )
+        .arg(
+            Arg::with_name("check")
+                .short("c")
+                .long("check")
+                .takes_value(true)
+                .help("Check the given image instead of packing"),
+        )
         .get_matches();
+    if let Some(image_path) = matches.value_of("check") {
+        if !easy_fs_check(image_path)? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     let src_path = matches.value_of("source").unwrap();
     let target_path = matches.value_of("target").unwrap();
+    let label = matches.value_of("label").unwrap_or("easy-fs");
     println!("src_path = {}\ntarget_path = {}", src_path, target_path);
     let block_file = Arc::new(BlockFile(Mutex::new({
         let f = OpenOptions::new()
@@ -59,7 +100,10 @@ fn easy_fs_pack() -> std::io::Result<()> {
         f
     })));
     // 16MiB, at most 4095 files
-    let efs = EasyFileSystem::create(block_file, 16 * 2048, 1);
+    set_clock(host_time);
+    let uuid: [u8; 16] = rand::random();
+    let efs = EasyFileSystem::create(block_file, 16 * 2048, 1, uuid, label);
+    println!("uuid = {}\nlabel = {}", format_uuid(&uuid), label);
     let root_inode = Arc::new(EasyFileSystem::root_inode(&efs));
     let apps: Vec<_> = read_dir(src_path)
         .unwrap()
@@ -87,6 +131,96 @@ fn easy_fs_pack() -> std::io::Result<()> {
     Ok(())
 }
 
+/// Time given to easy-fs by `test_clock`
+#[cfg(test)]
+static TEST_TIME: AtomicU32 = AtomicU32::new(0);
+
+#[cfg(test)]
+fn test_clock() -> u32 {
+    TEST_TIME.load(Ordering::Relaxed)
+}
+
+/// Log written by the `fs_stress` user program, one `NAME SIZE CRC32` line per file
+const STRESS_LOG: &str = "stress_log";
+
+/// Run fsck on an image and verify the files recorded in the stress log,
+/// return whether the image is consistent
+fn easy_fs_check(image_path: &str) -> std::io::Result<bool> {
+    let block_file = Arc::new(BlockFile(Mutex::new(
+        OpenOptions::new().read(true).write(true).open(image_path)?,
+    )));
+    let efs = EasyFileSystem::open(block_file);
+    let report = efs.lock().fsck();
+    println!("{} inodes, {} blocks in use", report.inodes, report.blocks);
+    for problem in report.problems.iter() {
+        println!("fsck: {:?}", problem);
+    }
+    let mut consistent = report.is_clean();
+    let root_inode = EasyFileSystem::root_inode(&efs);
+    let log = match root_inode.find(STRESS_LOG) {
+        Ok(log) => log,
+        Err(_) => {
+            println!("stress: the root directory is corrupted");
+            return Ok(false);
+        }
+    };
+    if let Some(log) = log {
+        let mut content = vec![0u8; log.size()];
+        let mut checked = 0;
+        if log.read_at(0, &mut content).is_err() {
+            println!("stress: {} is corrupted", STRESS_LOG);
+            return Ok(false);
+        }
+        for line in String::from_utf8_lossy(&content).lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (name, size, crc) = match fields[..] {
+                [name, size, crc] => (name, size.parse::<usize>(), u32::from_str_radix(crc, 16)),
+                _ => {
+                    println!("stress: bad log line {:?}", line);
+                    consistent = false;
+                    continue;
+                }
+            };
+            let file = match root_inode.find(name) {
+                Ok(Some(file)) => file,
+                Ok(None) => {
+                    println!("stress: {} is missing", name);
+                    consistent = false;
+                    continue;
+                }
+                Err(_) => {
+                    println!("stress: the root directory is corrupted");
+                    return Ok(false);
+                }
+            };
+            let mut data = vec![0u8; file.size()];
+            let ok = file.read_at(0, &mut data) == Ok(data.len())
+                && size == Ok(data.len())
+                && crc == Ok(crc32(&data));
+            if !ok {
+                println!("stress: {} does not match the log", name);
+                consistent = false;
+            }
+            checked += 1;
+        }
+        println!("{} files in {} checked", checked, STRESS_LOG);
+    }
+    Ok(consistent)
+}
+
+/// Format a uuid in the usual 8-4-4-4-12 form
+fn format_uuid(uuid: &[u8; 16]) -> String {
+    let hex: Vec<String> = uuid.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        hex[0..4].concat(),
+        hex[4..6].concat(),
+        hex[6..8].concat(),
+        hex[8..10].concat(),
+        hex[10..16].concat()
+    )
+}
+
 #[test]
 fn efs_test() -> std::io::Result<()> {
     let block_file = Arc::new(BlockFile(Mutex::new({
@@ -98,21 +232,246 @@ fn efs_test() -> std::io::Result<()> {
         f.set_len(8192 * 512).unwrap();
         f
     })));
-    EasyFileSystem::create(block_file.clone(), 4096, 1);
+    EasyFileSystem::create(block_file.clone(), 4096, 1, [0x5a; 16], "test");
     let efs = EasyFileSystem::open(block_file.clone());
+    assert_eq!(efs.lock().uuid(), [0x5a; 16]);
+    assert_eq!(efs.lock().label(), "test");
     let root_inode = EasyFileSystem::root_inode(&efs);
+    let free_blocks = efs.lock().stat().free_blocks;
     root_inode.create("filea");
     root_inode.create("fileb");
-    for name in root_inode.ls() {
+    let stat = efs.lock().stat();
+    // root directory, filea and fileb
+    assert_eq!(stat.free_inodes, stat.total_inodes - 3);
+    // one block for the dirents of the root directory
+    assert_eq!(stat.free_blocks, free_blocks - 1);
+    for name in root_inode.ls().unwrap() {
         println!("{}", name);
     }
-    let filea = root_inode.find("filea").unwrap();
+    let filea = root_inode.find("filea").unwrap().unwrap();
     let greet_str = "Hello, world!";
     filea.write_at(0, greet_str.as_bytes());
     //let mut buffer = [0u8; 512];
     let mut buffer = [0u8; 233];
-    let len = filea.read_at(0, &mut buffer);
+    let len = filea.read_at(0, &mut buffer).unwrap();
     assert_eq!(greet_str, core::str::from_utf8(&buffer[..len]).unwrap(),);
+    // sequential reads only queue readahead, the queued blocks are loaded
+    // between reads as the kernel does from its worker, and then serve the
+    // following reads. The file is larger than the whole block cache
+    let fileb = root_inode.find("fileb").unwrap().unwrap();
+    let blocks = 2 * BLOCK_CACHE_SIZE;
+    fileb.write_at(0, vec![b'x'; blocks * BLOCK_SZ].as_slice());
+    let stats = block_cache_stats();
+    let mut block = [0u8; BLOCK_SZ];
+    for i in 0..blocks {
+        let prefetched = block_cache_stats().prefetched;
+        assert_eq!(fileb.read_at(i * BLOCK_SZ, &mut block), Ok(BLOCK_SZ));
+        assert_eq!(block_cache_stats().prefetched, prefetched);
+        assert!(block.iter().all(|b| *b == b'x'));
+        block_cache_run_prefetch();
+    }
+    let after = block_cache_stats();
+    assert!(after.prefetch_hits > stats.prefetch_hits);
+    // blocks loaded ahead are hits instead of misses for the reader
+    assert!(after.misses - stats.misses < blocks);
+    // reads of sizes which are no multiple of the block size are sequential too
+    let stats = after;
+    let mut chunk = [0u8; 1000];
+    for offset in (0..blocks * BLOCK_SZ).step_by(chunk.len()) {
+        let len = chunk.len().min(blocks * BLOCK_SZ - offset);
+        assert_eq!(fileb.read_at(offset, &mut chunk), Ok(len));
+        assert!(chunk[..len].iter().all(|b| *b == b'x'));
+        block_cache_run_prefetch();
+    }
+    let after = block_cache_stats();
+    assert!(after.prefetch_hits > stats.prefetch_hits);
+    assert!(after.misses - stats.misses < blocks);
+    // copies at aligned and unaligned offsets
+    filea.clear();
+    for (src_offset, dst_offset, len) in [(0, 0, 3 * BLOCK_SZ + 100), (BLOCK_SZ, 7, 2 * BLOCK_SZ)] {
+        assert_eq!(fileb.copy_range(src_offset, &filea, dst_offset, len), Ok(len));
+        let mut copied = vec![0u8; len];
+        assert_eq!(filea.read_at(dst_offset, &mut copied), Ok(len));
+        assert!(copied.iter().all(|b| *b == b'x'));
+    }
+    fileb.clear();
+    filea.clear();
+    assert_eq!(efs.lock().stat().free_blocks, stat.free_blocks);
+    assert!(efs.lock().fsck().is_clean());
+    // blocks behind both levels of indirect blocks are owned by the file
+    filea.write_at(0, vec![b'y'; 300 * BLOCK_SZ].as_slice());
+    let report = efs.lock().fsck();
+    assert!(report.is_clean());
+    assert!(report.blocks > 300);
+    filea.clear();
+    // a block allocated without an owner is reported as leaked
+    let leaked = efs.lock().alloc_data();
+    assert_eq!(efs.lock().fsck().problems, vec![FsckProblem::LeakedBlock { block: leaked }]);
+    efs.lock().dealloc_data(leaked);
+    assert!(efs.lock().fsck().is_clean());
+    // a named pipe is an inode without data blocks
+    let fifo = root_inode.mkfifo("fifo").unwrap();
+    assert!(fifo.is_fifo());
+    assert!(root_inode.find("fifo").unwrap().unwrap().is_fifo());
+    assert!(!filea.is_fifo());
+    assert!(root_inode.mkfifo("fifo").is_none());
+    assert_eq!(fifo.size(), 0);
+    assert!(efs.lock().fsck().is_clean());
+    // inodes are stamped when they are created or modified, not when read
+    set_clock(test_clock);
+    TEST_TIME.store(1000, Ordering::Relaxed);
+    let filec = root_inode.create("filec").unwrap();
+    assert_eq!(filec.mtime(), 1000);
+    assert_eq!(root_inode.mtime(), 1000);
+    TEST_TIME.store(2000, Ordering::Relaxed);
+    filec.write_at(0, b"time");
+    TEST_TIME.store(3000, Ordering::Relaxed);
+    assert_eq!(filec.read_at(0, &mut buffer), Ok(4));
+    assert_eq!(filec.mtime(), 2000);
+    filec.clear();
+    assert_eq!(filec.mtime(), 3000);
+    assert_eq!(root_inode.mtime(), 1000);
+
+    // a bit flipped on disk is reported instead of served, also after
+    // another filesystem has been opened
+    let open_second = || -> std::io::Result<Arc<BlockFile>> {
+        let f = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open("target/fs2.img")?;
+        f.set_len(4096 * 512)?;
+        Ok(Arc::new(BlockFile(Mutex::new(f))))
+    };
+    let second_file = open_second()?;
+    let second = EasyFileSystem::create(second_file.clone(), 4096, 1, [0xa5; 16], "second");
+    let data = EasyFileSystem::root_inode(&second).create("data").unwrap();
+    data.write_at(0, &[b'z'; BLOCK_SZ]);
+    drop(data);
+    // a few blocks behind the indirect block, whose entries follow each other
+    let big = EasyFileSystem::root_inode(&second).create("big").unwrap();
+    big.write_at(0, &[b'q'; 30 * BLOCK_SZ]);
+    drop(big);
+    drop(second);
+    // flip the lowest bit of the first block of the image that `is_target` picks
+    let flip = |is_target: fn(&[u8]) -> bool| {
+        let mut block = [0u8; BLOCK_SZ];
+        let block_id = (0..4096)
+            .find(|&block_id| {
+                second_file.read_block(block_id, &mut block);
+                is_target(&block)
+            })
+            .unwrap();
+        block[0] ^= 1;
+        second_file.write_block(block_id, &block);
+    };
+    flip(|block| block.iter().all(|b| *b == b'z'));
+    // a new device each time, so that nothing is served from the block cache
+    let corrupted = EasyFileSystem::open(open_second()?);
+    let _other = EasyFileSystem::open(second_file.clone());
+    let data = EasyFileSystem::root_inode(&corrupted).find("data").unwrap().unwrap();
+    let mut block = [0u8; BLOCK_SZ];
+    assert_eq!(data.read_at(0, &mut block), Err(FsError::Corrupted));
+    // rewriting the block replaces the corrupted data and its checksum
+    assert_eq!(data.write_at(0, &[b'w'; BLOCK_SZ]), BLOCK_SZ);
+    assert_eq!(data.read_at(0, &mut block), Ok(BLOCK_SZ));
+    assert!(block.iter().all(|b| *b == b'w'));
+    // blocks behind a corrupted indirect block are neither read nor written
+    flip(|block| {
+        let entry = |i: usize| u32::from_le_bytes(block[i * 4..i * 4 + 4].try_into().unwrap());
+        entry(0) != 0
+            && entry(1) == entry(0).wrapping_add(1)
+            && entry(2) == entry(1).wrapping_add(1)
+            && block[12..].iter().all(|b| *b == 0)
+    });
+    let corrupted = EasyFileSystem::open(open_second()?);
+    let big = EasyFileSystem::root_inode(&corrupted).find("big").unwrap().unwrap();
+    assert_eq!(big.read_at(0, &mut block), Ok(BLOCK_SZ));
+    assert_eq!(big.read_at(28 * BLOCK_SZ, &mut block), Err(FsError::Corrupted));
+    assert_eq!(big.write_at(28 * BLOCK_SZ, b"q"), 0);
+    // a corrupted directory fails lookups instead of panicking
+    flip(|block| block.starts_with(b"data\0"));
+    let corrupted = EasyFileSystem::open(open_second()?);
+    let corrupted_root = EasyFileSystem::root_inode(&corrupted);
+    assert_eq!(corrupted_root.ls(), Err(FsError::Corrupted));
+    assert!(matches!(corrupted_root.find("data"), Err(FsError::Corrupted)));
+    assert!(corrupted_root.create("datb").is_none());
+
+    // a zeroed primary super block is recovered from a backup when the
+    // image is opened, and rewritten with counters recounted from the bitmaps
+    let second = EasyFileSystem::create(second_file.clone(), 4096, 1, [0xa5; 16], "second");
+    EasyFileSystem::root_inode(&second).create("kept").unwrap();
+    let stat = second.lock().stat();
+    drop(second);
+    let mut primary = [0u8; BLOCK_SZ];
+    second_file.read_block(0, &mut primary);
+    second_file.write_block(0, &[0u8; BLOCK_SZ]);
+    let recovered = EasyFileSystem::open(open_second()?);
+    assert_eq!(recovered.lock().uuid(), [0xa5; 16]);
+    assert_eq!(recovered.lock().label(), "second");
+    let recovered_stat = recovered.lock().stat();
+    assert_eq!(recovered_stat.free_blocks, stat.free_blocks);
+    assert_eq!(recovered_stat.free_inodes, stat.free_inodes);
+    let recovered_root = EasyFileSystem::root_inode(&recovered);
+    assert!(recovered_root.find("kept").unwrap().is_some());
+    second_file.read_block(0, &mut block);
+    assert_eq!(block, primary);
+    // backups are refreshed on sync, not only when the filesystem is created
+    EasyFileSystem::root_inode(&recovered).create("synced").unwrap();
+    recovered.lock().sync();
+    second_file.read_block(0, &mut primary);
+    second_file.read_block(2048, &mut block);
+    assert_eq!(block, primary);
+    drop(recovered);
+    // an image whose data area starts after the first backup interval and
+    // ends before the second keeps a backup in its last block, and only
+    // blocks inside the device are probed
+    let small_file = {
+        let f = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open("target/fs3.img")?;
+        f.set_len(1500 * 512)?;
+        Arc::new(BlockFile(Mutex::new(f)))
+    };
+    let small = EasyFileSystem::create(small_file.clone(), 1500, 1, [0x5a; 16], "small");
+    EasyFileSystem::root_inode(&small).create("kept").unwrap();
+    assert!(small.lock().fsck().is_clean());
+    drop(small);
+    small_file.write_block(0, &[0u8; BLOCK_SZ]);
+    let small_file = Arc::new(BlockFile(Mutex::new(
+        OpenOptions::new().read(true).write(true).open("target/fs3.img")?,
+    )));
+    let recovered = EasyFileSystem::open(small_file);
+    assert_eq!(recovered.lock().label(), "small");
+    assert!(EasyFileSystem::root_inode(&recovered).find("kept").unwrap().is_some());
+
+    // a FAT32 image shares the block cache with the mounted easy-fs
+    assert!(matches!(Fat32FileSystem::open(block_file.clone()), Err(FsError::BadFormat)));
+    let fat_file = Arc::new(BlockFile(Mutex::new(make_fat32_image("target/fat32.img")?)));
+    let fat = Fat32FileSystem::open(fat_file).unwrap();
+    let fat_root = Fat32FileSystem::root_inode(&fat);
+    assert_eq!(fat_root.ls().unwrap(), vec!["Hello World.txt", "DOCS"]);
+    let hello = fat_root.find("hello world.TXT").unwrap().unwrap();
+    assert!(!hello.is_dir());
+    assert_eq!(hello.size(), FAT32_FILE_SIZE);
+    let mut content = vec![0u8; FAT32_FILE_SIZE + 100];
+    assert_eq!(hello.read_at(0, &mut content), Ok(FAT32_FILE_SIZE));
+    assert!((0..FAT32_FILE_SIZE).all(|i| content[i] == (i % 251) as u8));
+    let mut part = vec![0u8; 700];
+    assert_eq!(hello.read_at(1500, &mut part), Ok(700));
+    assert!((0..700).all(|i| part[i] == ((1500 + i) % 251) as u8));
+    let docs = fat_root.find("docs").unwrap().unwrap();
+    assert!(docs.is_dir());
+    assert_eq!(docs.ls().unwrap(), vec!["note.txt"]);
+    let note = docs.find("note.txt").unwrap().unwrap();
+    let mut note_content = [0u8; 16];
+    assert_eq!(note.read_at(0, &mut note_content), Ok(5));
+    assert_eq!(&note_content[..5], b"hello");
+    assert!(fat_root.find("missing").unwrap().is_none());
     /* 
     let mut random_str_test = |len: usize| {
         filea.clear();
@@ -149,3 +508,112 @@ fn efs_test() -> std::io::Result<()> {
 
     Ok(())
 }
+
+/// Size of the long-named file in the FAT32 test image
+#[cfg(test)]
+const FAT32_FILE_SIZE: usize = 3 * 1024 + 100;
+
+/// Build a small FAT32 image with 2-sector clusters: the root directory holds
+/// a volume label, a deleted entry, "Hello World.txt" stored in the
+/// non-contiguous chain 3 -> 7 -> 5 -> 8, and directory "DOCS" with "note.txt"
+#[cfg(test)]
+fn make_fat32_image(path: &str) -> std::io::Result<File> {
+    const RESERVED: usize = 32;
+    const FAT_SIZE: usize = 8;
+    const TOTAL_SECTORS: usize = RESERVED + FAT_SIZE + 2 * 1000;
+    let mut image = vec![0u8; TOTAL_SECTORS * BLOCK_SZ];
+    let boot = &mut image[..BLOCK_SZ];
+    boot[11..13].copy_from_slice(&(BLOCK_SZ as u16).to_le_bytes());
+    boot[13] = 2;
+    boot[14..16].copy_from_slice(&(RESERVED as u16).to_le_bytes());
+    boot[16] = 1;
+    boot[32..36].copy_from_slice(&(TOTAL_SECTORS as u32).to_le_bytes());
+    boot[36..40].copy_from_slice(&(FAT_SIZE as u32).to_le_bytes());
+    boot[44..48].copy_from_slice(&2u32.to_le_bytes());
+    boot[510] = 0x55;
+    boot[511] = 0xaa;
+    let fat_entries: [(usize, u32); 10] = [
+        (0, 0x0fff_fff8),
+        (1, 0x0fff_ffff),
+        (2, 0x0fff_ffff),
+        (3, 7),
+        (7, 5),
+        (5, 8),
+        (8, 0x0fff_ffff),
+        (9, 0x0fff_ffff),
+        (10, 0x0fff_ffff),
+        (11, 0),
+    ];
+    for (cluster, entry) in fat_entries {
+        let offset = RESERVED * BLOCK_SZ + cluster * 4;
+        image[offset..offset + 4].copy_from_slice(&entry.to_le_bytes());
+    }
+    let cluster_offset = |cluster: usize| (RESERVED + FAT_SIZE + (cluster - 2) * 2) * BLOCK_SZ;
+    let short_entry = |name: &[u8; 11], attr: u8, case: u8, cluster: u32, size: u32| {
+        let mut entry = [0u8; 32];
+        entry[..11].copy_from_slice(name);
+        entry[11] = attr;
+        entry[12] = case;
+        entry[20..22].copy_from_slice(&((cluster >> 16) as u16).to_le_bytes());
+        entry[26..28].copy_from_slice(&(cluster as u16).to_le_bytes());
+        entry[28..32].copy_from_slice(&size.to_le_bytes());
+        entry
+    };
+    // "Hello World.txt" needs two long name entries, stored last part first
+    let long_name: Vec<u16> = "Hello World.txt".encode_utf16().collect();
+    let mut long_entries = Vec::new();
+    for (order, chunk) in [(0x42u8, &long_name[13..]), (0x01, &long_name[..13])] {
+        let mut chars = [0xffffu16; 13];
+        chars[..chunk.len()].copy_from_slice(chunk);
+        if chunk.len() < 13 {
+            chars[chunk.len()] = 0;
+        }
+        let mut entry = [0u8; 32];
+        entry[0] = order;
+        entry[11] = 0x0f;
+        let offsets = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+        for (c, offset) in chars.iter().zip(offsets) {
+            entry[offset..offset + 2].copy_from_slice(&c.to_le_bytes());
+        }
+        long_entries.push(entry);
+    }
+    let mut deleted = short_entry(b"GONE    TXT", 0x20, 0, 11, 1);
+    deleted[0] = 0xe5;
+    let root_entries = [
+        short_entry(b"TESTVOLUME ", 0x08, 0, 0, 0),
+        deleted,
+        long_entries[0],
+        long_entries[1],
+        short_entry(b"HELLOW~1TXT", 0x20, 0, 3, FAT32_FILE_SIZE as u32),
+        short_entry(b"DOCS       ", 0x10, 0, 9, 0),
+    ];
+    for (i, entry) in root_entries.iter().enumerate() {
+        let offset = cluster_offset(2) + i * 32;
+        image[offset..offset + 32].copy_from_slice(entry);
+    }
+    let docs_entries = [
+        short_entry(b".          ", 0x10, 0, 9, 0),
+        short_entry(b"..         ", 0x10, 0, 0, 0),
+        short_entry(b"NOTE    TXT", 0x20, 0x18, 10, 5),
+    ];
+    for (i, entry) in docs_entries.iter().enumerate() {
+        let offset = cluster_offset(9) + i * 32;
+        image[offset..offset + 32].copy_from_slice(entry);
+    }
+    image[cluster_offset(10)..cluster_offset(10) + 5].copy_from_slice(b"hello");
+    for (i, cluster) in [3, 7, 5, 8].into_iter().enumerate() {
+        for j in 0..1024 {
+            if i * 1024 + j < FAT32_FILE_SIZE {
+                image[cluster_offset(cluster) + j] = ((i * 1024 + j) % 251) as u8;
+            }
+        }
+    }
+    let mut f = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    f.write_all(&image)?;
+    Ok(f)
+}
@@ -3,7 +3,15 @@ use spin::Mutex;
 
 use alloc::{sync::Arc, collections::VecDeque};
 
-use crate::{BLOCK_SIZE, BlockDevice, BLOCK_CACHE_SIZE, block_dev};
+use crate::{BLOCK_SIZE, BlockDevice, BLOCK_CACHE_SIZE, BLOCK_CACHE_SHARDS};
+use crate::checksum::checksums_of;
+
+/// Default number of blocks prefetched after a sequential read
+pub const READAHEAD_WINDOW: usize = 4;
+/// The max number of read streams tracked for sequential detection
+const READAHEAD_STREAMS: usize = 8;
+/// The max number of blocks waiting to be prefetched
+const READAHEAD_QUEUE: usize = 32;
 
 
 /// Structure for cache block which is inside memory
@@ -16,20 +24,31 @@ pub struct BlockCache {
     block_device: Arc<dyn BlockDevice>,
     /// dirty flag
     modified: bool,
+    /// whether the data read from disk does not match its checksum, cleared
+    /// once the block is modified as its checksum is recomputed on sync
+    corrupted: bool,
 }
 
 impl BlockCache {
-    /// Load a new BlockCache from disk.
+    /// Load a new BlockCache from disk and verify its checksum.
     pub fn new(block_id: usize, block_device: Arc<dyn BlockDevice>) -> Self {
         let mut cache = [0u8; BLOCK_SIZE];
         block_device.read_block(block_id, &mut cache);
+        let corrupted = checksums_of(&block_device)
+            .map_or(false, |checksums| !checksums.lock().verify(block_id, &cache));
         Self {
             cache,
             block_id,
             block_device,
             modified: false,
+            corrupted,
         }
     }
+    /// Whether the block failed checksum verification when loaded and has
+    /// not been modified since
+    pub fn is_corrupted(&self) -> bool {
+        self.corrupted
+    }
     /// Get pointer from the cache by an offset
     fn addr_of_offset(&self, offset: usize) -> usize {
         &self.cache[offset] as *const _ as usize
@@ -53,6 +72,7 @@ impl BlockCache {
         // assert that the offset is within the block
         assert!(offset + type_size <= BLOCK_SIZE);
         self.modified = true;
+        self.corrupted = false;
         let addr = self.addr_of_offset(offset);
         unsafe { &mut *(addr as *mut T) }
     }
@@ -68,6 +88,9 @@ impl BlockCache {
     pub fn sync(&mut self) {
         if self.modified {
             self.modified = false;
+            if let Some(checksums) = checksums_of(&self.block_device) {
+                checksums.lock().update(self.block_id, &self.cache);
+            }
             self.block_device.write_block(self.block_id, &self.cache);
         }
     }
@@ -79,15 +102,101 @@ impl Drop for BlockCache {
     }
 }
 
+/// Statistics of the block cache, used to check whether readahead helps
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CacheStats {
+    /// requests served from the cache
+    pub hits: usize,
+    /// requests that had to load the block from disk
+    pub misses: usize,
+    /// blocks loaded by readahead
+    pub prefetched: usize,
+    /// prefetched blocks which were requested afterwards
+    pub prefetch_hits: usize,
+}
+
+/// A sequential read stream of an inode
+struct ReadStream {
+    /// key of the device holding the inode
+    device: usize,
+    /// key of the inode on its device
+    inode: usize,
+    /// the byte offset expected by the next sequential read
+    next_offset: usize,
+}
+
+/// Sequential access detection shared by all shards
+pub struct Readahead {
+    /// recently accessed read streams
+    streams: VecDeque<ReadStream>,
+    /// number of blocks to prefetch, `0` disables readahead
+    window: usize,
+    /// blocks waiting to be loaded by `block_cache_run_prefetch`, oldest first
+    pending: VecDeque<(usize, Arc<dyn BlockDevice>)>,
+}
+
+impl Readahead {
+    pub fn new() -> Self {
+        Self {
+            streams: VecDeque::new(),
+            window: READAHEAD_WINDOW,
+            pending: VecDeque::new(),
+        }
+    }
+    /// Record a read of bytes `[start, end)` of the inode identified by `inode`
+    /// on the device identified by `device`, return the number of blocks after
+    /// the last one read worth prefetching. Reads are sequential if each one
+    /// starts where the previous one ended, whatever their sizes
+    pub fn sequential_access(
+        &mut self,
+        device: usize,
+        inode: usize,
+        start: usize,
+        end: usize,
+    ) -> usize {
+        let sequential = match self
+            .streams
+            .iter()
+            .position(|s| s.device == device && s.inode == inode)
+        {
+            Some(idx) => {
+                let stream = self.streams.remove(idx).unwrap();
+                stream.next_offset == start
+            }
+            None => false,
+        };
+        if self.streams.len() == READAHEAD_STREAMS {
+            self.streams.pop_front();
+        }
+        self.streams.push_back(ReadStream { device, inode, next_offset: end });
+        if sequential {
+            self.window
+        } else {
+            0
+        }
+    }
+}
+
+/// Identify a block device by the address of its data, so that blocks with
+/// the same id on different devices are cached separately
+pub fn device_key(block_device: &Arc<dyn BlockDevice>) -> usize {
+    Arc::as_ptr(block_device) as *const () as usize
+}
+
+/// One shard of the block cache
 pub struct BlockCacheManager {
-    /// usize for block id
-    queue: VecDeque<(usize, Arc<Mutex<BlockCache>>)>,
+    /// usize for block id and device key, bool for whether the block is
+    /// loaded by readahead and has not been requested yet
+    queue: VecDeque<(usize, usize, Arc<Mutex<BlockCache>>, bool)>,
+    /// statistics
+    stats: CacheStats,
 }
 
 impl BlockCacheManager {
     pub fn new() -> Self {
         Self {
             queue: VecDeque::new(),
+            stats: CacheStats::default(),
         }
     }
     /// Try to get block cache, if not in the cache, load it from disk
@@ -97,40 +206,103 @@ impl BlockCacheManager {
         block_id: usize,
         block_device: Arc<dyn BlockDevice>,
     ) -> Arc<Mutex<BlockCache>> {
+        let device = device_key(&block_device);
         if let Some(pair) = 
-            self.queue.iter().find(|pair| pair.0 == block_id) {
-                Arc::clone(&pair.1)
+            self.queue.iter_mut().find(|pair| pair.0 == block_id && pair.1 == device) {
+                self.stats.hits += 1;
+                if pair.3 {
+                    pair.3 = false;
+                    self.stats.prefetch_hits += 1;
+                }
+                Arc::clone(&pair.2)
         } else {
             // cannot find
-            if self.queue.len() == BLOCK_CACHE_SIZE {
-                // remove a block with no strong reference
-                if let Some((idx, _)) = self
-                    .queue
-                    .iter()
-                    .enumerate()
-                    .find(|(_, pair)| Arc::strong_count(&pair.1) == 1)
-                {
-                    self.queue.drain(idx..=idx);
-                } else {
-                    panic!("Run out of BlockCache!");
-                }
+            self.stats.misses += 1;
+            if !self.make_room() {
+                panic!("Run out of BlockCache!");
             }
-            // load block into mem and push back to queue
-            let block_cache = Arc::new(
-                Mutex::new(BlockCache::new(
-                    block_id, 
-                    Arc::clone(&block_device)
-            )));
-            self.queue.push_back((block_id, Arc::clone(&block_cache)));
-            block_cache
+            self.load(block_id, block_device, false)
         }
     }
+    /// Remove a block with no strong reference if the shard has used its
+    /// share of the cache. When every block of the shard is in use it grows
+    /// beyond its share, up to `BLOCK_CACHE_SIZE` blocks like the cache
+    /// before sharding, so a shard holds as many blocks in use as the whole
+    /// cache did. Return false if there is no room for a new block
+    fn make_room(&mut self) -> bool {
+        if self.queue.len() < BLOCK_CACHE_SIZE / BLOCK_CACHE_SHARDS {
+            return true;
+        }
+        if let Some((idx, _)) = self
+            .queue
+            .iter()
+            .enumerate()
+            .find(|(_, pair)| Arc::strong_count(&pair.2) == 1)
+        {
+            self.queue.drain(idx..=idx);
+            true
+        } else {
+            self.queue.len() < BLOCK_CACHE_SIZE
+        }
+    }
+    /// Load block into mem and push back to queue
+    fn load(
+        &mut self,
+        block_id: usize,
+        block_device: Arc<dyn BlockDevice>,
+        prefetched: bool,
+    ) -> Arc<Mutex<BlockCache>> {
+        let block_cache = Arc::new(
+            Mutex::new(BlockCache::new(
+                block_id, 
+                Arc::clone(&block_device)
+        )));
+        self.queue.push_back((
+            block_id,
+            device_key(&block_device),
+            Arc::clone(&block_cache),
+            prefetched,
+        ));
+        block_cache
+    }
+    /// Whether a block is cached
+    fn contains(&self, block_id: usize, block_device: &Arc<dyn BlockDevice>) -> bool {
+        let device = device_key(block_device);
+        self.queue.iter().any(|pair| pair.0 == block_id && pair.1 == device)
+    }
+    /// Add a block loaded ahead of use, a block cached meanwhile is kept instead.
+    /// Readahead is only a hint, so give up instead of growing the shard beyond
+    /// its share of the cache
+    fn insert_prefetched(&mut self, block_cache: BlockCache) {
+        if self.contains(block_cache.block_id, &block_cache.block_device)
+            || !self.make_room()
+            || self.queue.len() >= BLOCK_CACHE_SIZE / BLOCK_CACHE_SHARDS
+        {
+            return;
+        }
+        self.queue.push_back((
+            block_cache.block_id,
+            device_key(&block_cache.block_device),
+            Arc::new(Mutex::new(block_cache)),
+            true,
+        ));
+        self.stats.prefetched += 1;
+    }
 }
 
 lazy_static! {
-    /// A global block cache manager
-    pub static ref BLOCK_CACHE_MANAGER: Mutex<BlockCacheManager> = 
-        Mutex::new(BlockCacheManager::new());
+    /// Shards of the global block cache, a block lives in shard
+    /// `block_id % BLOCK_CACHE_SHARDS` so that accesses to different
+    /// blocks seldom contend for the same lock
+    pub static ref BLOCK_CACHE_MANAGER: [Mutex<BlockCacheManager>; BLOCK_CACHE_SHARDS] =
+        core::array::from_fn(|_| Mutex::new(BlockCacheManager::new()));
+    /// Global sequential access detection
+    pub static ref READAHEAD: Mutex<Readahead> = Mutex::new(Readahead::new());
+}
+
+/// Get the shard caching the given block
+fn shard(block_id: usize) -> &'static Mutex<BlockCacheManager> {
+    &BLOCK_CACHE_MANAGER[block_id % BLOCK_CACHE_SHARDS]
 }
 
 /// Get the block cache corresponding to the given block id and block device
@@ -138,14 +310,71 @@ pub fn get_block_cache(
     block_id: usize,
     block_device: Arc<dyn BlockDevice>,
 ) -> Arc<Mutex<BlockCache>> {
-    BLOCK_CACHE_MANAGER
+    shard(block_id)
         .lock()
         .get_block_cache(block_id, block_device)
 }
 /// Sync all block cache to block device
 pub fn block_cache_syn_all() {
-    let manager = BLOCK_CACHE_MANAGER.lock();
-    for (_, cache) in manager.queue.iter() {
-        cache.lock().sync()
+    for manager in BLOCK_CACHE_MANAGER.iter() {
+        let manager = manager.lock();
+        for (_, _, cache, _) in manager.queue.iter() {
+            cache.lock().sync()
+        }
+    }
+}
+/// Queue blocks to be loaded ahead of use by `block_cache_run_prefetch`, so
+/// that the reader does not wait for them. When the queue is full the oldest
+/// blocks are forgotten
+pub fn block_cache_prefetch(block_ids: &[usize], block_device: &Arc<dyn BlockDevice>) {
+    let device = device_key(block_device);
+    let mut readahead = READAHEAD.lock();
+    for &block_id in block_ids {
+        let queued = readahead
+            .pending
+            .iter()
+            .any(|(id, queued_device)| *id == block_id && device_key(queued_device) == device);
+        if queued {
+            continue;
+        }
+        if readahead.pending.len() == READAHEAD_QUEUE {
+            readahead.pending.pop_front();
+        }
+        readahead.pending.push_back((block_id, Arc::clone(block_device)));
     }
-}
\ No newline at end of file
+}
+/// Load the blocks queued by readahead and return how many were read. The
+/// user of the filesystem runs it outside of any read, e.g. the kernel from
+/// a worker thread or the idle loop. No lock of the cache is held while a
+/// block is read from disk
+pub fn block_cache_run_prefetch() -> usize {
+    let mut loaded = 0;
+    loop {
+        let next = READAHEAD.lock().pending.pop_front();
+        let Some((block_id, block_device)) = next else {
+            break;
+        };
+        if shard(block_id).lock().contains(block_id, &block_device) {
+            continue;
+        }
+        let block_cache = BlockCache::new(block_id, block_device);
+        shard(block_id).lock().insert_prefetched(block_cache);
+        loaded += 1;
+    }
+    loaded
+}
+/// Set the number of blocks prefetched after a sequential read, `0` disables readahead
+pub fn set_readahead_window(window: usize) {
+    READAHEAD.lock().window = window;
+}
+/// Get the statistics of the block cache summed over all shards
+pub fn block_cache_stats() -> CacheStats {
+    BLOCK_CACHE_MANAGER.iter().fold(CacheStats::default(), |mut total, manager| {
+        let stats = manager.lock().stats;
+        total.hits += stats.hits;
+        total.misses += stats.misses;
+        total.prefetched += stats.prefetched;
+        total.prefetch_hits += stats.prefetch_hits;
+        total
+    })
+}
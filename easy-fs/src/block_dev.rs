@@ -5,4 +5,8 @@ pub trait BlockDevice: Send + Sync + Any {
     fn read_block(&self, block_id: usize, buf: &mut [u8]);
     /// Write data from buffer to block
     fn write_block(&self, block_id: usize, buf: &[u8]);
+    /// Get the number of blocks of the device, `None` if it is unknown
+    fn num_blocks(&self) -> Option<usize> {
+        None
+    }
 }
\ No newline at end of file
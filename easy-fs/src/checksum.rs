@@ -0,0 +1,133 @@
+use alloc::collections::BTreeMap;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use lazy_static::lazy_static;
+use spin::Mutex;
+
+use crate::{BLOCK_SIZE, BlockDevice};
+use crate::block_cache::device_key;
+
+/// Number of checksums stored in one block of the checksum area
+pub const CHECKSUMS_PER_BLOCK: usize = BLOCK_SIZE / 4;
+
+/// Compute the CRC32 (IEEE) of a buffer
+pub fn crc32(buf: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in buf {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Checksums of the data area, kept in memory and written through to the
+/// checksum area on disk. Blocks of the checksum area never go through the
+/// block cache, so the in-memory table is always the latest version
+pub struct Checksums {
+    /// first block of the checksum area
+    area_start_block: usize,
+    /// first block of the data area
+    data_start_block: usize,
+    /// checksums of data blocks indexed by the inner id in the data area
+    table: Vec<u32>,
+    /// whether loaded blocks are verified against their checksums
+    verify: bool,
+    block_device: Arc<dyn BlockDevice>,
+}
+
+impl Checksums {
+    /// Load the checksum table from disk
+    pub fn load(
+        area_start_block: usize,
+        data_start_block: usize,
+        data_blocks: usize,
+        verify: bool,
+        block_device: Arc<dyn BlockDevice>,
+    ) -> Self {
+        let mut table = Vec::with_capacity(data_blocks);
+        let mut buf = [0u8; BLOCK_SIZE];
+        for area_block in 0..(data_blocks + CHECKSUMS_PER_BLOCK - 1) / CHECKSUMS_PER_BLOCK {
+            block_device.read_block(area_start_block + area_block, &mut buf);
+            for checksum in buf.chunks_exact(4) {
+                if table.len() < data_blocks {
+                    table.push(u32::from_le_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]));
+                }
+            }
+        }
+        Self { area_start_block, data_start_block, table, verify, block_device }
+    }
+    /// Set the checksum of every data block to `checksum` and write the whole area
+    pub fn fill(&mut self, checksum: u32) {
+        self.table.iter_mut().for_each(|c| *c = checksum);
+        for area_block in 0..(self.table.len() + CHECKSUMS_PER_BLOCK - 1) / CHECKSUMS_PER_BLOCK {
+            self.write_area_block(area_block);
+        }
+    }
+    /// Get the inner id of a data block, `None` for blocks outside the data area
+    fn inner_id(&self, block_id: usize) -> Option<usize> {
+        block_id
+            .checked_sub(self.data_start_block)
+            .filter(|inner_id| *inner_id < self.table.len())
+    }
+    /// Check a block just read from disk, blocks outside the data area always pass
+    pub fn verify(&self, block_id: usize, data: &[u8]) -> bool {
+        match self.inner_id(block_id) {
+            Some(inner_id) if self.verify => self.table[inner_id] == crc32(data),
+            _ => true,
+        }
+    }
+    /// Update the checksum of a block about to be written to disk
+    pub fn update(&mut self, block_id: usize, data: &[u8]) {
+        if let Some(inner_id) = self.inner_id(block_id) {
+            let checksum = crc32(data);
+            if self.table[inner_id] != checksum {
+                self.table[inner_id] = checksum;
+                self.write_area_block(inner_id / CHECKSUMS_PER_BLOCK);
+            }
+        }
+    }
+    /// Write a block of the checksum area from the in-memory table
+    fn write_area_block(&self, area_block: usize) {
+        let mut buf = [0u8; BLOCK_SIZE];
+        let start = area_block * CHECKSUMS_PER_BLOCK;
+        let end = (start + CHECKSUMS_PER_BLOCK).min(self.table.len());
+        for (i, checksum) in self.table[start..end].iter().enumerate() {
+            buf[i * 4..i * 4 + 4].copy_from_slice(&checksum.to_le_bytes());
+        }
+        self.block_device.write_block(self.area_start_block + area_block, &buf);
+    }
+}
+
+lazy_static! {
+    /// Checksums of the mounted filesystems keyed by their device, each
+    /// table is owned by its `EasyFileSystem` and forgotten when it is dropped
+    static ref CHECKSUMS: Mutex<BTreeMap<usize, Weak<Mutex<Checksums>>>> =
+        Mutex::new(BTreeMap::new());
+}
+
+/// Verify and record the blocks of `block_device` with `checksums`
+pub fn register_checksums(block_device: &Arc<dyn BlockDevice>, checksums: &Arc<Mutex<Checksums>>) {
+    CHECKSUMS
+        .lock()
+        .insert(device_key(block_device), Arc::downgrade(checksums));
+}
+
+/// Stop verifying and recording the blocks of `block_device`
+pub fn unregister_checksums(block_device: &Arc<dyn BlockDevice>) {
+    CHECKSUMS.lock().remove(&device_key(block_device));
+}
+
+/// Get the checksums of the filesystem on `block_device`, `None` if no
+/// mounted easy-fs uses the device
+pub fn checksums_of(block_device: &Arc<dyn BlockDevice>) -> Option<Arc<Mutex<Checksums>>> {
+    let mut tables = CHECKSUMS.lock();
+    let key = device_key(block_device);
+    let checksums = tables.get(&key).and_then(Weak::upgrade);
+    if checksums.is_none() {
+        tables.remove(&key);
+    }
+    checksums
+}
@@ -0,0 +1,16 @@
+//! Source of the times stamped on inodes
+use spin::RwLock;
+
+/// Function giving the current time, inodes get time 0 until one is set
+static CLOCK: RwLock<Option<fn() -> u32>> = RwLock::new(None);
+
+/// Set the function giving the current time in seconds since the Unix epoch,
+/// which is called when inodes are created or modified
+pub fn set_clock(clock: fn() -> u32) {
+    *CLOCK.write() = Some(clock);
+}
+
+/// Get the current time in seconds since the Unix epoch
+pub(crate) fn now() -> u32 {
+    CLOCK.read().map_or(0, |clock| clock())
+}
@@ -1,11 +1,16 @@
-use alloc::sync::Arc;
-use spin::Mutex;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::{Arc, Weak};
+use spin::{Mutex, RwLock};
 
 use crate::block_dev::BlockDevice;
 use crate::bitmap::Bitmap;
 use crate::BLOCK_SIZE;
-use crate::layout::{DiskInode, SuperBlock, DiskInodeType};
+use crate::layout::{AreaLayout, DiskInode, SuperBlock, DiskInodeType};
 use crate::block_cache::{get_block_cache, block_cache_syn_all};
+use crate::checksum::{
+    crc32, register_checksums, unregister_checksums, Checksums, CHECKSUMS_PER_BLOCK,
+};
 use crate::vfs::Inode;
 
 
@@ -17,18 +22,73 @@ pub struct EasyFileSystem {
     pub inode_bitmap: Bitmap,
     /// Data bitmap
     pub data_bitmap: Bitmap,
+    /// Number of blocks in the filesystem
+    pub(crate) total_blocks: u32,
     inode_area_start_block: u32,
-    data_area_start_block: u32,
+    pub(crate) data_area_start_block: u32,
+    /// Locks of inodes in use, keyed by the position of the disk inode
+    inode_locks: BTreeMap<usize, Weak<RwLock<()>>>,
+    /// Checksums of the data area, only held here to keep them registered
+    /// with the block cache while the filesystem is alive
+    #[allow(unused)]
+    checksums: Arc<Mutex<Checksums>>,
 }
 
 type DataBlock = [u8; BLOCK_SIZE];
 
+/// Distance in blocks between two copies of the super block
+const SUPER_BLOCK_BACKUP_INTERVAL: usize = 1024;
+/// The max number of backup copies of the super block
+const SUPER_BLOCK_BACKUPS: usize = 4;
+
+/// Block ids of the backup super blocks of a filesystem, backups are kept
+/// at fixed intervals inside the data area. An image whose data area holds
+/// none of them keeps one backup in its last block instead
+pub(crate) fn super_block_backups(total_blocks: u32, data_area_start_block: u32) -> impl Iterator<Item = usize> {
+    let in_data_area = move |block_id: &usize| {
+        *block_id >= data_area_start_block as usize && *block_id < total_blocks as usize
+    };
+    let intervals = (1..=SUPER_BLOCK_BACKUPS).map(|i| i * SUPER_BLOCK_BACKUP_INTERVAL);
+    let last_block = (total_blocks as usize)
+        .checked_sub(1)
+        .filter(|_| !intervals.clone().any(|block_id| in_data_area(&block_id)));
+    intervals.chain(last_block).filter(in_data_area)
+}
+
+/// Block ids which may hold a backup super block on a device of
+/// `device_blocks` blocks. The backup in the last block of a small image is
+/// only found if the image fills the device. If the size of the device is
+/// unknown every interval is tried
+fn super_block_candidates(device_blocks: Option<usize>) -> impl Iterator<Item = usize> {
+    (1..=SUPER_BLOCK_BACKUPS)
+        .map(|i| i * SUPER_BLOCK_BACKUP_INTERVAL)
+        .chain(device_blocks.and_then(|blocks| blocks.checked_sub(1)))
+        .filter(move |block_id| device_blocks.map_or(true, |blocks| *block_id < blocks))
+}
+
+/// Usage of a filesystem
+#[derive(Debug, Clone, Copy)]
+pub struct FsStat {
+    /// size of a block in bytes
+    pub block_size: u32,
+    /// number of blocks in the data area
+    pub total_blocks: u32,
+    /// number of unallocated data blocks
+    pub free_blocks: u32,
+    /// number of inodes
+    pub total_inodes: u32,
+    /// number of unallocated inodes
+    pub free_inodes: u32,
+}
+
 impl EasyFileSystem {
-    /// Create a new easy file system
+    /// Create a new easy file system identified by `uuid` and `label`
     pub fn create(
         block_device: Arc<dyn BlockDevice>,
         total_blocks: u32,
         inode_bitmap_blocks: u32,
+        uuid: [u8; 16],
+        label: &str,
     ) -> Arc<Mutex<Self>> {
         // get block size of areas and create bitmaps
         let inode_bitmap = Bitmap::new(1, inode_bitmap_blocks as usize);
@@ -38,19 +98,19 @@ impl EasyFileSystem {
         let inode_total_blocks = inode_bitmap_blocks + inode_area_blocks;
         
         let data_total_blocks = total_blocks - 1 - inode_total_blocks;
-        let data_bitmap_blocks = (data_total_blocks + 4096) / 4097;
-        let data_area_blocks = data_total_blocks - data_bitmap_blocks;
+        // every 4096 data blocks need one bitmap block and 32 checksum blocks
+        let groups = (data_total_blocks + 4128) / 4129;
+        let data_bitmap_blocks = groups;
+        let checksum_area_blocks = groups * (4096 / CHECKSUMS_PER_BLOCK) as u32;
+        let data_area_blocks = data_total_blocks - data_bitmap_blocks - checksum_area_blocks;
         let data_bitmap = Bitmap::new(
             (1 + inode_total_blocks) as usize,
             data_bitmap_blocks as usize,
         );
-        let mut efs = Self {
-            block_device: Arc::clone(&block_device),
-            inode_bitmap,
-            data_bitmap,
-            inode_area_start_block: 1 + inode_bitmap_blocks,
-            data_area_start_block: 1 + inode_total_blocks + data_bitmap_blocks,
-        };
+        let data_area_start_block =
+            1 + inode_total_blocks + data_bitmap_blocks + checksum_area_blocks;
+        // forget checksums of a filesystem previously on the device
+        unregister_checksums(&block_device);
         // clear all blocks
         for i in 0..total_blocks {
             get_block_cache(i as usize, Arc::clone(&block_device))
@@ -59,16 +119,44 @@ impl EasyFileSystem {
                     data_block.iter_mut().for_each(|i| *i = 0);
                 });
         }
+        // every data block is zero now, checksum them before writing anything else
+        block_cache_syn_all();
+        let mut checksums = Checksums::load(
+            (1 + inode_total_blocks + data_bitmap_blocks) as usize,
+            data_area_start_block as usize,
+            data_area_blocks as usize,
+            true,
+            Arc::clone(&block_device),
+        );
+        checksums.fill(crc32(&[0u8; BLOCK_SIZE]));
+        let checksums = Arc::new(Mutex::new(checksums));
+        register_checksums(&block_device, &checksums);
+        let mut efs = Self {
+            block_device: Arc::clone(&block_device),
+            total_blocks,
+            inode_bitmap,
+            data_bitmap,
+            inode_area_start_block: 1 + inode_bitmap_blocks,
+            data_area_start_block,
+            inode_locks: BTreeMap::new(),
+            checksums,
+        };
         // initialize SuperBlock
         get_block_cache(0, Arc::clone(&block_device))
             .lock()
             .modify(0, |super_block: &mut SuperBlock| {
                 super_block.initialize(
-                    total_blocks,
-                    inode_bitmap_blocks,
-                    inode_area_blocks,
-                    data_bitmap_blocks,
-                    data_area_blocks,
+                    AreaLayout {
+                        total_blocks,
+                        inode_bitmap_blocks,
+                        inode_area_blocks,
+                        data_bitmap_blocks,
+                        checksum_area_blocks,
+                        data_area_blocks,
+                    },
+                    inode_num as u32,
+                    uuid,
+                    label,
                 );
             });
         // Write back immidiately
@@ -80,41 +168,121 @@ impl EasyFileSystem {
             .modify(root_inode_offset, |root_inode: &mut DiskInode| {
                 root_inode.initialize(DiskInodeType::Direcotry);
             });
-        block_cache_syn_all();
+        // reserve the blocks of backup super blocks in the data area
+        for block_id in super_block_backups(total_blocks, efs.data_area_start_block) {
+            efs.data_bitmap.reserve(
+                &block_device,
+                block_id - efs.data_area_start_block as usize,
+            );
+            efs.modify_super_block(|super_block| super_block.free_blocks -= 1);
+        }
+        efs.sync();
         Arc::new(Mutex::new(efs))
     }
-    /// Open a block device as filesystem
+    /// Open a block device as filesystem and verify data blocks against
+    /// their checksums
     pub fn open(block_device: Arc<dyn BlockDevice>) -> Arc<Mutex<Self>> {
+        Self::open_with(block_device, true)
+    }
+    /// Open a block device as filesystem, `verify_checksums` tells whether
+    /// data blocks loaded from disk are checked. If the primary super block
+    /// is corrupted, recover it from a backup
+    pub fn open_with(block_device: Arc<dyn BlockDevice>, verify_checksums: bool) -> Arc<Mutex<Self>> {
+        // find a valid SuperBlock, a backup only counts where its own layout puts one
+        let super_block_id = core::iter::once(0)
+            .chain(super_block_candidates(block_device.num_blocks()))
+            .find(|&block_id| {
+                get_block_cache(block_id, Arc::clone(&block_device))
+                    .lock()
+                    .read(0, |super_block: &SuperBlock| {
+                        super_block.is_valid()
+                            && (block_id == 0
+                                || super_block_backups(
+                                    super_block.total_blocks,
+                                    super_block.data_area_start_block(),
+                                )
+                                .any(|backup| backup == block_id))
+                    })
+            })
+            .expect("Error loading EFS!");
         // read SuperBlock
-        get_block_cache(0, Arc::clone(&block_device))
+        let super_block = get_block_cache(super_block_id, Arc::clone(&block_device))
             .lock()
-            .read(0, |super_block: &SuperBlock| {
-                assert!(super_block.is_valid(), "Error loading EFS!");
-                let inode_total_block = 
-                    super_block.inode_bitmap_blocks + super_block.inode_area_blocks;
-                let efs = Self {
-                    block_device,
-                    inode_bitmap: Bitmap::new(
-                        1usize, 
-                        super_block.inode_bitmap_blocks as usize,
-                    ),
-                    data_bitmap: Bitmap::new(
-                        (1 + inode_total_block) as usize,
-                        super_block.data_area_blocks as usize,
-                    ),
-                    inode_area_start_block: 1 + super_block.inode_bitmap_blocks,
-                    data_area_start_block: 1 + inode_total_block + super_block.data_bitmap_blocks,
-                };
-                Arc::new(Mutex::new(efs))
-            })
+            .read(0, |super_block: &SuperBlock| *super_block);
+        let inode_total_block = 
+            super_block.inode_bitmap_blocks + super_block.inode_area_blocks;
+        let data_area_start_block = super_block.data_area_start_block();
+        let checksums = Arc::new(Mutex::new(Checksums::load(
+            (1 + inode_total_block + super_block.data_bitmap_blocks) as usize,
+            data_area_start_block as usize,
+            super_block.data_area_blocks as usize,
+            verify_checksums,
+            Arc::clone(&block_device),
+        )));
+        register_checksums(&block_device, &checksums);
+        let efs = Self {
+            block_device,
+            total_blocks: super_block.total_blocks,
+            inode_bitmap: Bitmap::new(
+                1usize, 
+                super_block.inode_bitmap_blocks as usize,
+            ),
+            data_bitmap: Bitmap::new(
+                (1 + inode_total_block) as usize,
+                super_block.data_bitmap_blocks as usize,
+            ),
+            inode_area_start_block: 1 + super_block.inode_bitmap_blocks,
+            data_area_start_block,
+            inode_locks: BTreeMap::new(),
+            checksums,
+        };
+        if super_block_id != 0 {
+            efs.recover_super_block(super_block);
+        }
+        Arc::new(Mutex::new(efs))
+    }
+    /// Rewrite the primary super block from a backup. Counters in backups may
+    /// be stale, so they are recounted from the bitmaps
+    fn recover_super_block(&self, backup: SuperBlock) {
+        let free_blocks = backup.data_area_blocks
+            - self.data_bitmap.count_allocated(&self.block_device) as u32;
+        let free_inodes = self.inode_bitmap.maximum() as u32
+            - self.inode_bitmap.count_allocated(&self.block_device) as u32;
+        self.modify_super_block(|super_block| {
+            *super_block = backup;
+            super_block.free_blocks = free_blocks;
+            super_block.free_inodes = free_inodes;
+        });
+        self.sync();
+    }
+    /// Copy the primary super block to all backups
+    fn write_super_block_backups(&self) {
+        let super_block = get_block_cache(0, Arc::clone(&self.block_device))
+            .lock()
+            .read(0, |super_block: &SuperBlock| *super_block);
+        for block_id in super_block_backups(self.total_blocks, self.data_area_start_block) {
+            get_block_cache(block_id, Arc::clone(&self.block_device))
+                .lock()
+                .modify(0, |backup: &mut SuperBlock| *backup = super_block);
+        }
+    }
+    /// Call a function over the super block to modify it
+    fn modify_super_block<V>(&self, f: impl FnOnce(&mut SuperBlock) -> V) -> V {
+        get_block_cache(0, Arc::clone(&self.block_device))
+            .lock()
+            .modify(0, f)
     }
     /// Allocate a new inode, return `0` if success
     pub fn alloc_inode(&mut self) -> u32 {
-        self.inode_bitmap.alloc(&self.block_device).unwrap() as u32
+        let inode_id = self.inode_bitmap.alloc(&self.block_device).unwrap() as u32;
+        self.modify_super_block(|super_block| super_block.free_inodes -= 1);
+        inode_id
     }
     /// Allocate a data block
     pub fn alloc_data(&mut self) -> u32 {
-        self.data_bitmap.alloc(&self.block_device).unwrap() as u32 + self.data_area_start_block
+        let block_id = self.data_bitmap.alloc(&self.block_device).unwrap() as u32;
+        self.modify_super_block(|super_block| super_block.free_blocks -= 1);
+        block_id + self.data_area_start_block
     }
     /// Deallocate a data block
     pub fn dealloc_data(&mut self, block_id: u32) {
@@ -127,6 +295,19 @@ impl EasyFileSystem {
             &self.block_device,
             (block_id - self.data_area_start_block) as usize
         );
+        self.modify_super_block(|super_block| super_block.free_blocks += 1);
+    }
+    /// Get the usage of the filesystem from the counters in the super block
+    pub fn stat(&self) -> FsStat {
+        get_block_cache(0, Arc::clone(&self.block_device))
+            .lock()
+            .read(0, |super_block: &SuperBlock| FsStat {
+                block_size: BLOCK_SIZE as u32,
+                total_blocks: super_block.data_area_blocks,
+                free_blocks: super_block.free_blocks,
+                total_inodes: self.inode_bitmap.maximum() as u32,
+                free_inodes: super_block.free_inodes,
+            })
     }
     /// Get the root inode of the filesystem
     pub fn root_inode(efs: &Arc<Mutex<Self>>) -> Inode {
@@ -144,8 +325,45 @@ impl EasyFileSystem {
             (inode_id % inodes_per_block) as usize * inode_size,
         )
     }
+    /// Get the unique id of the filesystem
+    pub fn uuid(&self) -> [u8; 16] {
+        get_block_cache(0, Arc::clone(&self.block_device))
+            .lock()
+            .read(0, |super_block: &SuperBlock| super_block.uuid)
+    }
+    /// Get the volume label of the filesystem
+    pub fn label(&self) -> String {
+        get_block_cache(0, Arc::clone(&self.block_device))
+            .lock()
+            .read(0, |super_block: &SuperBlock| String::from(super_block.label()))
+    }
+    /// Get the lock shared by all `Inode`s of the disk inode at the given position
+    pub fn inode_lock(&mut self, block_id: u32, block_offset: usize) -> Arc<RwLock<()>> {
+        let key = block_id as usize * BLOCK_SIZE + block_offset;
+        if let Some(lock) = self.inode_locks.get(&key).and_then(Weak::upgrade) {
+            return lock;
+        }
+        // forget inodes which are no longer in use
+        self.inode_locks.retain(|_, lock| lock.strong_count() > 0);
+        let lock = Arc::new(RwLock::new(()));
+        self.inode_locks.insert(key, Arc::downgrade(&lock));
+        lock
+    }
+    /// Refresh the backup super blocks and write back all cached blocks. The
+    /// counters in the backups are only as recent as the last sync
+    pub fn sync(&self) {
+        self.write_super_block_backups();
+        block_cache_syn_all();
+    }
     /// Get data block's disk block id by inner id
     pub fn get_data_block_id(&self, data_block_id: u32) -> u32 {
         self.data_area_start_block + data_block_id
     }
+}
+
+impl Drop for EasyFileSystem {
+    /// Write back cached blocks while the checksums can still record them
+    fn drop(&mut self) {
+        self.sync();
+    }
 }
\ No newline at end of file
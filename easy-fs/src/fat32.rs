@@ -0,0 +1,334 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::block_cache::get_block_cache;
+use crate::block_dev::BlockDevice;
+use crate::{BLOCK_SIZE, DIRENT_SIZE, FsError};
+
+/// A sector of the device
+type Sector = [u8; BLOCK_SIZE];
+
+/// Mask of the valid bits of a FAT32 entry
+const FAT_ENTRY_MASK: u32 = 0x0fff_ffff;
+/// FAT entries at or above this value end a cluster chain
+const FAT_END_OF_CHAIN: u32 = 0x0fff_fff8;
+/// FAT entry of a bad cluster
+const FAT_BAD_CLUSTER: u32 = 0x0fff_fff7;
+/// The first cluster of the data region
+const FIRST_CLUSTER: u32 = 2;
+
+const ATTR_VOLUME_ID: u8 = 0x08;
+const ATTR_DIRECTORY: u8 = 0x10;
+/// Attribute of a long file name entry
+const ATTR_LONG_NAME: u8 = 0x0f;
+/// First name byte of a deleted entry
+const ENTRY_DELETED: u8 = 0xe5;
+/// First name byte of the entry ending a directory
+const ENTRY_END: u8 = 0x00;
+/// Flags in byte 12 of a short entry telling its name or extension is lower case
+const LOWER_CASE_BASE: u8 = 0x08;
+const LOWER_CASE_EXT: u8 = 0x10;
+/// Flag in the order byte of the last long file name entry
+const LAST_LONG_ENTRY: u8 = 0x40;
+/// Offsets of the characters stored in a long file name entry
+const LONG_NAME_CHARS: [usize; 13] = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+
+fn le16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn le32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
+}
+
+/// Read-only FAT32 filesystem sharing the block cache with easy-fs
+pub struct Fat32FileSystem {
+    block_device: Arc<dyn BlockDevice>,
+    /// sectors in one cluster
+    sectors_per_cluster: usize,
+    /// first sector of the first FAT
+    fat_start_sector: usize,
+    /// first sector of cluster 2
+    data_start_sector: usize,
+    /// number of clusters in the data region
+    total_clusters: u32,
+    /// first cluster of the root directory
+    root_cluster: u32,
+}
+
+impl Fat32FileSystem {
+    /// Parse the boot sector of a FAT32 filesystem, sectors must be `BLOCK_SIZE` bytes
+    pub fn open(block_device: Arc<dyn BlockDevice>) -> Result<Arc<Self>, FsError> {
+        get_block_cache(0, Arc::clone(&block_device))
+            .lock()
+            .read(0, |boot: &Sector| {
+                let bytes_per_sector = le16(boot, 11) as usize;
+                let sectors_per_cluster = boot[13] as usize;
+                let reserved_sectors = le16(boot, 14) as usize;
+                let fats = boot[16] as usize;
+                let root_entries = le16(boot, 17);
+                let fat_size_16 = le16(boot, 22);
+                let total_sectors = match le16(boot, 19) {
+                    0 => le32(boot, 32) as usize,
+                    sectors => sectors as usize,
+                };
+                let fat_size = le32(boot, 36) as usize;
+                // FAT12/16 have a fixed root directory and a 16-bit FAT size
+                if boot[510..512] != [0x55, 0xaa]
+                    || bytes_per_sector != BLOCK_SIZE
+                    || !sectors_per_cluster.is_power_of_two()
+                    || reserved_sectors == 0
+                    || fats == 0
+                    || root_entries != 0
+                    || fat_size_16 != 0
+                    || fat_size == 0
+                {
+                    return Err(FsError::BadFormat);
+                }
+                let data_start_sector = reserved_sectors + fats * fat_size;
+                let total_clusters = total_sectors
+                    .checked_sub(data_start_sector)
+                    .ok_or(FsError::BadFormat)?
+                    / sectors_per_cluster;
+                // the FAT must have an entry for every cluster
+                let total_clusters = total_clusters.min(fat_size * BLOCK_SIZE / 4 - 2) as u32;
+                Ok(Arc::new(Self {
+                    block_device: Arc::clone(&block_device),
+                    sectors_per_cluster,
+                    fat_start_sector: reserved_sectors,
+                    data_start_sector,
+                    total_clusters,
+                    root_cluster: le32(boot, 44),
+                }))
+            })
+    }
+    /// Get the root directory
+    pub fn root_inode(fs: &Arc<Self>) -> Fat32Inode {
+        Fat32Inode {
+            fs: Arc::clone(fs),
+            first_cluster: fs.root_cluster,
+            size: 0,
+            is_dir: true,
+        }
+    }
+    /// Size of a cluster in bytes
+    fn cluster_size(&self) -> usize {
+        self.sectors_per_cluster * BLOCK_SIZE
+    }
+    /// Whether a cluster lies in the data region
+    fn is_valid_cluster(&self, cluster: u32) -> bool {
+        cluster >= FIRST_CLUSTER && cluster - FIRST_CLUSTER < self.total_clusters
+    }
+    /// Follow the FAT, return `None` at the end of the chain
+    fn next_cluster(&self, cluster: u32) -> Result<Option<u32>, FsError> {
+        let offset = cluster as usize * 4;
+        let entry = get_block_cache(
+            self.fat_start_sector + offset / BLOCK_SIZE,
+            Arc::clone(&self.block_device),
+        )
+        .lock()
+        .read(offset % BLOCK_SIZE, |entry: &u32| *entry & FAT_ENTRY_MASK);
+        if entry >= FAT_END_OF_CHAIN {
+            Ok(None)
+        } else if entry == FAT_BAD_CLUSTER || !self.is_valid_cluster(entry) {
+            Err(FsError::Corrupted)
+        } else {
+            Ok(Some(entry))
+        }
+    }
+    /// Get the first sector of a cluster
+    fn cluster_sector(&self, cluster: u32) -> usize {
+        self.data_start_sector + (cluster - FIRST_CLUSTER) as usize * self.sectors_per_cluster
+    }
+}
+
+/// A file or directory of a FAT32 filesystem
+pub struct Fat32Inode {
+    fs: Arc<Fat32FileSystem>,
+    /// `0` for an empty file
+    first_cluster: u32,
+    /// size in bytes, directories record no size
+    size: u32,
+    is_dir: bool,
+}
+
+/// A directory entry with its long name assembled
+struct Fat32DirEntry {
+    name: String,
+    first_cluster: u32,
+    size: u32,
+    is_dir: bool,
+}
+
+impl Fat32Inode {
+    /// Whether the inode is a directory
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+    /// Size of a file in bytes
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+    /// Read data from the inode, a directory is read to the end of its cluster chain
+    pub fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<usize, FsError> {
+        let end = if self.is_dir {
+            offset + buf.len()
+        } else {
+            (offset + buf.len()).min(self.size as usize)
+        };
+        if offset >= end || !self.fs.is_valid_cluster(self.first_cluster) {
+            return Ok(0);
+        }
+        let cluster_size = self.fs.cluster_size();
+        let mut cluster = self.first_cluster;
+        for _ in 0..offset / cluster_size {
+            match self.fs.next_cluster(cluster)? {
+                Some(next) => cluster = next,
+                None => return Ok(0),
+            }
+        }
+        let mut start = offset;
+        // a loop in the chain can not be longer than the data region
+        for _ in 0..self.fs.total_clusters {
+            let cluster_end = (start / cluster_size + 1) * cluster_size;
+            while start < end.min(cluster_end) {
+                let sector_start = start / BLOCK_SIZE * BLOCK_SIZE;
+                let copy_end = end.min(sector_start + BLOCK_SIZE);
+                let sector = self.fs.cluster_sector(cluster) + start % cluster_size / BLOCK_SIZE;
+                get_block_cache(sector, Arc::clone(&self.fs.block_device))
+                    .lock()
+                    .read(0, |data: &Sector| {
+                        buf[start - offset..copy_end - offset]
+                            .copy_from_slice(&data[start - sector_start..copy_end - sector_start]);
+                    });
+                start = copy_end;
+            }
+            if start == end {
+                return Ok(start - offset);
+            }
+            match self.fs.next_cluster(cluster)? {
+                Some(next) => cluster = next,
+                None => return Ok(start - offset),
+            }
+        }
+        Err(FsError::Corrupted)
+    }
+    /// Read all entries of a directory, skipping the volume label, `.` and `..`.
+    /// The cluster chain is walked once, sector by sector
+    fn entries(&self) -> Result<Vec<Fat32DirEntry>, FsError> {
+        let mut entries = Vec::new();
+        if !self.is_dir || !self.fs.is_valid_cluster(self.first_cluster) {
+            return Ok(entries);
+        }
+        let mut long_name: Vec<u16> = Vec::new();
+        let mut cluster = self.first_cluster;
+        // a loop in the chain can not be longer than the data region
+        for _ in 0..self.fs.total_clusters {
+            for i in 0..self.fs.sectors_per_cluster {
+                let sector = get_block_cache(
+                    self.fs.cluster_sector(cluster) + i,
+                    Arc::clone(&self.fs.block_device),
+                )
+                .lock()
+                .read(0, |data: &Sector| *data);
+                for raw in sector.chunks_exact(DIRENT_SIZE) {
+                    let raw: &[u8; DIRENT_SIZE] = raw.try_into().unwrap();
+                    if !Self::push_entry(raw, &mut long_name, &mut entries) {
+                        return Ok(entries);
+                    }
+                }
+            }
+            match self.fs.next_cluster(cluster)? {
+                Some(next) => cluster = next,
+                None => return Ok(entries),
+            }
+        }
+        Err(FsError::Corrupted)
+    }
+    /// Add the entry ending at the raw directory entry to `entries`, collecting
+    /// long name parts in `long_name`. Return false at the end of the directory
+    fn push_entry(
+        raw: &[u8; DIRENT_SIZE],
+        long_name: &mut Vec<u16>,
+        entries: &mut Vec<Fat32DirEntry>,
+    ) -> bool {
+        match raw[0] {
+            ENTRY_END => return false,
+            ENTRY_DELETED => {
+                long_name.clear();
+                return true;
+            }
+            _ => {}
+        }
+        let attr = raw[11];
+        if attr == ATTR_LONG_NAME {
+            // long name entries come in reverse order before their short entry
+            let mut part: Vec<u16> = LONG_NAME_CHARS.iter().map(|&i| le16(raw, i)).collect();
+            if let Some(len) = part.iter().position(|&c| c == 0) {
+                part.truncate(len);
+            }
+            if raw[0] & LAST_LONG_ENTRY != 0 {
+                long_name.clear();
+            }
+            part.extend_from_slice(long_name);
+            *long_name = part;
+            return true;
+        }
+        if attr & ATTR_VOLUME_ID != 0 || raw[0] == b'.' {
+            long_name.clear();
+            return true;
+        }
+        let name = if long_name.is_empty() {
+            Self::short_name(raw)
+        } else {
+            char::decode_utf16(long_name.iter().copied())
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        };
+        long_name.clear();
+        entries.push(Fat32DirEntry {
+            name,
+            first_cluster: (le16(raw, 20) as u32) << 16 | le16(raw, 26) as u32,
+            size: le32(raw, 28),
+            is_dir: attr & ATTR_DIRECTORY != 0,
+        });
+        true
+    }
+    /// Format the 8.3 name of a short entry
+    fn short_name(raw: &[u8; DIRENT_SIZE]) -> String {
+        let part = |bytes: &[u8], lower: bool| -> String {
+            bytes
+                .iter()
+                .take_while(|&&c| c != b' ')
+                .map(|&c| if lower { c.to_ascii_lowercase() } else { c } as char)
+                .collect()
+        };
+        let mut name = part(&raw[0..8], raw[12] & LOWER_CASE_BASE != 0);
+        let ext = part(&raw[8..11], raw[12] & LOWER_CASE_EXT != 0);
+        if !ext.is_empty() {
+            name.push('.');
+            name.push_str(&ext);
+        }
+        name
+    }
+    /// Find an inode under the current directory by name, ignoring ASCII case like FAT does
+    pub fn find(&self, name: &str) -> Result<Option<Arc<Fat32Inode>>, FsError> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(name))
+            .map(|entry| {
+                Arc::new(Fat32Inode {
+                    fs: Arc::clone(&self.fs),
+                    first_cluster: entry.first_cluster,
+                    size: entry.size,
+                    is_dir: entry.is_dir,
+                })
+            }))
+    }
+    /// List the names in the current directory
+    pub fn ls(&self) -> Result<Vec<String>, FsError> {
+        Ok(self.entries()?.into_iter().map(|entry| entry.name).collect())
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::block_cache::get_block_cache;
+use crate::efs::{super_block_backups, EasyFileSystem};
+use crate::layout::{DirEntry, DiskInode, SuperBlock};
+use crate::DIRENT_SIZE;
+
+/// An inconsistency found by `EasyFileSystem::fsck`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsckProblem {
+    /// a directory entry refers to an inode which is out of range or not allocated
+    BadDirEntry { dir: u32, name: String, inode: u32 },
+    /// an inode is linked by more than one directory entry
+    LinkedTwice { inode: u32 },
+    /// an inode refers to a block outside the data area
+    BlockOutOfRange { inode: u32, block: u32 },
+    /// an inode refers to a block which is free in the data bitmap
+    BlockNotAllocated { inode: u32, block: u32 },
+    /// a block is referred to by two inodes, or twice by the same inode
+    BlockShared { block: u32, inodes: (u32, u32) },
+    /// a block does not match its checksum
+    Corrupted { inode: u32, block: u32 },
+    /// a block is allocated but not referred to by any inode
+    LeakedBlock { block: u32 },
+    /// an inode is allocated but not reachable from the root directory
+    LeakedInode { inode: u32 },
+    /// the free block counter in the super block differs from the data bitmap
+    FreeBlocks { recorded: u32, counted: u32 },
+    /// the free inode counter in the super block differs from the inode bitmap
+    FreeInodes { recorded: u32, counted: u32 },
+}
+
+/// Result of `EasyFileSystem::fsck`
+#[derive(Debug, Default)]
+pub struct FsckReport {
+    /// number of inodes reachable from the root directory, including itself
+    pub inodes: usize,
+    /// number of blocks referred to by reachable inodes
+    pub blocks: usize,
+    pub problems: Vec<FsckProblem>,
+}
+
+impl FsckReport {
+    /// Whether no problem is found
+    pub fn is_clean(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl EasyFileSystem {
+    /// Check the filesystem for inconsistencies between directories, inodes,
+    /// bitmaps, checksums and the counters in the super block. No inode should
+    /// be modified while checking
+    pub fn fsck(&self) -> FsckReport {
+        let block_device = &self.block_device;
+        let super_block = get_block_cache(0, Arc::clone(block_device))
+            .lock()
+            .read(0, |super_block: &SuperBlock| *super_block);
+        let data_area = self.data_area_start_block..self.data_area_start_block + super_block.data_area_blocks;
+        let max_inodes = self.inode_bitmap.maximum() as u32;
+        let mut report = FsckReport::default();
+        // owner inode of every referred block
+        let mut owners: BTreeMap<u32, u32> = BTreeMap::new();
+        let mut visited: BTreeSet<u32> = BTreeSet::new();
+        visited.insert(0);
+        let mut pending = vec![0u32];
+        while let Some(inode_id) = pending.pop() {
+            let (block_id, block_offset) = self.get_disk_inode_pos(inode_id);
+            get_block_cache(block_id as usize, Arc::clone(block_device))
+                .lock()
+                .read(block_offset, |disk_inode: &DiskInode| {
+                    let mut readable = true;
+                    for block in disk_inode.referenced_blocks(|block| data_area.contains(&block), block_device) {
+                        if !data_area.contains(&block) {
+                            report.problems.push(FsckProblem::BlockOutOfRange { inode: inode_id, block });
+                            readable = false;
+                            continue;
+                        }
+                        let bit = (block - data_area.start) as usize;
+                        if !self.data_bitmap.is_allocated(block_device, bit) {
+                            report.problems.push(FsckProblem::BlockNotAllocated { inode: inode_id, block });
+                        }
+                        if let Some(&owner) = owners.get(&block) {
+                            report.problems.push(FsckProblem::BlockShared { block, inodes: (owner, inode_id) });
+                        } else {
+                            owners.insert(block, inode_id);
+                        }
+                        if get_block_cache(block as usize, Arc::clone(block_device)).lock().is_corrupted() {
+                            report.problems.push(FsckProblem::Corrupted { inode: inode_id, block });
+                            readable = false;
+                        }
+                    }
+                    if !disk_inode.is_dir() || !readable {
+                        return;
+                    }
+                    for i in 0..disk_inode.size as usize / DIRENT_SIZE {
+                        let mut dirent = DirEntry::empty();
+                        if disk_inode.read_at(i * DIRENT_SIZE, dirent.as_bytes_mut(), block_device) != Ok(DIRENT_SIZE) {
+                            break;
+                        }
+                        let child = dirent.inode_number();
+                        if child >= max_inodes || !self.inode_bitmap.is_allocated(block_device, child as usize) {
+                            report.problems.push(FsckProblem::BadDirEntry {
+                                dir: inode_id,
+                                name: String::from(dirent.name()),
+                                inode: child,
+                            });
+                        } else if !visited.insert(child) {
+                            report.problems.push(FsckProblem::LinkedTwice { inode: child });
+                        } else {
+                            pending.push(child);
+                        }
+                    }
+                });
+        }
+        report.inodes = visited.len();
+        report.blocks = owners.len();
+        // allocated but unreachable blocks and inodes
+        let backups: BTreeSet<usize> = super_block_backups(self.total_blocks, self.data_area_start_block).collect();
+        for block in data_area.clone() {
+            if self.data_bitmap.is_allocated(block_device, (block - data_area.start) as usize)
+                && !owners.contains_key(&block)
+                && !backups.contains(&(block as usize))
+            {
+                report.problems.push(FsckProblem::LeakedBlock { block });
+            }
+        }
+        for inode in 0..max_inodes {
+            if self.inode_bitmap.is_allocated(block_device, inode as usize) && !visited.contains(&inode) {
+                report.problems.push(FsckProblem::LeakedInode { inode });
+            }
+        }
+        // counters
+        let counted = super_block.data_area_blocks - self.data_bitmap.count_allocated(block_device) as u32;
+        if counted != super_block.free_blocks {
+            report.problems.push(FsckProblem::FreeBlocks { recorded: super_block.free_blocks, counted });
+        }
+        let counted = max_inodes - self.inode_bitmap.count_allocated(block_device) as u32;
+        if counted != super_block.free_inodes {
+            report.problems.push(FsckProblem::FreeInodes { recorded: super_block.free_inodes, counted });
+        }
+        report
+    }
+}
@@ -2,12 +2,14 @@ use core::fmt::Debug;
 
 use alloc::{sync::Arc, vec::Vec};
 
-use crate::{BLOCK_SIZE, block_dev::BlockDevice, block_cache::get_block_cache};
+use crate::{BLOCK_SIZE, FsError, block_dev::BlockDevice, block_cache::get_block_cache};
 use crate::DIRENT_SIZE;
-/// Magic number for sanity check
-const EFS_MAGIC: u32 = 0xdeadbeef;
+/// Magic number for sanity check, changed whenever the on-disk layout
+/// changes so that an image of another layout is refused instead of misread.
+/// `0xdeadbeef` marked the layout without free counters in the super block
+const EFS_MAGIC: u32 = 0xdeadbef0;
 /// The max number of direcion link in an inode
-const INODE_DIRECT_COUNT: usize = 28;
+const INODE_DIRECT_COUNT: usize = 27;
 /// The max number of index using indirect1 inode
 const INODE_INDIRECT1_COUNT: usize = BLOCK_SIZE / 4;
 /// The max number of index using indirect2 inode
@@ -21,10 +23,29 @@ const INDIRECT1_BOUND: usize = DIRECT_BOUND + INODE_INDIRECT1_COUNT;
 const INDIRECT2_BOUND: usize = INDIRECT1_BOUND + INODE_INDIRECT2_COUNT;
 /// The max length of inode name
 const NAME_LENGTH_LIMIT: usize = 27;
+/// The max length of volume label
+pub const LABEL_LENGTH_LIMIT: usize = 15;
+
+/// Sizes of the areas of a filesystem in blocks, given to `SuperBlock::initialize`
+#[derive(Debug, Clone, Copy)]
+pub struct AreaLayout {
+    /// number of blocks in the file system
+    pub total_blocks: u32,
+    /// the number of blocks of inode bitmap
+    pub inode_bitmap_blocks: u32,
+    /// the number of blocks of inode area
+    pub inode_area_blocks: u32,
+    /// the number of blocks of data bitmap
+    pub data_bitmap_blocks: u32,
+    /// the number of blocks of data checksums
+    pub checksum_area_blocks: u32,
+    /// the number of blocks of data area
+    pub data_area_blocks: u32,
+}
 
 /// Super block 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct SuperBlock {
     /// magic number for sanity check
     magic: u32,
@@ -36,39 +57,70 @@ pub struct SuperBlock {
     pub inode_area_blocks: u32,
     /// the number of blocks of data bitmap
     pub data_bitmap_blocks: u32,
+    /// the number of blocks of data checksums
+    pub checksum_area_blocks: u32,
     /// the number of blocks of data area
     pub data_area_blocks: u32,
+    /// the number of unallocated data blocks
+    pub free_blocks: u32,
+    /// the number of unallocated inodes
+    pub free_inodes: u32,
+    /// unique id of the filesystem
+    pub uuid: [u8; 16],
+    /// volume label, terminated by `\0`
+    label: [u8; LABEL_LENGTH_LIMIT + 1],
 }
 
 impl SuperBlock {
     /// Intialize a super block
     pub fn initialize(
         &mut self,
-        total_blocks: u32,
-        inode_bitmap_blocks: u32,
-        inode_area_blocks: u32,
-        data_bitmap_blocks: u32,
-        data_area_blocks: u32,
+        layout: AreaLayout,
+        total_inodes: u32,
+        uuid: [u8; 16],
+        label: &str,
     ) {
+        assert!(label.len() <= LABEL_LENGTH_LIMIT, "Volume label too long!");
+        let mut label_bytes = [0u8; LABEL_LENGTH_LIMIT + 1];
+        label_bytes[..label.len()].copy_from_slice(label.as_bytes());
         *self = Self{
             magic: EFS_MAGIC,
-            total_blocks,
-            inode_bitmap_blocks,
-            inode_area_blocks,
-            data_bitmap_blocks,
-            data_area_blocks,
+            total_blocks: layout.total_blocks,
+            inode_bitmap_blocks: layout.inode_bitmap_blocks,
+            inode_area_blocks: layout.inode_area_blocks,
+            data_bitmap_blocks: layout.data_bitmap_blocks,
+            checksum_area_blocks: layout.checksum_area_blocks,
+            data_area_blocks: layout.data_area_blocks,
+            free_blocks: layout.data_area_blocks,
+            free_inodes: total_inodes,
+            uuid,
+            label: label_bytes,
         };
     }
+    /// Get the volume label
+    pub fn label(&self) -> &str {
+        let len = (0usize..).find(|i| self.label[*i] == 0).unwrap();
+        core::str::from_utf8(&self.label[..len]).unwrap()
+    }
     /// Check if a super block is valid using magi number
     pub fn is_valid(&self) -> bool {
         self.magic == EFS_MAGIC
     }
+    /// Get the first block of the data area
+    pub fn data_area_start_block(&self) -> u32 {
+        1 + self.inode_bitmap_blocks
+            + self.inode_area_blocks
+            + self.data_bitmap_blocks
+            + self.checksum_area_blocks
+    }
 }
 /// Type of a disk inode
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub enum DiskInodeType {
     File,
     Direcotry,
+    /// a named pipe, which has no data on the disk
+    Fifo,
 }
 
 /// An indirect block
@@ -78,6 +130,7 @@ type DataBlock = [u8; BLOCK_SIZE];
 
 /// Disk inode
 #[repr(C)]
+#[derive(Clone)]
 pub struct DiskInode {
     /// size of the file
     pub size: u32,
@@ -87,6 +140,8 @@ pub struct DiskInode {
     pub indirect1: u32,
     /// level 2 indirect inode
     pub indirect2: u32,
+    /// last modification time in seconds since the Unix epoch
+    pub mtime: u32,
     /// type of the file
     type_: DiskInodeType,
 }
@@ -99,6 +154,7 @@ impl DiskInode {
         self.direct.iter_mut().for_each(|v| *v = 0);
         self.indirect1 = 0;
         self.indirect2 = 0;
+        self.mtime = 0;
         self.type_ = type_;
     }
     /// return whether the inode is directory
@@ -110,6 +166,10 @@ impl DiskInode {
     pub fn is_file(&self) -> bool {
         self.type_ == DiskInodeType::File
     }
+    /// return whether the inode is a named pipe
+    pub fn is_fifo(&self) -> bool {
+        self.type_ == DiskInodeType::Fifo
+    }
     /// Return block number correspond to size
     pub fn data_blocks(&self) -> u32 {
         Self::_data_blocks(self.size)
@@ -285,18 +345,57 @@ impl DiskInode {
         self.indirect2 = 0;
         v
     }
+    /// List the data blocks and indirect blocks of the inode without changing
+    /// it, indirect blocks failing `is_valid` are listed but not read
+    pub fn referenced_blocks(
+        &self,
+        is_valid: impl Fn(u32) -> bool,
+        block_device: &Arc<dyn BlockDevice>,
+    ) -> Vec<u32> {
+        let data_blocks = self.data_blocks() as usize;
+        let mut v: Vec<u32> = self.direct.iter().take(data_blocks).copied().collect();
+        // list an indirect block and the first `count` entries in it
+        let read_indirect = |block_id: u32, count: usize, v: &mut Vec<u32>| {
+            v.push(block_id);
+            if is_valid(block_id) {
+                get_block_cache(block_id as usize, Arc::clone(block_device))
+                    .lock()
+                    .read(0, |indirect: &IndirectBlock| v.extend_from_slice(&indirect[..count]));
+            }
+        };
+        if data_blocks > DIRECT_BOUND {
+            read_indirect(self.indirect1, (data_blocks - DIRECT_BOUND).min(INODE_INDIRECT1_COUNT), &mut v);
+        }
+        if data_blocks > INDIRECT1_BOUND {
+            let rest = data_blocks - INDIRECT1_BOUND;
+            let mut indirect1_blocks = Vec::new();
+            read_indirect(
+                self.indirect2,
+                (rest + INODE_INDIRECT1_COUNT - 1) / INODE_INDIRECT1_COUNT,
+                &mut indirect1_blocks,
+            );
+            // the first one is the indirect2 block itself
+            v.push(indirect1_blocks.remove(0));
+            for (a, indirect1) in indirect1_blocks.into_iter().enumerate() {
+                let count = (rest - a * INODE_INDIRECT1_COUNT).min(INODE_INDIRECT1_COUNT);
+                read_indirect(indirect1, count, &mut v);
+            }
+        }
+        v
+    }
     /// Read data from current disk node, start at offset and write to buf until
-    /// buf is full or file is end, return the length of data read
+    /// buf is full or file is end, return the length of data read or
+    /// `FsError::Corrupted` if a data block fails checksum verification
     pub fn read_at(
         &self,
         offset: usize,
         buf: &mut [u8],
         block_device: &Arc<dyn BlockDevice>
-    ) -> usize {
+    ) -> Result<usize, FsError> {
         let mut start = offset;
         let end = (offset + buf.len()).min(self.size as usize);
         if start >= end {
-            return 0;
+            return Ok(0);
         }
         // inner block id
         let mut start_block = start / BLOCK_SIZE;
@@ -309,12 +408,15 @@ impl DiskInode {
             // read and update read size
             let block_read_size = end_current_block - start;
             let dst = &mut buf[read_size..read_size + block_read_size];
-            get_block_cache(
-                self.get_block_id(start_block as u32, block_device) as usize,
+            let block_cache = get_block_cache(
+                self.get_block_id(start_block as u32, block_device)? as usize,
                 Arc::clone(block_device)
-            )
-            .lock()
-            .read(0, |data_block: &DataBlock| {
+            );
+            let block_cache = block_cache.lock();
+            if block_cache.is_corrupted() {
+                return Err(FsError::Corrupted);
+            }
+            block_cache.read(0, |data_block: &DataBlock| {
                 let src = &data_block[start % BLOCK_SIZE..start % BLOCK_SIZE + block_read_size];
                 dst.copy_from_slice(src);
             });
@@ -326,10 +428,11 @@ impl DiskInode {
             start_block += 1;
             start = end_current_block;
         }
-        read_size
+        Ok(read_size)
     }
     /// Write data into current disk inode
-    /// size must be adjusted properly beforehand
+    /// size must be adjusted properly beforehand, the write stops short at a
+    /// block behind a corrupted indirect block
     pub fn write_at(
         &mut self,
         offset: usize,
@@ -347,10 +450,11 @@ impl DiskInode {
             end_current_block = end_current_block.min(end);
             // write adn update write size
             let block_write_size = end_current_block - start;
-            get_block_cache(
-                self.get_block_id(start_block as u32, block_device) as usize,
-                Arc::clone(block_device)
-            )
+            // stop short instead of writing through a corrupted indirect block
+            let Ok(block_id) = self.get_block_id(start_block as u32, block_device) else {
+                break;
+            };
+            get_block_cache(block_id as usize, Arc::clone(block_device))
             .lock()
             .modify(0, |data_block: &mut DataBlock| {
                 let src = &buf[write_size..write_size + block_write_size];
@@ -367,34 +471,42 @@ impl DiskInode {
         }
         write_size
     }
-    /// Get the block id given id in the file
-    pub fn get_block_id(&self, inner_id: u32, block_device: &Arc<dyn BlockDevice>) -> u32 {
+    /// Get the block id given id in the file, `FsError::Corrupted` if an
+    /// indirect block on the way fails checksum verification
+    pub fn get_block_id(
+        &self,
+        inner_id: u32,
+        block_device: &Arc<dyn BlockDevice>,
+    ) -> Result<u32, FsError> {
         let inner_id = inner_id as usize;
         if inner_id < INODE_DIRECT_COUNT {
-            self.direct[inner_id]
+            Ok(self.direct[inner_id])
         } else if inner_id < INDIRECT1_BOUND {
-            get_block_cache(self.indirect1 as usize, Arc::clone(block_device))
-                .lock()
-                .read(0, |indirect_block: &IndirectBlock| {
-                    indirect_block[inner_id - INODE_DIRECT_COUNT]
-                })
+            read_indirect(self.indirect1, inner_id - INODE_DIRECT_COUNT, block_device)
         } else {
             let last = inner_id - INDIRECT1_BOUND;
-            let indirect1: usize = 
-                get_block_cache(self.indirect2 as usize, Arc::clone(block_device))
-                    .lock()
-                    .read(0, |indirect2: &IndirectBlock| {
-                        indirect2[last / INODE_INDIRECT1_COUNT]
-                    }) as usize;
-            get_block_cache(indirect1, Arc::clone(block_device))
-                    .lock()
-                    .read(0, |indirect_block: &IndirectBlock| {
-                        indirect_block[last % INODE_INDIRECT1_COUNT]
-                    })
+            let indirect1 =
+                read_indirect(self.indirect2, last / INODE_INDIRECT1_COUNT, block_device)?;
+            read_indirect(indirect1, last % INODE_INDIRECT1_COUNT, block_device)
         }
     }
 }
 
+/// Read an entry of an indirect block, `FsError::Corrupted` if the block fails
+/// checksum verification
+fn read_indirect(
+    block_id: u32,
+    index: usize,
+    block_device: &Arc<dyn BlockDevice>,
+) -> Result<u32, FsError> {
+    let block_cache = get_block_cache(block_id as usize, Arc::clone(block_device));
+    let block_cache = block_cache.lock();
+    if block_cache.is_corrupted() {
+        return Err(FsError::Corrupted);
+    }
+    Ok(block_cache.read(0, |indirect_block: &IndirectBlock| indirect_block[index]))
+}
+
 /// A directory entry
 #[repr(C)]
 pub struct DirEntry {
@@ -8,12 +8,42 @@ mod layout;
 mod bitmap;
 mod efs;
 mod vfs;
+mod checksum;
+mod fat32;
+mod fsck;
+mod clock;
 
 pub const BLOCK_SIZE: usize = 512;
+/// The number of blocks in the block cache, split evenly between its shards.
+/// A shard whose blocks are all in use may hold up to this many blocks
 pub const BLOCK_CACHE_SIZE: usize = 16;
+/// The number of shards of the block cache
+pub const BLOCK_CACHE_SHARDS: usize = 4;
 /// The size of one directory entry
 pub const DIRENT_SIZE: usize = 32;
 
+/// Errors reported by the filesystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsError {
+    /// a data block does not match its checksum
+    Corrupted,
+    /// the device does not hold a filesystem of the expected type
+    BadFormat,
+}
+
 pub use block_dev::BlockDevice;
-pub use efs::EasyFileSystem;
+pub use efs::{EasyFileSystem, FsStat};
+pub use fsck::{FsckProblem, FsckReport};
+pub use checksum::crc32;
 pub use vfs::Inode;
+pub use fat32::{Fat32FileSystem, Fat32Inode};
+pub use layout::LABEL_LENGTH_LIMIT;
+pub use clock::set_clock;
+/// Sequential reads only queue the following blocks, which are loaded when
+/// `block_cache_run_prefetch` is called. The user of the filesystem must call
+/// it regularly outside of reads, e.g. the kernel from a worker thread or the
+/// idle loop, otherwise nothing is prefetched and the queue keeps only the
+/// most recent blocks
+pub use block_cache::{
+    CacheStats, READAHEAD_WINDOW, block_cache_run_prefetch, block_cache_stats, set_readahead_window,
+};
@@ -1,21 +1,31 @@
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
-use spin::{Mutex, MutexGuard};
+use spin::{Mutex, MutexGuard, RwLock};
 
 use crate::efs::EasyFileSystem;
 use crate::block_dev::BlockDevice;
 use crate::layout::{DiskInode, DirEntry, DiskInodeType};
-use crate::block_cache::{get_block_cache, block_cache_syn_all};
-use crate::DIRENT_SIZE;
-
+use crate::block_cache::{
+    get_block_cache, block_cache_syn_all, block_cache_prefetch, device_key, READAHEAD,
+};
+use crate::{BLOCK_SIZE, DIRENT_SIZE, FsError};
+use crate::clock::now;
 
 /// Virtual filesystem layer over easy-fs
+///
+/// Locks are always taken in the order: inode lock, filesystem lock,
+/// block cache locks. The filesystem lock only guards the bitmaps, so it is
+/// held just while blocks or inodes are allocated and never while waiting
+/// for an inode lock. A directory is locked before the inodes under it.
 pub struct Inode {
     block_id: usize,
     block_offset: usize,
     fs: Arc<Mutex<EasyFileSystem>>,
     block_device: Arc<dyn BlockDevice>,
+    /// shared by all `Inode`s of the same disk inode, read for data access
+    /// and write for changing size or directory entries
+    lock: Arc<RwLock<()>>,
 }
 
 impl Inode {
@@ -26,11 +36,13 @@ impl Inode {
         fs: Arc<Mutex<EasyFileSystem>>,
         block_device: Arc<dyn BlockDevice>,
     ) -> Self {
+        let lock = fs.lock().inode_lock(block_id, block_offset);
         Self {
             block_id: block_id as usize,
             block_offset,
             fs,
             block_device,
+            lock,
         }
     }
     /// Call a function over a disk inode to read it
@@ -46,36 +58,34 @@ impl Inode {
             .modify(self.block_offset, f)
     }
     /// Find inode id under a disk directory inode by name
-    fn find_inode_id(&self, name: &str, disk_inode: &DiskInode) -> Option<u32> {
+    fn find_inode_id(&self, name: &str, disk_inode: &DiskInode) -> Result<Option<u32>, FsError> {
         // assert it is a directory
         assert!(disk_inode.is_dir());
         let file_count = (disk_inode.size as usize) / DIRENT_SIZE;
         let mut dirent = DirEntry::empty();
         for i in 0..file_count {
-            assert_eq!(
-                disk_inode.read_at(DIRENT_SIZE * i, dirent.as_bytes_mut(), &self.block_device),
-                DIRENT_SIZE,
-            );
+            disk_inode.read_at(DIRENT_SIZE * i, dirent.as_bytes_mut(), &self.block_device)?;
             if dirent.name() == name {
-                return Some(dirent.inode_number() as u32)
+                return Ok(Some(dirent.inode_number() as u32))
             }
         }
-        None
+        Ok(None)
     }
-    /// Find and create an `Inode` under curent inode by name
-    pub fn find(&self, name: &str) -> Option<Arc<Inode>> {
-        let fs = self.fs.lock();
-        self.read_disk_inode(|disk_inode| {
-            self.find_inode_id(name, disk_inode).map(|inode_id| {
-                let (block_id, block_offset) = fs.get_disk_inode_pos(inode_id);
-                Arc::new(Self::new(
-                    block_id,
-                    block_offset,
-                    self.fs.clone(),
-                    self.block_device.clone(),
-                ))
-            })
-        })
+    /// Find and create an `Inode` under curent inode by name, `Ok(None)` if
+    /// there is no such entry
+    pub fn find(&self, name: &str) -> Result<Option<Arc<Inode>>, FsError> {
+        let _dir = self.lock.read();
+        let inode_id = match self.read_disk_inode(|disk_inode| self.find_inode_id(name, disk_inode))? {
+            Some(inode_id) => inode_id,
+            None => return Ok(None),
+        };
+        let (block_id, block_offset) = self.fs.lock().get_disk_inode_pos(inode_id);
+        Ok(Some(Arc::new(Self::new(
+            block_id,
+            block_offset,
+            self.fs.clone(),
+            self.block_device.clone(),
+        ))))
     }
     /// Increase the size of a disk inode
     pub fn increase_size(
@@ -96,14 +106,30 @@ impl Inode {
     }
     /// Create inode under current inode by name
     pub fn create(&self, name: &str) -> Option<Arc<Inode>> {
+        self.create_inode(name, DiskInodeType::File)
+    }
+    /// Create a named pipe under current inode by name, the kernel connects
+    /// its readers and writers through a pipe when it is opened
+    pub fn mkfifo(&self, name: &str) -> Option<Arc<Inode>> {
+        self.create_inode(name, DiskInodeType::Fifo)
+    }
+    /// Whether the inode is a named pipe
+    pub fn is_fifo(&self) -> bool {
+        let _inode = self.lock.read();
+        self.read_disk_inode(|disk_inode| disk_inode.is_fifo())
+    }
+    /// Create an inode of the given type under current inode by name,
+    /// `None` if the name exists or the directory cannot be read
+    fn create_inode(&self, name: &str, type_: DiskInodeType) -> Option<Arc<Inode>> {
+        let _dir = self.lock.write();
         let mut fs = self.fs.lock();
         let confirm_existance = |root_inode: &DiskInode| {
             // assert it is a directory
             assert!(root_inode.is_dir());
             self.find_inode_id(name, root_inode)
         };
-        if self.read_disk_inode(confirm_existance).is_some(){
-            // file with same name has already existed
+        if self.read_disk_inode(confirm_existance) != Ok(None) {
+            // file with same name has already existed, or the directory is corrupted
             return None;
         }
         // create a new file
@@ -114,7 +140,8 @@ impl Inode {
         get_block_cache(new_inode_block_id as usize, Arc::clone(&self.block_device))
             .lock()
             .modify(new_inode_block_offset, |new_inode: &mut DiskInode| {
-                new_inode.initialize(DiskInodeType::File);
+                new_inode.initialize(type_);
+                new_inode.mtime = now();
             });
         // add new inode to current directory
         self.modify_disk_inode(|root_inode| {
@@ -130,9 +157,11 @@ impl Inode {
                 dirent.as_bytes(), 
                 &self.block_device
             );
+            root_inode.mtime = now();
         });
 
         let (block_id, block_offset) = fs.get_disk_inode_pos(new_inode_id);
+        drop(fs);
         block_cache_syn_all();
         // return inode
         Some(Arc::new(Self::new(
@@ -143,42 +172,124 @@ impl Inode {
         )))
     }
     /// List inodes under current inode
-    pub fn ls(&self) -> Vec<String> {
-        let _fs = self.fs.lock();
+    pub fn ls(&self) -> Result<Vec<String>, FsError> {
+        let _dir = self.lock.read();
         self.read_disk_inode(|disk_inode| {
             let file_count = (disk_inode.size as usize) / DIRENT_SIZE;
             let mut v: Vec<String> = Vec::new();
             for i in 0..file_count {
                 let mut dirent = DirEntry::empty();
-                assert_eq!(
-                    disk_inode.read_at(i * DIRENT_SIZE, dirent.as_bytes_mut(), &self.block_device),
-                    DIRENT_SIZE,
-                );
+                disk_inode.read_at(i * DIRENT_SIZE, dirent.as_bytes_mut(), &self.block_device)?;
                 v.push(String::from(dirent.name()));
             }
-            v
+            Ok(v)
         }) 
     }
-    /// Read data from current inode
-    pub fn read_at(&self, offset: usize, buf: &mut [u8]) -> usize {
-        let _fs = self.fs.lock();
-        self.read_disk_inode(|disk_inode| disk_inode.read_at(offset, buf, &self.block_device))
+    /// Get the size of the inode in bytes
+    pub fn size(&self) -> usize {
+        let _inode = self.lock.read();
+        self.read_disk_inode(|disk_inode| disk_inode.size as usize)
+    }
+    /// Get the last modification time in seconds since the Unix epoch
+    pub fn mtime(&self) -> u32 {
+        let _inode = self.lock.read();
+        self.read_disk_inode(|disk_inode| disk_inode.mtime)
+    }
+    /// Read data from current inode, queue the following blocks for
+    /// prefetching if the inode is read sequentially
+    pub fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<usize, FsError> {
+        let _inode = self.lock.read();
+        // the inode lock keeps the disk inode from changing, so work on a copy
+        // instead of holding the block of the disk inode while reading data
+        let disk_inode = self.read_disk_inode(DiskInode::clone);
+        let size = disk_inode.read_at(offset, buf, &self.block_device)?;
+        if size > 0 {
+            self.readahead(offset, offset + size, &disk_inode);
+        }
+        Ok(size)
+    }
+    /// Queue the blocks after bytes `[start, end)` just read for prefetching
+    fn readahead(&self, start: usize, end: usize, disk_inode: &DiskInode) {
+        let window = READAHEAD.lock().sequential_access(
+            device_key(&self.block_device),
+            self.key(),
+            start,
+            end,
+        );
+        // the block holding the last byte read is cached already
+        let end = (end - 1) / BLOCK_SIZE + 1;
+        let last = (end + window).min(disk_inode.data_blocks() as usize);
+        if end >= last {
+            return;
+        }
+        // readahead is only a hint, so stop at a corrupted indirect block
+        let block_ids: Vec<usize> = (end..last)
+            .map_while(|inner_id| disk_inode.get_block_id(inner_id as u32, &self.block_device).ok())
+            .map(|block_id| block_id as usize)
+            .collect();
+        block_cache_prefetch(&block_ids, &self.block_device);
+    }
+    /// Grow current inode to `new_size`, the inode lock must be held for writing
+    fn grow(&self, new_size: u32) {
+        let block_needed = self.read_disk_inode(|disk_inode| {
+            (new_size > disk_inode.size).then(|| disk_inode.block_num_needed(new_size))
+        });
+        // only hold the filesystem lock while allocating blocks
+        if let Some(block_needed) = block_needed {
+            let new_blocks = {
+                let mut fs = self.fs.lock();
+                (0..block_needed).map(|_| fs.alloc_data()).collect::<Vec<u32>>()
+            };
+            self.modify_disk_inode(|disk_inode| {
+                disk_inode.increase_size(new_size, new_blocks, &self.block_device);
+            });
+        }
     }
     /// Write data to current inode
     pub fn write_at(&self, offset: usize, buf: &[u8]) -> usize {
-        let mut fs = self.fs.lock();
+        let _inode = self.lock.write();
+        self.grow((offset + buf.len()) as u32);
         let size = self.modify_disk_inode(|disk_inode| {
-            self.increase_size((offset + buf.len()) as u32, disk_inode, &mut fs);
+            disk_inode.mtime = now();
             disk_inode.write_at(offset, buf, &self.block_device)
         });
         block_cache_syn_all();
         size
     }
+    /// Copy `len` bytes at `src_offset` of current inode to `dst_offset` of `dst`
+    /// through a buffer one block at a time, return the number of bytes copied.
+    /// The data is copied into blocks of `dst`, never shared between the inodes
+    pub fn copy_range(
+        &self,
+        src_offset: usize,
+        dst: &Inode,
+        dst_offset: usize,
+        len: usize,
+    ) -> Result<usize, FsError> {
+        let mut buffer = [0u8; BLOCK_SIZE];
+        let mut copied = 0usize;
+        while copied < len {
+            let size = (len - copied).min(BLOCK_SIZE);
+            let read_size = self.read_at(src_offset + copied, &mut buffer[..size])?;
+            if read_size == 0 {
+                break;
+            }
+            dst.write_at(dst_offset + copied, &buffer[..read_size]);
+            copied += read_size;
+        }
+        Ok(copied)
+    }
+    /// The position of the disk inode, which identifies the inode
+    fn key(&self) -> usize {
+        self.block_id * BLOCK_SIZE + self.block_offset
+    }
     /// Clear the data in current inode
     pub fn clear(&self) {
+        let _inode = self.lock.write();
         let mut fs = self.fs.lock();
         self.modify_disk_inode(|disk_inode| {
             let size = disk_inode.size;
+            disk_inode.mtime = now();
             let data_block_dealloc = disk_inode.clear_size(&self.block_device);
             assert!(data_block_dealloc.len() == DiskInode::total_blocks(size) as usize);
             for block in data_block_dealloc.into_iter() {
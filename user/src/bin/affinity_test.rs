@@ -0,0 +1,51 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{exit, fork, getpid, sched_getaffinity, sched_setaffinity, waitpid, yield_};
+
+/// Far beyond the harts of the machine
+const MISSING_HART: usize = 1 << (usize::BITS - 1);
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // processes may run on every hart at first, hart 0 always exists
+    let mut all = 0;
+    assert_eq!(sched_getaffinity(0, &mut all), 0);
+    assert!(all & 1 != 0);
+    let mut mask = 0;
+    assert_eq!(sched_getaffinity(getpid() as usize, &mut mask), 0);
+    assert_eq!(mask, all);
+
+    // a process pinned to hart 0 keeps running there
+    assert_eq!(sched_setaffinity(0, 1), 0);
+    assert_eq!(sched_getaffinity(0, &mut mask), 0);
+    assert_eq!(mask, 1);
+    for _ in 0..10 {
+        yield_();
+    }
+
+    // masks without an existing hart are refused
+    assert_eq!(sched_setaffinity(0, 0), -1);
+    assert_eq!(sched_setaffinity(0, MISSING_HART), -1);
+    assert_eq!(sched_getaffinity(0, &mut mask), 0);
+    assert_eq!(mask, 1);
+
+    // children inherit the mask
+    let pid = fork();
+    if pid == 0 {
+        let mut mask = 0;
+        sched_getaffinity(0, &mut mask);
+        exit(if mask == 1 { 0 } else { -1 });
+    }
+    let mut exit_code = -1;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    assert_eq!(sched_getaffinity(pid as usize, &mut mask), -1);
+
+    assert_eq!(sched_setaffinity(0, all), 0);
+    println!("affinity_test passed!");
+    0
+}
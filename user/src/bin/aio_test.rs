@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{aio_poll, aio_read, aio_wait, aio_write, close, open, OpenFlags};
+
+const LEN: usize = 4096;
+
+/// Some work to overlap with the disk I/O
+fn compute(round: usize) -> usize {
+    (0..1000).fold(round, |acc, i| acc.wrapping_mul(31).wrapping_add(i))
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut data = [0u8; LEN];
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte = (i % 251) as u8;
+    }
+    let fd = open("aio_file\0", OpenFlags::CREATE | OpenFlags::WRONLY);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    // `data` is left alone until the write is done
+    let id = unsafe { aio_write(fd, &data) };
+    assert!(id >= 0);
+    // keep computing until the write is done
+    let mut rounds = 0usize;
+    let mut acc = 0usize;
+    let written = loop {
+        match aio_poll(id as usize) {
+            -2 => {
+                acc = compute(acc);
+                rounds += 1;
+            }
+            ret => break ret,
+        }
+    };
+    assert_eq!(written, LEN as isize);
+    close(fd);
+    println!("aio write done after {} rounds of compute ({})", rounds, acc);
+
+    let fd = open("aio_file\0", OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let mut buffer = [0u8; LEN];
+    // `buffer` is only looked at after `aio_wait`
+    let id = unsafe { aio_read(fd, &mut buffer) };
+    assert!(id >= 0);
+    assert_eq!(aio_wait(id as usize), LEN as isize);
+    close(fd);
+    assert_eq!(data, buffer);
+    println!("aio_test passed!");
+    0
+}
@@ -0,0 +1,42 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use user_lib::{chroot, execvp};
+
+/// Program run in the new root when none is given
+const DEFAULT_PROGRAM: &str = "user_shell";
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc < 2 {
+        println!("usage: chroot DIR [PROGRAM [ARGS...]]");
+        return -1;
+    }
+    let mut root = String::from(argv[1]);
+    root.push('\0');
+    if chroot(root.as_str()) != 0 {
+        println!("chroot: cannot change root to {}", argv[1]);
+        return -1;
+    }
+    // the program is looked up in the new root
+    let args: &[&str] = if argc > 2 { &argv[2..argc] } else { &[DEFAULT_PROGRAM] };
+    let args_copy: Vec<String> = args
+        .iter()
+        .map(|arg| {
+            let mut arg = String::from(*arg);
+            arg.push('\0');
+            arg
+        })
+        .collect();
+    let mut args_addr: Vec<*const u8> = args_copy.iter().map(|arg| arg.as_ptr()).collect();
+    args_addr.push(core::ptr::null::<u8>());
+    execvp(args_copy[0].as_str(), args_addr.as_slice());
+    println!("chroot: cannot execute {}", &args_copy[0][..args_copy[0].len() - 1]);
+    -1
+}
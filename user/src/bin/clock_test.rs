@@ -0,0 +1,43 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{clock_gettime, get_time, get_time_us, TimeSpec, CLOCK_MONOTONIC, CLOCK_REALTIME};
+
+/// 2020-01-01T00:00:00Z, any RTC time should be later
+const EARLIEST_REALTIME: usize = 1_577_836_800;
+const UNKNOWN_CLOCK: usize = 1000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // the monotonic clock never goes backwards
+    let mut last = TimeSpec::default();
+    for _ in 0..1000 {
+        let mut now = TimeSpec::default();
+        assert_eq!(clock_gettime(CLOCK_MONOTONIC, &mut now), 0);
+        assert!(now.nsec < TimeSpec::NSEC_PER_SEC);
+        assert!((now.sec, now.nsec) >= (last.sec, last.nsec));
+        last = now;
+    }
+
+    // and is finer than milliseconds
+    let start = get_time_us();
+    let mut now = start;
+    while now == start {
+        now = get_time_us();
+    }
+    assert!(now - start < 1000);
+    let (ms, us) = (get_time(), get_time_us());
+    assert!(us / 1000 - ms <= 1);
+
+    // the wall clock comes from the RTC
+    let mut real = TimeSpec::default();
+    assert_eq!(clock_gettime(CLOCK_REALTIME, &mut real), 0);
+    assert!(real.sec >= EARLIEST_REALTIME);
+
+    assert_eq!(clock_gettime(UNKNOWN_CLOCK, &mut real), -1);
+    println!("clock_test passed!");
+    0
+}
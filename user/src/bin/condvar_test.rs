@@ -0,0 +1,53 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    condvar_create, condvar_signal, condvar_wait, exit, mutex_blocking_create, mutex_lock,
+    mutex_unlock, sleep, thread_create, waittid,
+};
+
+static mut READY: bool = false;
+static mut MUTEX: usize = 0;
+static mut CONDVAR: usize = 0;
+
+/// Set the flag late, so the other thread is already waiting
+fn signaller() -> ! {
+    sleep(100);
+    let (mutex, condvar) = unsafe { (MUTEX, CONDVAR) };
+    mutex_lock(mutex);
+    unsafe { core::ptr::addr_of_mut!(READY).write_volatile(true) };
+    condvar_signal(condvar);
+    mutex_unlock(mutex);
+    exit(0);
+    unreachable!()
+}
+
+fn waiter() -> ! {
+    let (mutex, condvar) = unsafe { (MUTEX, CONDVAR) };
+    mutex_lock(mutex);
+    while !unsafe { core::ptr::addr_of!(READY).read_volatile() } {
+        condvar_wait(condvar, mutex);
+    }
+    mutex_unlock(mutex);
+    exit(1);
+    unreachable!()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    unsafe {
+        MUTEX = mutex_blocking_create() as usize;
+        CONDVAR = condvar_create() as usize;
+    }
+    let waiter = thread_create(waiter as fn() -> ! as usize, 0);
+    let signaller = thread_create(signaller as fn() -> ! as usize, 0);
+    assert_eq!(waittid(waiter as usize), 1);
+    assert_eq!(waittid(signaller as usize), 0);
+    // signalling without waiters is not remembered and does not block
+    condvar_signal(unsafe { CONDVAR });
+    println!("condvar_test passed!");
+    0
+}
@@ -0,0 +1,50 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::string::String;
+use user_lib::{close, copy_file_range, open, OpenFlags};
+
+/// Bytes copied by one copy_file_range call
+const CHUNK: usize = 64 * 1024;
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc != 3 {
+        println!("usage: cp SOURCE DEST");
+        return -1;
+    }
+    let mut src = String::from(argv[1]);
+    src.push('\0');
+    let mut dst = String::from(argv[2]);
+    dst.push('\0');
+    let fd_in = open(src.as_str(), OpenFlags::RDONLY);
+    if fd_in < 0 {
+        println!("cp: cannot open {}", argv[1]);
+        return -1;
+    }
+    let fd_out = open(dst.as_str(), OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC);
+    if fd_out < 0 {
+        println!("cp: cannot open {}", argv[2]);
+        close(fd_in as usize);
+        return -1;
+    }
+    let mut exit_code = 0;
+    loop {
+        let copied = copy_file_range(fd_in as usize, None, fd_out as usize, None, CHUNK);
+        if copied == 0 {
+            break;
+        }
+        if copied < 0 {
+            println!("cp: error when copying {} to {}", argv[1], argv[2]);
+            exit_code = -1;
+            break;
+        }
+    }
+    close(fd_in as usize);
+    close(fd_out as usize);
+    exit_code
+}
@@ -0,0 +1,63 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{clock_gettime, TimeSpec, CLOCK_REALTIME};
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const SECS_PER_DAY: usize = 86400;
+
+/// Convert days since 1970-01-01 to `(year, month, day)`, with months and
+/// days counted from 1
+fn civil_from_days(days: usize) -> (usize, usize, usize) {
+    // count from 0000-03-01 so that the leap day is the last of a year
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = era * 400 + year_of_era + usize::from(month <= 2);
+    (year, month, day)
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let mut now = TimeSpec::default();
+    if clock_gettime(CLOCK_REALTIME, &mut now) != 0 {
+        println!("date: cannot read the clock");
+        return -1;
+    }
+    if argc > 1 && argv[1] == "+%s" {
+        println!("{}", now.sec);
+        return 0;
+    }
+    let days = now.sec / SECS_PER_DAY;
+    let secs = now.sec % SECS_PER_DAY;
+    let (year, month, day) = civil_from_days(days);
+    // 1970-01-01 was a Thursday
+    let weekday = (days + 4) % 7;
+    println!(
+        "{} {} {:2} {:02}:{:02}:{:02} UTC {}",
+        WEEKDAYS[weekday],
+        MONTHS[month - 1],
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        year
+    );
+    0
+}
@@ -0,0 +1,35 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{statfs, StatFs};
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut st = StatFs::default();
+    if statfs("/\0", &mut st) != 0 {
+        println!("df: cannot get filesystem usage");
+        return -1;
+    }
+    let used_blocks = st.total_blocks - st.free_blocks;
+    let used_inodes = st.total_inodes - st.free_inodes;
+    println!("{:>10} {:>10} {:>10} {:>5}", "1K-blocks", "Used", "Available", "Use%");
+    println!(
+        "{:>10} {:>10} {:>10} {:>4}%",
+        st.total_blocks * st.block_size / 1024,
+        used_blocks * st.block_size / 1024,
+        st.free_blocks * st.block_size / 1024,
+        used_blocks * 100 / st.total_blocks.max(1),
+    );
+    println!("{:>10} {:>10} {:>10} {:>5}", "Inodes", "IUsed", "IFree", "IUse%");
+    println!(
+        "{:>10} {:>10} {:>10} {:>4}%",
+        st.total_inodes,
+        used_inodes,
+        st.free_inodes,
+        used_inodes * 100 / st.total_inodes.max(1),
+    );
+    0
+}
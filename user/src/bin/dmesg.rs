@@ -0,0 +1,40 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{read_kernel_log, syslog, SYSLOG_ACTION_CLEAR};
+
+const USAGE: &str = "usage: dmesg [-c|-C]";
+
+/// Print the kernel log kept since boot or the last clear, `-c` clears it
+/// after printing and `-C` only clears it
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let option = match argc {
+        1 => "",
+        2 if argv[1] == "-c" || argv[1] == "-C" => argv[1],
+        _ => {
+            println!("{}", USAGE);
+            return -1;
+        }
+    };
+    if option == "-C" {
+        if syslog(SYSLOG_ACTION_CLEAR, &mut []) != 0 {
+            println!("dmesg: cannot clear the kernel log");
+            return -1;
+        }
+        return 0;
+    }
+    match read_kernel_log(option == "-c") {
+        Some(log) => {
+            print!("{}", log);
+            0
+        }
+        None => {
+            println!("dmesg: cannot read the kernel log");
+            -1
+        }
+    }
+}
@@ -0,0 +1,318 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use user_lib::console::getchar;
+use user_lib::term::*;
+use user_lib::{close, open, read, write, OpenFlags};
+
+/// Size of the screen, the last row is the status line
+const ROWS: usize = 24;
+const COLS: usize = 80;
+const TEXT_ROWS: usize = ROWS - 1;
+
+const ESC: u8 = 0x1bu8;
+const LF: u8 = 0x0au8;
+const CR: u8 = 0x0du8;
+const DL: u8 = 0x7fu8;
+const BS: u8 = 0x08u8;
+const CTRL_Q: u8 = 0x11u8;
+const CTRL_S: u8 = 0x13u8;
+
+enum Key {
+    Char(u8),
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    Enter,
+    Backspace,
+    Save,
+    Quit,
+    Unknown,
+}
+
+/// Decode one key press, arrow and Home/End keys arrive as escape sequences
+fn read_key() -> Key {
+    match getchar() {
+        ESC => {
+            if getchar() != b'[' {
+                return Key::Unknown;
+            }
+            match getchar() {
+                b'A' => Key::Up,
+                b'B' => Key::Down,
+                b'C' => Key::Right,
+                b'D' => Key::Left,
+                b'H' => Key::Home,
+                b'F' => Key::End,
+                c @ (b'1' | b'4' | b'7' | b'8') => {
+                    // `ESC [ n ~` form of Home and End
+                    if getchar() != b'~' {
+                        return Key::Unknown;
+                    }
+                    if c == b'1' || c == b'7' {
+                        Key::Home
+                    } else {
+                        Key::End
+                    }
+                }
+                _ => Key::Unknown,
+            }
+        }
+        LF | CR => Key::Enter,
+        BS | DL => Key::Backspace,
+        CTRL_S => Key::Save,
+        CTRL_Q => Key::Quit,
+        c if (0x20..0x7f).contains(&c) || c == b'\t' => Key::Char(c),
+        _ => Key::Unknown,
+    }
+}
+
+/// How a byte of the text is shown, every byte takes one column so that
+/// the cursor stays in place on lines which are not ASCII
+fn display_byte(c: u8) -> u8 {
+    match c {
+        b'\t' => b' ',
+        0x20..=0x7e => c,
+        _ => b'?',
+    }
+}
+
+struct Editor {
+    /// file name terminated by `\0`
+    path: String,
+    /// lines are kept as bytes, so a file which is not UTF-8 is saved unchanged
+    lines: Vec<Vec<u8>>,
+    /// cursor position in the text, `col` is a byte index in the line
+    row: usize,
+    col: usize,
+    /// first line shown on the screen
+    row_offset: usize,
+    /// first column shown on the screen
+    col_offset: usize,
+    dirty: bool,
+    status: String,
+}
+
+impl Editor {
+    fn open(name: &str) -> Self {
+        let mut path = String::from(name);
+        path.push('\0');
+        let mut lines = Vec::new();
+        let fd = open(path.as_str(), OpenFlags::RDONLY);
+        if fd >= 0 {
+            let mut content: Vec<u8> = Vec::new();
+            let mut buffer = [0u8; 512];
+            loop {
+                let len = read(fd as usize, &mut buffer);
+                if len <= 0 {
+                    break;
+                }
+                content.extend_from_slice(&buffer[..len as usize]);
+            }
+            close(fd as usize);
+            for line in content.split(|c| *c == LF) {
+                lines.push(line.to_vec());
+            }
+            // a trailing newline does not start another line
+            if content.last() == Some(&LF) {
+                lines.pop();
+            }
+        }
+        if lines.is_empty() {
+            lines.push(Vec::new());
+        }
+        Self {
+            path,
+            lines,
+            row: 0,
+            col: 0,
+            row_offset: 0,
+            col_offset: 0,
+            dirty: false,
+            status: String::from("Ctrl-S: save  Ctrl-Q: quit"),
+        }
+    }
+
+    /// Rewrite the file in place. Without truncate and rename syscalls the
+    /// text cannot be written to a temporary file and renamed over the old
+    /// one, so a failed write can leave the file cut short
+    fn save(&mut self) {
+        let fd = open(
+            self.path.as_str(),
+            OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC,
+        );
+        if fd < 0 {
+            self.status = String::from("cannot open file for writing!");
+            return;
+        }
+        // write the whole text with one call instead of one per line
+        let mut content: Vec<u8> = Vec::new();
+        for line in self.lines.iter() {
+            content.extend_from_slice(line);
+            content.push(LF);
+        }
+        let written = write(fd as usize, &content);
+        close(fd as usize);
+        if written == content.len() as isize {
+            self.dirty = false;
+            self.status = format!("saved {} bytes", written);
+        } else {
+            self.status = String::from("error when writing file!");
+        }
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].len()
+    }
+
+    fn process(&mut self, key: Key) {
+        match key {
+            Key::Up => {
+                self.row = self.row.saturating_sub(1);
+                self.col = self.col.min(self.line_len());
+            }
+            Key::Down => {
+                if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                }
+                self.col = self.col.min(self.line_len());
+            }
+            Key::Left => {
+                if self.col > 0 {
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    self.row -= 1;
+                    self.col = self.line_len();
+                }
+            }
+            Key::Right => {
+                if self.col < self.line_len() {
+                    self.col += 1;
+                } else if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                    self.col = 0;
+                }
+            }
+            Key::Home => self.col = 0,
+            Key::End => self.col = self.line_len(),
+            Key::Char(c) => {
+                self.lines[self.row].insert(self.col, c);
+                self.col += 1;
+                self.dirty = true;
+            }
+            Key::Enter => {
+                let rest = self.lines[self.row].split_off(self.col);
+                self.lines.insert(self.row + 1, rest);
+                self.row += 1;
+                self.col = 0;
+                self.dirty = true;
+            }
+            Key::Backspace => {
+                if self.col > 0 {
+                    self.lines[self.row].remove(self.col - 1);
+                    self.col -= 1;
+                    self.dirty = true;
+                } else if self.row > 0 {
+                    let line = self.lines.remove(self.row);
+                    self.row -= 1;
+                    self.col = self.line_len();
+                    self.lines[self.row].extend_from_slice(&line);
+                    self.dirty = true;
+                }
+            }
+            Key::Save => self.save(),
+            Key::Quit | Key::Unknown => {}
+        }
+    }
+
+    /// Keep the cursor on the screen
+    fn scroll(&mut self) {
+        if self.row < self.row_offset {
+            self.row_offset = self.row;
+        }
+        if self.row >= self.row_offset + TEXT_ROWS {
+            self.row_offset = self.row + 1 - TEXT_ROWS;
+        }
+        if self.col < self.col_offset {
+            self.col_offset = self.col;
+        }
+        if self.col >= self.col_offset + COLS {
+            self.col_offset = self.col + 1 - COLS;
+        }
+    }
+
+    fn render(&mut self) {
+        self.scroll();
+        hide_cursor();
+        move_to(1, 1);
+        for screen_row in 0..TEXT_ROWS {
+            clear_to_eol();
+            match self.lines.get(self.row_offset + screen_row) {
+                Some(line) if line.len() > self.col_offset => {
+                    let end = line.len().min(self.col_offset + COLS);
+                    let shown: Vec<u8> = line[self.col_offset..end]
+                        .iter()
+                        .map(|c| display_byte(*c))
+                        .collect();
+                    write(1, &shown);
+                }
+                Some(_) => {}
+                None => print!("~"),
+            }
+            print!("\r\n");
+        }
+        // status line
+        clear_to_eol();
+        reverse();
+        print!(
+            "{} {} - {}:{} {}",
+            &self.path[..self.path.len() - 1],
+            if self.dirty { "[modified]" } else { "" },
+            self.row + 1,
+            self.col + 1,
+            self.status
+        );
+        reset();
+        move_to(self.row - self.row_offset + 1, self.col - self.col_offset + 1);
+        show_cursor();
+    }
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc != 2 {
+        println!("usage: edit FILE");
+        return -1;
+    }
+    let mut editor = Editor::open(argv[1]);
+    let mut quit_confirm = false;
+    // keys like Ctrl-S reach the editor as typed, the console is restored on return
+    let _raw_mode = enable_raw_mode();
+    clear_screen();
+    loop {
+        editor.render();
+        let key = read_key();
+        if let Key::Quit = key {
+            if !editor.dirty || quit_confirm {
+                break;
+            }
+            quit_confirm = true;
+            editor.status = String::from("unsaved changes, press Ctrl-Q again to quit");
+            continue;
+        }
+        quit_confirm = false;
+        editor.process(key);
+    }
+    clear_screen();
+    0
+}
@@ -0,0 +1,77 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, epoll_create, epoll_ctl, epoll_wait, pipe, read, write, EpollEvent, EPOLLET, EPOLLHUP,
+    EPOLLIN, EPOLL_CTL_ADD, EPOLL_CTL_DEL, EPOLL_CTL_MOD,
+};
+
+const PIPES: usize = 4;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let epfd = epoll_create();
+    assert!(epfd > 0);
+    let epfd = epfd as usize;
+    let mut pipes = [[0usize; 2]; PIPES];
+    for (i, pipe_fd) in pipes.iter_mut().enumerate() {
+        assert_eq!(pipe(pipe_fd), 0);
+        let event = EpollEvent {
+            events: EPOLLIN,
+            data: i as u64,
+        };
+        assert_eq!(epoll_ctl(epfd, EPOLL_CTL_ADD, pipe_fd[0], Some(&event)), 0);
+    }
+    // an fd can only be added once
+    let event = EpollEvent {
+        events: EPOLLIN,
+        data: 0,
+    };
+    assert!(epoll_ctl(epfd, EPOLL_CTL_ADD, pipes[0][0], Some(&event)) < 0);
+    let mut events = [EpollEvent::default(); PIPES];
+    assert_eq!(epoll_wait(epfd, &mut events, 0), 0);
+
+    // only the pipe written to is reported, with its data
+    assert_eq!(write(pipes[2][1], b"abcd"), 4);
+    assert_eq!(epoll_wait(epfd, &mut events, -1), 1);
+    assert_eq!(events[0].data, 2);
+    assert_eq!(events[0].events & EPOLLIN, EPOLLIN);
+
+    // level triggered: reported again while data is left
+    let mut buffer = [0u8; 2];
+    assert_eq!(read(pipes[2][0], &mut buffer), 2);
+    assert_eq!(epoll_wait(epfd, &mut events, 0), 1);
+
+    // edge triggered: reported once until new data arrives
+    let event = EpollEvent {
+        events: EPOLLIN | EPOLLET,
+        data: 2,
+    };
+    assert_eq!(epoll_ctl(epfd, EPOLL_CTL_MOD, pipes[2][0], Some(&event)), 0);
+    assert_eq!(epoll_wait(epfd, &mut events, 0), 1);
+    assert_eq!(epoll_wait(epfd, &mut events, 0), 0);
+    assert_eq!(write(pipes[2][1], b"ef"), 2);
+    assert_eq!(epoll_wait(epfd, &mut events, 0), 1);
+    assert_eq!(epoll_wait(epfd, &mut events, 0), 0);
+
+    // removed fds are not reported
+    assert_eq!(epoll_ctl(epfd, EPOLL_CTL_DEL, pipes[2][0], None), 0);
+    assert_eq!(epoll_wait(epfd, &mut events, 0), 0);
+
+    // hang-ups are reported without being waited for
+    close(pipes[1][1]);
+    assert_eq!(epoll_wait(epfd, &mut events, -1), 1);
+    assert_eq!(events[0].data, 1);
+    assert_eq!(events[0].events & EPOLLHUP, EPOLLHUP);
+
+    for pipe_fd in pipes.iter() {
+        close(pipe_fd[0]);
+        close(pipe_fd[1]);
+    }
+    close(epfd);
+    println!("epoll_test passed!");
+    0
+}
@@ -0,0 +1,62 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::fb::{rgb, Framebuffer};
+use user_lib::sleep;
+
+const FRAMES: usize = 300;
+const FRAME_MS: usize = 16;
+const BOX_SIZE: usize = 48;
+
+/// Draw a gradient with a box bouncing over it
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut fb = match Framebuffer::open() {
+        Some(fb) => fb,
+        None => {
+            println!("fb_demo: no framebuffer, run QEMU with a virtio-gpu device");
+            return -1;
+        }
+    };
+    let (width, height) = (fb.width(), fb.height());
+    println!("fb_demo: {}x{} display", width, height);
+    let background = |fb: &mut Framebuffer, x: usize, y: usize, w: usize, h: usize| {
+        for y in y..(y + h).min(height) {
+            for x in x..(x + w).min(width) {
+                let r = (x * 255 / width) as u8;
+                let g = (y * 255 / height) as u8;
+                fb.set_pixel(x, y, rgb(r, g, 128));
+            }
+        }
+    };
+    background(&mut fb, 0, 0, width, height);
+    let (mut x, mut y) = (0isize, 0isize);
+    let (mut dx, mut dy) = (5isize, 3isize);
+    for frame in 0..FRAMES {
+        // only the old position of the box is painted over
+        background(&mut fb, x as usize, y as usize, BOX_SIZE, BOX_SIZE);
+        if x + dx < 0 || (x + dx) as usize + BOX_SIZE > width {
+            dx = -dx;
+        }
+        if y + dy < 0 || (y + dy) as usize + BOX_SIZE > height {
+            dy = -dy;
+        }
+        x += dx;
+        y += dy;
+        let shade = (frame * 255 / FRAMES) as u8;
+        fb.fill_rect(
+            x as usize,
+            y as usize,
+            BOX_SIZE,
+            BOX_SIZE,
+            rgb(255, shade, 0),
+        );
+        fb.flush();
+        sleep(FRAME_MS);
+    }
+    println!("fb_demo: done");
+    0
+}
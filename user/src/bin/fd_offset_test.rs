@@ -0,0 +1,73 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{close, dup, exit, fork, open, read, waitpid, write, OpenFlags};
+
+const CONTENT: &str = "0123456789abcdefghijklmnopqrstuv";
+
+/// Read 4 bytes from fd and check they are the given part of CONTENT
+fn expect_chunk(fd: usize, start: usize) {
+    let mut buffer = [0u8; 4];
+    assert_eq!(read(fd, &mut buffer), 4);
+    assert_eq!(&buffer, &CONTENT.as_bytes()[start..start + 4]);
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let path = "fd_offset\0";
+    let fd = open(path, OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC);
+    assert!(fd > 0);
+    write(fd as usize, CONTENT.as_bytes());
+    close(fd as usize);
+
+    // reads through a dup'd fd continue where the original stopped
+    let fd = open(path, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let dup_fd = dup(fd);
+    assert!(dup_fd > 0);
+    let dup_fd = dup_fd as usize;
+    expect_chunk(fd, 0);
+    expect_chunk(dup_fd, 4);
+    expect_chunk(fd, 8);
+
+    // a child inherits the offset and its reads move the parent's offset too
+    let pid = fork();
+    if pid == 0 {
+        expect_chunk(fd, 12);
+        expect_chunk(dup_fd, 16);
+        exit(0);
+    }
+    let mut exit_code: i32 = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    expect_chunk(dup_fd, 20);
+    close(dup_fd);
+    close(fd);
+
+    // writes from parent and child append after each other instead of overwriting
+    let fd = open(path, OpenFlags::WRONLY | OpenFlags::TRUNC);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let pid = fork();
+    if pid == 0 {
+        write(fd, &CONTENT.as_bytes()[..16]);
+        exit(0);
+    }
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    write(fd, &CONTENT.as_bytes()[16..]);
+    close(fd);
+
+    let fd = open(path, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let mut buffer = [0u8; 64];
+    let len = read(fd, &mut buffer) as usize;
+    close(fd);
+    assert_eq!(&buffer[..len], CONTENT.as_bytes());
+    println!("fd_offset_test passed!");
+    0
+}
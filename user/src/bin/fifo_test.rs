@@ -0,0 +1,55 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{close, exec, exit, fork, mkfifo, open, read, waitpid, write, OpenFlags};
+
+const PATH: &str = "fifo_test_pipe\0";
+const MESSAGE: &[u8] = b"through a named pipe";
+
+#[no_mangle]
+pub fn main(argc: usize, _argv: &[&str]) -> i32 {
+    // the writer runs as a new program, so it shares nothing with the reader
+    if argc > 1 {
+        let fd = open(PATH, OpenFlags::WRONLY);
+        assert!(fd > 0);
+        assert_eq!(write(fd as usize, MESSAGE), MESSAGE.len() as isize);
+        close(fd as usize);
+        return 0;
+    }
+    // the fifo may be left by an earlier run, but there is only one
+    mkfifo(PATH);
+    assert!(mkfifo(PATH) < 0);
+    let pid = fork();
+    if pid == 0 {
+        let args = [
+            "fifo_test\0".as_ptr(),
+            "writer\0".as_ptr(),
+            core::ptr::null(),
+        ];
+        exec("fifo_test\0", &args);
+        exit(-1);
+    }
+    let fd = open(PATH, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let mut buffer = [0u8; 64];
+    let mut len = 0;
+    loop {
+        let n = read(fd, &mut buffer[len..]);
+        assert!(n >= 0);
+        if n == 0 {
+            break;
+        }
+        len += n as usize;
+    }
+    close(fd);
+    assert_eq!(&buffer[..len], MESSAGE);
+    let mut exit_code: i32 = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    println!("fifo_test passed!");
+    0
+}
@@ -0,0 +1,128 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use user_lib::rand::Rng;
+use user_lib::{close, get_time, open, read, write, OpenFlags};
+
+/// Number of files created when not given on the command line
+const DEFAULT_FILES: usize = 2000;
+/// The max size of a file
+const MAX_FILE_SIZE: usize = 4096;
+/// Log checked by `easy-fs-fuse --check`, one `NAME SIZE CRC32` line per file
+const STRESS_LOG: &str = "stress_log\0";
+
+/// Compute the CRC32 (IEEE) of a buffer, the same as easy-fs uses
+fn crc32(buf: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in buf {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Replace the content of a file
+fn write_file(path: &str, data: &[u8]) -> bool {
+    let fd = open(path, OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC);
+    if fd < 0 {
+        return false;
+    }
+    let written = write(fd as usize, data);
+    close(fd as usize);
+    written == data.len() as isize
+}
+
+/// Read a whole file
+fn read_file(path: &str) -> Option<Vec<u8>> {
+    let fd = open(path, OpenFlags::RDONLY);
+    if fd < 0 {
+        return None;
+    }
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 512];
+    loop {
+        let len = read(fd as usize, &mut buffer);
+        if len < 0 {
+            close(fd as usize);
+            return None;
+        }
+        if len == 0 {
+            break;
+        }
+        data.extend_from_slice(&buffer[..len as usize]);
+    }
+    close(fd as usize);
+    Some(data)
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let files = match argv.get(1).map(|n| n.parse::<usize>()) {
+        None => DEFAULT_FILES,
+        Some(Ok(n)) if argc == 2 && n > 0 => n,
+        _ => {
+            println!("usage: fs_stress [FILES]");
+            return -1;
+        }
+    };
+    // seeded from the time when the kernel has no random numbers
+    let mut rng = Rng::new().unwrap_or_else(|| Rng::from_seed(get_time() as u64));
+    // size and checksum of every file, in creation order
+    let mut expected: Vec<(usize, u32)> = Vec::with_capacity(files);
+    let name = |i: usize| format!("stress{}", i);
+    let path = |i: usize| format!("stress{}\0", i);
+    let mut content = vec![0u8; MAX_FILE_SIZE];
+    // create files, rewriting an earlier file now and then
+    for i in 0..files {
+        let mut targets = vec![i];
+        if i > 0 && rng.below(4) == 0 {
+            targets.push(rng.below(i));
+        }
+        for target in targets {
+            let size = rng.below(MAX_FILE_SIZE + 1);
+            rng.fill(&mut content[..size]);
+            if !write_file(path(target).as_str(), &content[..size]) {
+                println!("fs_stress: cannot write {}", name(target));
+                return -1;
+            }
+            let record = (size, crc32(&content[..size]));
+            if target == expected.len() {
+                expected.push(record);
+            } else {
+                expected[target] = record;
+            }
+        }
+        if (i + 1) % 500 == 0 {
+            println!("fs_stress: {} files written", i + 1);
+        }
+    }
+    // read everything back
+    let mut log = String::new();
+    for (i, (size, crc)) in expected.iter().enumerate() {
+        match read_file(path(i).as_str()) {
+            Some(data) if data.len() == *size && crc32(&data) == *crc => {}
+            _ => {
+                println!("fs_stress: {} does not match what was written", name(i));
+                return -1;
+            }
+        }
+        log.push_str(&format!("{} {} {:08x}\n", name(i), size, crc));
+    }
+    if !write_file(STRESS_LOG, log.as_bytes()) {
+        println!("fs_stress: cannot write the log");
+        return -1;
+    }
+    println!("fs_stress passed! {} files recorded in stress_log", files);
+    0
+}
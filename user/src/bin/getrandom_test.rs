@@ -0,0 +1,44 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::rand::{self, Rng};
+use user_lib::{getrandom, GRND_NONBLOCK};
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // the whole buffer is filled, and two draws differ
+    let mut a = [0u8; 64];
+    let mut b = [0u8; 64];
+    assert_eq!(getrandom(&mut a, 0), a.len() as isize);
+    assert_eq!(getrandom(&mut b, GRND_NONBLOCK), b.len() as isize);
+    assert_ne!(a, b);
+    assert!(a.iter().any(|&byte| byte != 0));
+    assert_eq!(getrandom(&mut [], 0), 0);
+    println!("getrandom ok");
+
+    // every value of a small range comes up
+    let mut seen = [false; 8];
+    for _ in 0..200 {
+        seen[rand::below(seen.len()).unwrap()] = true;
+    }
+    assert!(seen.iter().all(|&s| s));
+
+    // a seeded generator repeats itself, kernel seeded ones do not
+    let mut x = Rng::from_seed(42);
+    let mut y = Rng::from_seed(42);
+    for _ in 0..16 {
+        assert_eq!(x.next_u64(), y.next_u64());
+    }
+    assert_ne!(
+        Rng::new().unwrap().next_u64(),
+        Rng::new().unwrap().next_u64()
+    );
+    let mut buf = [0u8; 13];
+    x.fill(&mut buf);
+    assert!(buf.iter().any(|&byte| byte != 0));
+    println!("getrandom_test passed!");
+    0
+}
@@ -0,0 +1,51 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use user_lib::{brk, sbrk, PAGE_SIZE};
+
+/// Several times the static heap of user_lib
+const BIG_LEN: usize = 256 * 1024;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // the break only moves when asked to
+    let start = sbrk(0);
+    assert!(start > 0);
+    assert_eq!(brk(0), start);
+
+    // a new page above the break is usable and can be given back
+    assert_eq!(sbrk(PAGE_SIZE as isize), start);
+    assert_eq!(sbrk(0), start + PAGE_SIZE as isize);
+    let page = unsafe { core::slice::from_raw_parts_mut(start as *mut u8, PAGE_SIZE) };
+    for (i, b) in page.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    assert!(page.iter().enumerate().all(|(i, b)| *b == i as u8));
+    assert_eq!(sbrk(-(PAGE_SIZE as isize)), start + PAGE_SIZE as isize);
+    assert_eq!(sbrk(0), start);
+
+    // the heap grows beyond its static buffer
+    let mut big: Vec<u8> = Vec::with_capacity(BIG_LEN);
+    for i in 0..BIG_LEN {
+        big.push(i as u8);
+    }
+    assert!(sbrk(0) >= start + BIG_LEN as isize);
+    assert!(big.iter().enumerate().all(|(i, b)| *b == i as u8));
+
+    // many small allocations share the grown memory
+    let chunks: Vec<Vec<usize>> = (0..64).map(|i| alloc::vec![i; 512]).collect();
+    assert!(chunks
+        .iter()
+        .enumerate()
+        .all(|(i, c)| c.iter().all(|x| *x == i)));
+    drop(big);
+    drop(chunks);
+    println!("heap_grow_test passed!");
+    0
+}
@@ -0,0 +1,148 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use user_lib::net::write_all;
+use user_lib::{
+    accept, bind, close, listen, open, read, read_dir, socket, OpenFlags, SockAddrIn, AF_INET,
+    SOCK_STREAM,
+};
+
+const DEFAULT_PORT: u16 = 80;
+const BACKLOG: usize = 4;
+/// Requests longer than this are refused
+const MAX_REQUEST: usize = 2048;
+
+/// Send the status line and headers of a response
+fn send_head(fd: usize, status: &str, content_type: &str) {
+    let head = format!(
+        "HTTP/1.0 {}\r\nContent-Type: {}\r\nConnection: close\r\n\r\n",
+        status, content_type
+    );
+    write_all(fd, head.as_bytes());
+}
+
+/// Send a whole response with a plain text body
+fn send_error(fd: usize, status: &str) -> &str {
+    send_head(fd, status, "text/plain");
+    write_all(fd, status.as_bytes());
+    write_all(fd, b"\n");
+    status
+}
+
+/// Send a page linking every file in the root directory
+fn send_index(fd: usize) -> &'static str {
+    let Some(mut names) = read_dir("/\0") else {
+        return send_error(fd, "500 Internal Server Error");
+    };
+    names.sort();
+    send_head(fd, "200 OK", "text/html");
+    write_all(fd, b"<html><body><h1>Files</h1><ul>\n");
+    for name in names.iter() {
+        write_all(
+            fd,
+            format!("<li><a href=\"/{0}\">{0}</a></li>\n", name).as_bytes(),
+        );
+    }
+    write_all(fd, b"</ul></body></html>\n");
+    "200 OK"
+}
+
+/// Send the content of a file under the root directory
+fn send_file(fd: usize, name: &str) -> &'static str {
+    if name.contains("..") {
+        return send_error(fd, "403 Forbidden");
+    }
+    let mut path = String::from(name);
+    path.push('\0');
+    let file = open(path.as_str(), OpenFlags::RDONLY);
+    if file < 0 {
+        return send_error(fd, "404 Not Found");
+    }
+    let file = file as usize;
+    send_head(fd, "200 OK", "application/octet-stream");
+    let mut buffer = [0u8; 512];
+    loop {
+        let len = read(file, &mut buffer);
+        if len <= 0 || !write_all(fd, &buffer[..len as usize]) {
+            break;
+        }
+    }
+    close(file);
+    "200 OK"
+}
+
+/// Read one request and answer it, return the request line and status
+fn handle(fd: usize) -> Option<(String, &'static str)> {
+    let mut request = [0u8; MAX_REQUEST];
+    let mut len = 0;
+    while !request[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+        if len == MAX_REQUEST {
+            return Some((String::from("?"), send_error(fd, "413 Payload Too Large")));
+        }
+        let n = read(fd, &mut request[len..]);
+        if n <= 0 {
+            return None;
+        }
+        len += n as usize;
+    }
+    let request = core::str::from_utf8(&request[..len]).ok()?;
+    let line = request.lines().next()?;
+    let mut parts = line.split(' ');
+    let status = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/")) => send_index(fd),
+        (Some("GET"), Some(path)) if path.starts_with('/') => send_file(fd, &path[1..]),
+        (Some("GET"), _) => send_error(fd, "400 Bad Request"),
+        _ => send_error(fd, "405 Method Not Allowed"),
+    };
+    Some((String::from(line), status))
+}
+
+/// Usage: httpd [PORT]
+///
+/// Serve the files in the root directory over HTTP/1.0, one connection at a time
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let port = if argc > 1 {
+        match argv[1].parse() {
+            Ok(port) => port,
+            Err(_) => {
+                println!("httpd: invalid port {}", argv[1]);
+                return -1;
+            }
+        }
+    } else {
+        DEFAULT_PORT
+    };
+    let fd = socket(AF_INET, SOCK_STREAM, 0);
+    if fd < 0 {
+        println!("httpd: cannot create a socket");
+        return -1;
+    }
+    let fd = fd as usize;
+    if bind(fd, &SockAddrIn::new([0, 0, 0, 0], port)) != 0 || listen(fd, BACKLOG) != 0 {
+        println!("httpd: cannot listen on port {}", port);
+        return -1;
+    }
+    println!("httpd: serving on port {}", port);
+    loop {
+        let mut peer = SockAddrIn::default();
+        let conn = accept(fd, Some(&mut peer));
+        if conn < 0 {
+            println!("httpd: accept failed");
+            return -1;
+        }
+        let conn = conn as usize;
+        if let Some((line, status)) = handle(conn) {
+            let [a, b, c, d] = peer.addr;
+            println!("{}.{}.{}.{} \"{}\" {}", a, b, c, d, line, status);
+        }
+        close(conn);
+    }
+}
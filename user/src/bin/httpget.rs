@@ -0,0 +1,101 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::vec::Vec;
+use user_lib::net::{resolve, tcp_connect, write_all};
+use user_lib::{close, read, write};
+
+const HTTP_PORT: u16 = 80;
+
+/// Split `http://HOST[:PORT][/PATH]` into host, port and path
+fn parse_url(url: &str) -> Option<(&str, u16, &str)> {
+    let rest = url.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (authority, HTTP_PORT),
+    };
+    if host.is_empty() {
+        return None;
+    }
+    Some((host, port, path))
+}
+
+/// Usage: httpget URL
+///
+/// Fetch a page over HTTP/1.0 and print its body, fail unless the status is 2xx
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc != 2 {
+        println!("usage: httpget http://HOST[:PORT]/PATH");
+        return -1;
+    }
+    let Some((host, port, path)) = parse_url(argv[1]) else {
+        println!("httpget: invalid url {}", argv[1]);
+        return -1;
+    };
+    let Some(addr) = resolve(host) else {
+        println!("httpget: cannot resolve {}", host);
+        return -1;
+    };
+    let Some(fd) = tcp_connect(addr, port) else {
+        println!("httpget: cannot connect to {}:{}", host, port);
+        return -1;
+    };
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, host
+    );
+    if !write_all(fd, request.as_bytes()) {
+        println!("httpget: cannot send the request");
+        close(fd);
+        return -1;
+    }
+    // keep the response until the end of the headers, then stream the body
+    let mut head: Vec<u8> = Vec::new();
+    let mut status = None;
+    let mut buffer = [0u8; 512];
+    loop {
+        let len = read(fd, &mut buffer);
+        if len <= 0 {
+            break;
+        }
+        let data = &buffer[..len as usize];
+        if status.is_some() {
+            write(1, data);
+            continue;
+        }
+        head.extend_from_slice(data);
+        let Some(end) = head.windows(4).position(|w| w == b"\r\n\r\n") else {
+            continue;
+        };
+        // `HTTP/1.0 200 OK`
+        status = core::str::from_utf8(&head[..end])
+            .ok()
+            .and_then(|head| head.split(' ').nth(1))
+            .and_then(|code| code.parse::<u32>().ok())
+            .or(Some(0));
+        write(1, &head[end + 4..]);
+    }
+    close(fd);
+    match status {
+        Some(code) if (200..300).contains(&code) => 0,
+        Some(code) => {
+            println!("httpget: server answered {}", code);
+            -1
+        }
+        None => {
+            println!("httpget: incomplete response");
+            -1
+        }
+    }
+}
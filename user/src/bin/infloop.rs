@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::hint::black_box;
+use user_lib::getpid;
+
+/// Spin forever without a system call, only a signal typed on the console,
+/// Ctrl-C or Ctrl-Z, can end or stop it
+#[no_mangle]
+pub fn main() -> i32 {
+    println!(
+        "infloop: process {} spins, press Ctrl-C to kill it or Ctrl-Z to stop it",
+        getpid()
+    );
+    let mut x = 0usize;
+    loop {
+        x = black_box(x.wrapping_add(1));
+    }
+}
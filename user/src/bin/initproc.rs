@@ -3,18 +3,24 @@
 
 extern crate user_lib;
 
-use user_lib::{exec, fork, wait, yield_};
+use user_lib::{exec, fork, ioctl, setsid, sleep, wait, TIOCSCTTY};
 
 #[no_mangle]
 fn main() -> i32 {
     if fork() == 0 {
+        // the shell leads a session owning the console, so keys like Ctrl-C
+        // signal the group it puts in the foreground
+        setsid();
+        ioctl(0, TIOCSCTTY, 0);
         exec("user_shell\0", &[core::ptr::null::<u8>()]);
     } else {
         loop {
             let mut exit_code: i32 = 0;
+            // sleeps until a child or an orphan handed to initproc exits
             let pid = wait(&mut exit_code);
             if pid == -1 {
-                yield_();
+                // nothing left to reap
+                sleep(1000);
                 continue;
             }
             /*
@@ -0,0 +1,25 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::arch::asm;
+
+/// `li a0, 42` and `ret`
+const CODE: [u32; 2] = [0x02a0_0513, 0x0000_8067];
+
+/// In the writable data segment, which must not be executable
+static mut BUFFER: [u32; 2] = [0; 2];
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("It should trigger segmentation fault!");
+    let buffer = unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) };
+    buffer.copy_from_slice(&CODE);
+    let injected: extern "C" fn() -> i32 = unsafe { core::mem::transmute(buffer.as_ptr()) };
+    // make the stores visible to instruction fetches, so only the page
+    // permissions can stop the injected code
+    unsafe { asm!("fence.i") };
+    injected()
+}
@@ -0,0 +1,107 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::hint::black_box;
+use core::ptr::{addr_of, addr_of_mut};
+use user_lib::{
+    alarm, get_time, getitimer, setitimer, sigaction, sigreturn, ITimerVal, SignalAction,
+    SignalFlags, TimeSpec, ITIMER_REAL, ITIMER_VIRTUAL, SIGALRM, SIGVTALRM,
+};
+
+/// How long to compute at most while waiting for a signal
+const TIMEOUT_MS: isize = 5000;
+
+static mut ALARMS: usize = 0;
+static mut VIRTUAL_ALARMS: usize = 0;
+
+fn on_alarm() {
+    unsafe { addr_of_mut!(ALARMS).write_volatile(alarms() + 1) };
+    sigreturn();
+}
+
+fn on_virtual_alarm() {
+    unsafe { addr_of_mut!(VIRTUAL_ALARMS).write_volatile(virtual_alarms() + 1) };
+    sigreturn();
+}
+
+fn alarms() -> usize {
+    unsafe { addr_of!(ALARMS).read_volatile() }
+}
+
+fn virtual_alarms() -> usize {
+    unsafe { addr_of!(VIRTUAL_ALARMS).read_volatile() }
+}
+
+/// Compute until `done` returns true, which a signal handler must cause
+fn compute_until(done: impl Fn() -> bool) {
+    let start = get_time();
+    let mut t = 2usize;
+    while !done() {
+        assert!(get_time() - start < TIMEOUT_MS, "the timer did not expire");
+        for _ in 0..1000 {
+            t = black_box(t * t % 10007);
+        }
+    }
+}
+
+fn timer(interval_ms: usize, value_ms: usize) -> ITimerVal {
+    ITimerVal {
+        interval: TimeSpec::from_millis(interval_ms),
+        value: TimeSpec::from_millis(value_ms),
+    }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for (signum, handler) in [
+        (SIGALRM, on_alarm as fn() as usize),
+        (SIGVTALRM, on_virtual_alarm as fn() as usize),
+    ] {
+        let action = SignalAction {
+            handler,
+            mask: SignalFlags::empty(),
+        };
+        assert_eq!(sigaction(signum, Some(&action), None), 0);
+    }
+
+    // a one-shot timer interrupts the computation once
+    let start = get_time();
+    assert_eq!(setitimer(ITIMER_REAL, &timer(0, 100), None), 0);
+    compute_until(|| alarms() == 1);
+    assert!(get_time() - start >= 100);
+    let mut curr = ITimerVal::default();
+    assert_eq!(getitimer(ITIMER_REAL, &mut curr), 0);
+    assert_eq!(curr, ITimerVal::default());
+
+    // a periodic timer keeps firing until it is disarmed
+    assert_eq!(setitimer(ITIMER_REAL, &timer(50, 50), None), 0);
+    compute_until(|| alarms() == 4);
+    let mut old = ITimerVal::default();
+    assert_eq!(
+        setitimer(ITIMER_REAL, &ITimerVal::default(), Some(&mut old)),
+        0
+    );
+    assert_eq!(old.interval, TimeSpec::from_millis(50));
+    let fired = alarms();
+    let start = get_time();
+    compute_until(|| get_time() - start >= 200);
+    assert_eq!(alarms(), fired);
+
+    // alarm reports the time left of the previous one and can cancel it
+    assert_eq!(alarm(10), 0);
+    assert_eq!(alarm(0), 10);
+    assert_eq!(getitimer(ITIMER_REAL, &mut curr), 0);
+    assert_eq!(curr, ITimerVal::default());
+
+    // a virtual timer only counts time spent computing
+    assert_eq!(setitimer(ITIMER_VIRTUAL, &timer(0, 100), None), 0);
+    compute_until(|| virtual_alarms() == 1);
+    assert_eq!(alarms(), fired);
+
+    assert_eq!(setitimer(2, &timer(0, 100), None), -1);
+    println!("itimer_test passed!");
+    0
+}
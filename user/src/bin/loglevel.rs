@@ -0,0 +1,35 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{set_console_log_level, LOG_DEBUG, LOG_ERROR, LOG_INFO, LOG_TRACE, LOG_WARN};
+
+const LEVELS: [(&str, usize); 5] = [
+    ("error", LOG_ERROR),
+    ("warn", LOG_WARN),
+    ("info", LOG_INFO),
+    ("debug", LOG_DEBUG),
+    ("trace", LOG_TRACE),
+];
+
+/// Change which kernel log lines are printed on the console
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let level = match LEVELS
+        .iter()
+        .find(|(name, _)| argc == 2 && *name == argv[1])
+    {
+        Some(&(_, level)) => level,
+        None => {
+            println!("usage: loglevel error|warn|info|debug|trace");
+            return -1;
+        }
+    };
+    if set_console_log_level(level) != 0 {
+        println!("loglevel: cannot change the level");
+        return -1;
+    }
+    0
+}
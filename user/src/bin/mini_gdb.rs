@@ -0,0 +1,324 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use user_lib::readline::Readline;
+use user_lib::{
+    execvp, exit, fork, ptrace, ptrace_getregs, ptrace_peek, ptrace_poke, ptrace_setregs,
+    waitpid_options, UserRegs, WaitStatus, PTRACE_ATTACH, PTRACE_CONT, PTRACE_DETACH, PTRACE_KILL,
+    PTRACE_SINGLESTEP, PTRACE_TRACEME, SIGSTOP, SIGTRAP,
+};
+
+/// `c.ebreak`, short enough to replace any instruction
+const EBREAK: u16 = 0x9002;
+/// Words shown by `x` when no count is given
+const DEFAULT_WORDS: usize = 4;
+
+const REG_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+const HELP: &str = "\
+break ADDR       (b)  stop when ADDR is reached
+delete ADDR      (d)  remove the breakpoint at ADDR
+continue         (c)  run until a breakpoint, a signal or the exit
+stepi            (si) run one instruction
+regs             (r)  show all registers
+print REG        (p)  show a register, like a0 or pc
+set REG VALUE         change a register
+x ADDR [N]            show N words of memory from ADDR
+poke ADDR VALUE       change the word at ADDR
+quit             (q)  kill the program, or detach from an attached one";
+
+/// Parse a number written in hex with `0x` or in decimal
+fn parse_number(s: &str) -> Option<usize> {
+    match s.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// Number of a register in `UserRegs::x`, `None` for `pc` or an unknown name
+fn reg_number(name: &str) -> Option<usize> {
+    REG_NAMES.iter().position(|reg| *reg == name).or_else(|| {
+        let n: usize = name.strip_prefix('x')?.parse().ok()?;
+        (n < 32).then_some(n)
+    })
+}
+
+struct Debugger {
+    pid: usize,
+    /// whether the tracee was attached to instead of started
+    attached: bool,
+    /// the original instruction halfword at every breakpoint
+    breakpoints: BTreeMap<usize, u16>,
+    /// signal which stopped the tracee, delivered when it is resumed
+    pending_signal: usize,
+}
+
+impl Debugger {
+    fn wait(&self) -> WaitStatus {
+        let mut status = WaitStatus::Exited(0);
+        waitpid_options(self.pid as isize, &mut status, 0);
+        status
+    }
+    fn regs(&self) -> UserRegs {
+        let mut regs = UserRegs::default();
+        ptrace_getregs(self.pid, &mut regs);
+        regs
+    }
+    /// Read the halfword at an even address, within one word of memory
+    fn read_half(&self, addr: usize) -> Option<u16> {
+        let word = ptrace_peek(self.pid, addr & !7)?;
+        Some((word >> ((addr & 7) * 8)) as u16)
+    }
+    fn write_half(&self, addr: usize, half: u16) -> bool {
+        let shift = (addr & 7) * 8;
+        match ptrace_peek(self.pid, addr & !7) {
+            Some(word) => {
+                let word = word & !(0xffff << shift) | (half as usize) << shift;
+                ptrace_poke(self.pid, addr & !7, word) == 0
+            }
+            None => false,
+        }
+    }
+    fn set_breakpoint(&mut self, addr: usize) -> Result<(), &'static str> {
+        if addr & 1 != 0 {
+            return Err("instructions are at even addresses");
+        }
+        if self.breakpoints.contains_key(&addr) {
+            return Err("there is already a breakpoint there");
+        }
+        let original = self.read_half(addr).ok_or("cannot read the address")?;
+        if !self.write_half(addr, EBREAK) {
+            return Err("cannot write the address");
+        }
+        self.breakpoints.insert(addr, original);
+        Ok(())
+    }
+    fn delete_breakpoint(&mut self, addr: usize) -> Result<(), &'static str> {
+        let original = self
+            .breakpoints
+            .remove(&addr)
+            .ok_or("no breakpoint there")?;
+        self.write_half(addr, original);
+        Ok(())
+    }
+    /// Resume with `PTRACE_CONT` or `PTRACE_SINGLESTEP`, a breakpoint at the
+    /// pc is stepped over with its original instruction first
+    fn resume(&mut self, request: usize) -> WaitStatus {
+        let signal = core::mem::take(&mut self.pending_signal);
+        let pc = self.regs().pc;
+        if let Some(&original) = self.breakpoints.get(&pc) {
+            self.write_half(pc, original);
+            ptrace(PTRACE_SINGLESTEP, self.pid, 0, signal);
+            let status = self.wait();
+            if let WaitStatus::Exited(_) = status {
+                return status;
+            }
+            self.write_half(pc, EBREAK);
+            if request == PTRACE_SINGLESTEP || status != WaitStatus::Stopped(SIGTRAP) {
+                return status;
+            }
+            ptrace(request, self.pid, 0, 0);
+        } else {
+            ptrace(request, self.pid, 0, signal);
+        }
+        self.wait()
+    }
+    /// Show why the tracee stopped, return false if it has exited
+    fn report(&mut self, status: WaitStatus) -> bool {
+        match status {
+            WaitStatus::Exited(code) => {
+                println!("process {} exited with code {}", self.pid, code);
+                false
+            }
+            WaitStatus::Stopped(signal) => {
+                let pc = self.regs().pc;
+                if signal == SIGTRAP && self.breakpoints.contains_key(&pc) {
+                    println!("breakpoint at {:#x}", pc);
+                } else if signal == SIGTRAP {
+                    println!("stopped at {:#x}", pc);
+                } else {
+                    println!("stopped by signal {} at {:#x}", signal, pc);
+                    // SIGSTOP of an attach is not passed on
+                    if signal != SIGSTOP {
+                        self.pending_signal = signal as usize;
+                    }
+                }
+                true
+            }
+        }
+    }
+    fn show_regs(&self) {
+        let regs = self.regs();
+        println!("pc   {:#018x}", regs.pc);
+        for (i, name) in REG_NAMES.iter().enumerate().skip(1) {
+            print!("{:<4} {:#018x}", name, regs.x[i]);
+            print!("{}", if i % 3 == 0 { "\n" } else { "    " });
+        }
+        println!("");
+    }
+    /// Run a command, return false when the debugger should quit
+    fn run_command(&mut self, words: &[&str]) -> bool {
+        let number = |i: usize| words.get(i).and_then(|w| parse_number(w));
+        match (words[0], words.len()) {
+            ("b" | "break", 2) | ("d" | "delete", 2) => {
+                let Some(addr) = number(1) else {
+                    println!("not an address: {}", words[1]);
+                    return true;
+                };
+                let result = if words[0].starts_with('b') {
+                    self.set_breakpoint(addr)
+                } else {
+                    self.delete_breakpoint(addr)
+                };
+                if let Err(message) = result {
+                    println!("{}", message);
+                }
+            }
+            ("c" | "continue", 1) => {
+                let status = self.resume(PTRACE_CONT);
+                return self.report(status);
+            }
+            ("si" | "stepi", 1) => {
+                let status = self.resume(PTRACE_SINGLESTEP);
+                return self.report(status);
+            }
+            ("r" | "regs", 1) => self.show_regs(),
+            ("p" | "print", 2) => {
+                let regs = self.regs();
+                match (words[1], reg_number(words[1])) {
+                    ("pc", _) => println!("pc = {:#x}", regs.pc),
+                    (name, Some(n)) => println!("{} = {:#x}", name, regs.x[n]),
+                    (name, None) => println!("no register {}", name),
+                }
+            }
+            ("set", 3) => {
+                let mut regs = self.regs();
+                let Some(value) = number(2) else {
+                    println!("not a number: {}", words[2]);
+                    return true;
+                };
+                match (words[1], reg_number(words[1])) {
+                    ("pc", _) => regs.pc = value,
+                    ("zero" | "x0", _) => println!("zero can not be changed"),
+                    (_, Some(n)) => regs.x[n] = value,
+                    (name, None) => println!("no register {}", name),
+                }
+                ptrace_setregs(self.pid, &regs);
+            }
+            ("x", 2 | 3) => {
+                let (Some(addr), count) = (number(1), number(2).unwrap_or(DEFAULT_WORDS)) else {
+                    println!("not an address: {}", words[1]);
+                    return true;
+                };
+                for i in 0..count {
+                    let addr = (addr & !7) + i * 8;
+                    match ptrace_peek(self.pid, addr) {
+                        Some(word) => println!("{:#x}:  {:#018x}", addr, word),
+                        None => {
+                            println!("{:#x}:  cannot read", addr);
+                            break;
+                        }
+                    }
+                }
+            }
+            ("poke", 3) => match (number(1), number(2)) {
+                (Some(addr), Some(value)) if ptrace_poke(self.pid, addr, value) == 0 => {}
+                _ => println!("cannot write {} at {}", words[2], words[1]),
+            },
+            ("q" | "quit", 1) => {
+                if self.attached {
+                    let addrs: Vec<usize> = self.breakpoints.keys().copied().collect();
+                    for addr in addrs {
+                        let _ = self.delete_breakpoint(addr);
+                    }
+                    ptrace(PTRACE_DETACH, self.pid, 0, 0);
+                } else {
+                    ptrace(PTRACE_KILL, self.pid, 0, 0);
+                    self.wait();
+                }
+                return false;
+            }
+            ("help", 1) => println!("{}", HELP),
+            _ => println!("unknown command, try help"),
+        }
+        true
+    }
+}
+
+/// Start `argv[1..]` traced, stopped before its first instruction
+fn start(argv: &[&str]) -> Option<usize> {
+    let args: Vec<String> = argv
+        .iter()
+        .map(|arg| {
+            let mut arg = String::from(*arg);
+            arg.push('\0');
+            arg
+        })
+        .collect();
+    let mut args_addr: Vec<*const u8> = args.iter().map(|arg| arg.as_ptr()).collect();
+    args_addr.push(core::ptr::null::<u8>());
+    let pid = fork();
+    if pid == 0 {
+        ptrace(PTRACE_TRACEME, 0, 0, 0);
+        execvp(args[0].as_str(), args_addr.as_slice());
+        println!("mini_gdb: cannot execute {}", argv[0]);
+        exit(-1);
+    }
+    Some(pid as usize)
+}
+
+/// Debug a program with breakpoints, single steps and access to its
+/// registers and memory: `mini_gdb PROGRAM [ARGS]` or `mini_gdb -p PID`
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let (pid, attached) = match argc {
+        3 if argv[1] == "-p" => match parse_number(argv[2]) {
+            Some(pid) if ptrace(PTRACE_ATTACH, pid, 0, 0) == 0 => (pid, true),
+            _ => {
+                println!("mini_gdb: cannot attach to {}", argv[2]);
+                return -1;
+            }
+        },
+        2.. => match start(&argv[1..argc]) {
+            Some(pid) => (pid, false),
+            None => return -1,
+        },
+        _ => {
+            println!("usage: mini_gdb PROGRAM [ARGS] | mini_gdb -p PID");
+            return -1;
+        }
+    };
+    let mut debugger = Debugger {
+        pid,
+        attached,
+        breakpoints: BTreeMap::new(),
+        pending_signal: 0,
+    };
+    let status = debugger.wait();
+    if !debugger.report(status) {
+        return -1;
+    }
+    let mut editor = Readline::new("(gdb) ");
+    loop {
+        let line = editor.read_line();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        editor.add_history(line.as_str());
+        if !debugger.run_command(&words) {
+            return 0;
+        }
+    }
+}
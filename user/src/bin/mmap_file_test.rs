@@ -0,0 +1,94 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, mmap_file, msync, munmap, open, read, write, MapFlags, OpenFlags, ProtFlags, PAGE_SIZE,
+};
+
+const PATH: &str = "mmap_file\0";
+const LEN: usize = 2 * PAGE_SIZE + 100;
+
+fn pattern(i: usize) -> u8 {
+    (i % 251) as u8
+}
+
+/// Read the whole test file
+fn read_file(buffer: &mut [u8]) -> usize {
+    let fd = open(PATH, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let mut len = 0;
+    loop {
+        let n = read(fd as usize, &mut buffer[len..]);
+        assert!(n >= 0);
+        if n == 0 {
+            break;
+        }
+        len += n as usize;
+    }
+    close(fd as usize);
+    len
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut content = [0u8; LEN];
+    for (i, b) in content.iter_mut().enumerate() {
+        *b = pattern(i);
+    }
+    let fd = open(
+        PATH,
+        OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC,
+    );
+    assert!(fd > 0);
+    assert_eq!(write(fd as usize, &content), LEN as isize);
+    close(fd as usize);
+
+    let fd = open(PATH, OpenFlags::RDWR);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+
+    // a private mapping shows the file, but its writes stay in the process
+    let addr = mmap_file(fd, 0, LEN, rw, MapFlags::PRIVATE);
+    assert!(addr > 0);
+    let mapped = unsafe { core::slice::from_raw_parts_mut(addr as *mut u8, LEN) };
+    assert_eq!(&mapped[..], &content[..]);
+    mapped[0] = 0xff;
+    assert_eq!(munmap(addr as usize, LEN), 0);
+    let mut file = [0u8; LEN + 1];
+    assert_eq!(read_file(&mut file), LEN);
+    assert_eq!(file[0], pattern(0));
+
+    // a mapping from the second page starts at that offset of the file
+    let addr = mmap_file(fd, PAGE_SIZE, PAGE_SIZE, ProtFlags::READ, MapFlags::SHARED);
+    assert!(addr > 0);
+    let mapped = unsafe { core::slice::from_raw_parts(addr as *const u8, PAGE_SIZE) };
+    assert_eq!(mapped, &content[PAGE_SIZE..2 * PAGE_SIZE]);
+    assert_eq!(munmap(addr as usize, PAGE_SIZE), 0);
+    // the offset must be page-aligned
+    assert_eq!(
+        mmap_file(fd, 1, PAGE_SIZE, ProtFlags::READ, MapFlags::SHARED),
+        -1
+    );
+
+    // writes to a shared mapping reach the file on msync and on munmap
+    let addr = mmap_file(fd, 0, LEN, rw, MapFlags::SHARED);
+    assert!(addr > 0);
+    let mapped = unsafe { core::slice::from_raw_parts_mut(addr as *mut u8, LEN) };
+    mapped[1] = 0xee;
+    assert_eq!(msync(addr as usize, LEN), 0);
+    assert_eq!(read_file(&mut file), LEN);
+    assert_eq!(file[1], 0xee);
+    mapped[LEN - 1] = 0xdd;
+    assert_eq!(munmap(addr as usize, LEN), 0);
+    // the file does not grow to the end of the last page
+    assert_eq!(read_file(&mut file), LEN);
+    assert_eq!(file[LEN - 1], 0xdd);
+    assert_eq!(&file[2..LEN - 1], &content[2..LEN - 1]);
+    close(fd);
+    println!("mmap_file_test passed!");
+    0
+}
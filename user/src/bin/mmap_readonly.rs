@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap_anonymous, ProtFlags, PAGE_SIZE};
+
+const START: usize = 0x1000_0000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("It should trigger segmentation fault!");
+    assert_eq!(
+        mmap_anonymous(START, PAGE_SIZE, ProtFlags::READ),
+        START as isize
+    );
+    assert_eq!(unsafe { (START as *const u8).read_volatile() }, 0);
+    // writing a read-only mapping faults
+    unsafe { (START as *mut u8).write_volatile(1) };
+    0
+}
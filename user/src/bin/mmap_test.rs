@@ -0,0 +1,67 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap_anonymous, munmap, ProtFlags, PAGE_SIZE};
+
+const START: usize = 0x1000_0000;
+/// Larger than the physical memory, only works if pages are given frames lazily
+const LAZY_LEN: usize = 256 << 20;
+
+fn bytes(addr: usize, len: usize) -> &'static mut [u8] {
+    unsafe { core::slice::from_raw_parts_mut(addr as *mut u8, len) }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+
+    // new pages are zeroed and keep what is written
+    assert_eq!(mmap_anonymous(START, 3 * PAGE_SIZE, rw), START as isize);
+    let pages = bytes(START, 3 * PAGE_SIZE);
+    assert!(pages.iter().all(|b| *b == 0));
+    for (i, b) in pages.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    assert!(pages.iter().enumerate().all(|(i, b)| *b == i as u8));
+
+    // misaligned, empty and overlapping mappings are refused
+    assert_eq!(mmap_anonymous(START + 1, PAGE_SIZE, rw), -1);
+    assert_eq!(mmap_anonymous(START + 4 * PAGE_SIZE, 0, rw), -1);
+    assert_eq!(mmap_anonymous(START + 2 * PAGE_SIZE, 2 * PAGE_SIZE, rw), -1);
+
+    // an unmapped page leaves a hole which can be mapped again
+    assert_eq!(munmap(START + PAGE_SIZE, PAGE_SIZE), 0);
+    assert_eq!(munmap(START + PAGE_SIZE, PAGE_SIZE), -1);
+    assert_eq!(
+        mmap_anonymous(START + PAGE_SIZE, PAGE_SIZE, rw),
+        (START + PAGE_SIZE) as isize
+    );
+    let pages = bytes(START, 3 * PAGE_SIZE);
+    for (i, b) in pages.iter().enumerate() {
+        let in_hole = (PAGE_SIZE..2 * PAGE_SIZE).contains(&i);
+        assert_eq!(*b, if in_hole { 0 } else { i as u8 });
+    }
+    assert_eq!(munmap(START, 3 * PAGE_SIZE), 0);
+
+    // the kernel picks a page-aligned address when none is given
+    let addr = mmap_anonymous(0, PAGE_SIZE, rw);
+    assert!(addr > 0 && addr as usize % PAGE_SIZE == 0);
+    bytes(addr as usize, PAGE_SIZE)[PAGE_SIZE - 1] = 0x5a;
+    assert_eq!(munmap(addr as usize, PAGE_SIZE), 0);
+
+    // only the pages touched get frames
+    let addr = mmap_anonymous(0, LAZY_LEN, rw);
+    assert!(addr > 0);
+    let big = bytes(addr as usize, LAZY_LEN);
+    for i in [0, LAZY_LEN / 2, LAZY_LEN - 1] {
+        assert_eq!(big[i], 0);
+        big[i] = 0xa5;
+        assert_eq!(big[i], 0xa5);
+    }
+    assert_eq!(munmap(addr as usize, LAZY_LEN), 0);
+    println!("mmap_test passed!");
+    0
+}
@@ -0,0 +1,23 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap_anonymous, munmap, ProtFlags, PAGE_SIZE};
+
+const START: usize = 0x1000_0000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("It should trigger segmentation fault!");
+    assert_eq!(
+        mmap_anonymous(START, PAGE_SIZE, ProtFlags::READ | ProtFlags::WRITE),
+        START as isize
+    );
+    unsafe { (START as *mut u8).write_volatile(1) };
+    assert_eq!(munmap(START, PAGE_SIZE), 0);
+    // the page is gone after munmap
+    unsafe { (START as *mut u8).write_volatile(2) };
+    0
+}
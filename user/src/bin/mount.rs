@@ -0,0 +1,65 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use user_lib::{mount, MountFlags};
+
+/// Filesystem type used when none is given
+const DEFAULT_FSTYPE: &str = "easy-fs";
+
+fn usage() -> i32 {
+    println!("usage: mount [-o ro,noexec] [--bind] SOURCE TARGET [FSTYPE]");
+    -1
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let mut flags = MountFlags::empty();
+    let mut operands: Vec<&str> = Vec::new();
+    let mut i = 1;
+    while i < argc {
+        match argv[i] {
+            "--bind" => flags |= MountFlags::BIND,
+            "-o" => {
+                i += 1;
+                if i == argc {
+                    return usage();
+                }
+                for option in argv[i].split(',') {
+                    match option {
+                        "ro" => flags |= MountFlags::RDONLY,
+                        "rw" => flags.remove(MountFlags::RDONLY),
+                        "noexec" => flags |= MountFlags::NOEXEC,
+                        "exec" => flags.remove(MountFlags::NOEXEC),
+                        "bind" => flags |= MountFlags::BIND,
+                        _ => {
+                            println!("mount: unknown option {}", option);
+                            return -1;
+                        }
+                    }
+                }
+            }
+            operand => operands.push(operand),
+        }
+        i += 1;
+    }
+    if operands.len() != 2 && operands.len() != 3 {
+        return usage();
+    }
+    let mut source = String::from(operands[0]);
+    source.push('\0');
+    let mut target = String::from(operands[1]);
+    target.push('\0');
+    let mut fstype = String::from(operands.get(2).copied().unwrap_or(DEFAULT_FSTYPE));
+    fstype.push('\0');
+    if mount(source.as_str(), target.as_str(), fstype.as_str(), flags) != 0 {
+        println!("mount: cannot mount {} on {}", operands[0], operands[1]);
+        return -1;
+    }
+    0
+}
@@ -0,0 +1,21 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap_anonymous, mprotect, ProtFlags, PAGE_SIZE};
+
+const START: usize = 0x1000_0000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("It should trigger segmentation fault!");
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    assert_eq!(mmap_anonymous(START, PAGE_SIZE, rw), START as isize);
+    unsafe { (START as *mut u8).write_volatile(1) };
+    assert_eq!(mprotect(START, PAGE_SIZE, ProtFlags::empty()), 0);
+    // a page without permissions faults even on reads
+    unsafe { (START as *const u8).read_volatile() };
+    0
+}
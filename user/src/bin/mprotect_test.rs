@@ -0,0 +1,59 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap_anonymous, mprotect, munmap, ProtFlags, PAGE_SIZE};
+
+const START: usize = 0x1000_0000;
+
+fn bytes(addr: usize, len: usize) -> &'static mut [u8] {
+    unsafe { core::slice::from_raw_parts_mut(addr as *mut u8, len) }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    assert_eq!(mmap_anonymous(START, 3 * PAGE_SIZE, rw), START as isize);
+    let pages = bytes(START, 3 * PAGE_SIZE);
+    for (i, b) in pages.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+
+    // a read-only page keeps its contents
+    assert_eq!(mprotect(START + PAGE_SIZE, PAGE_SIZE, ProtFlags::READ), 0);
+    assert!(pages.iter().enumerate().all(|(i, b)| *b == i as u8));
+    // the pages around it stay writable
+    pages[0] = 0xa5;
+    pages[3 * PAGE_SIZE - 1] = 0x5a;
+
+    // and it can be made writable again
+    assert_eq!(mprotect(START + PAGE_SIZE, PAGE_SIZE, rw), 0);
+    pages[PAGE_SIZE] = 0xff;
+    assert_eq!(pages[PAGE_SIZE], 0xff);
+
+    // misaligned, unmapped and partly mapped ranges are refused
+    assert_eq!(mprotect(START + 1, PAGE_SIZE, ProtFlags::READ), -1);
+    assert_eq!(
+        mprotect(START + 4 * PAGE_SIZE, PAGE_SIZE, ProtFlags::READ),
+        -1
+    );
+    assert_eq!(
+        mprotect(START + 2 * PAGE_SIZE, 2 * PAGE_SIZE, ProtFlags::READ),
+        -1
+    );
+    // so the last page is still writable
+    pages[2 * PAGE_SIZE] = 0x11;
+    assert_eq!(pages[2 * PAGE_SIZE], 0x11);
+
+    // a range split by mprotect is still unmapped as a whole
+    assert_eq!(
+        mprotect(START + PAGE_SIZE, PAGE_SIZE, ProtFlags::empty()),
+        0
+    );
+    assert_eq!(munmap(START, 3 * PAGE_SIZE), 0);
+    assert_eq!(mprotect(START, PAGE_SIZE, rw), -1);
+    println!("mprotect_test passed!");
+    0
+}
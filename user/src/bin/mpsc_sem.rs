@@ -0,0 +1,98 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec::Vec;
+use user_lib::{
+    exit, mutex_blocking_create, mutex_lock, mutex_unlock, semaphore_create, semaphore_down,
+    semaphore_up, thread_create, waittid,
+};
+
+const BUFFER_SIZE: usize = 8;
+const PRODUCER_COUNT: usize = 4;
+const NUMBER_PER_PRODUCER: usize = 100;
+
+/// A ring buffer guarded by `mutex`, `empty` counts its free slots and
+/// `full` the items in it
+struct Channel {
+    buffer: [usize; BUFFER_SIZE],
+    front: usize,
+    tail: usize,
+    mutex: usize,
+    empty: usize,
+    full: usize,
+}
+
+static mut CHANNEL: Channel = Channel {
+    buffer: [0; BUFFER_SIZE],
+    front: 0,
+    tail: 0,
+    mutex: 0,
+    empty: 0,
+    full: 0,
+};
+
+fn channel() -> &'static mut Channel {
+    unsafe { &mut *core::ptr::addr_of_mut!(CHANNEL) }
+}
+
+fn send(item: usize) {
+    let (empty, full, mutex) = (channel().empty, channel().full, channel().mutex);
+    semaphore_down(empty);
+    mutex_lock(mutex);
+    let ch = channel();
+    ch.buffer[ch.tail] = item;
+    ch.tail = (ch.tail + 1) % BUFFER_SIZE;
+    mutex_unlock(mutex);
+    semaphore_up(full);
+}
+
+fn receive() -> usize {
+    let (empty, full, mutex) = (channel().empty, channel().full, channel().mutex);
+    semaphore_down(full);
+    mutex_lock(mutex);
+    let ch = channel();
+    let item = ch.buffer[ch.front];
+    ch.front = (ch.front + 1) % BUFFER_SIZE;
+    mutex_unlock(mutex);
+    semaphore_up(empty);
+    item
+}
+
+fn producer(id: usize) -> ! {
+    for i in 0..NUMBER_PER_PRODUCER {
+        send(id * NUMBER_PER_PRODUCER + i);
+    }
+    exit(0);
+    unreachable!()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let ch = channel();
+    ch.mutex = mutex_blocking_create() as usize;
+    ch.empty = semaphore_create(BUFFER_SIZE) as usize;
+    ch.full = semaphore_create(0) as usize;
+    let producers: Vec<isize> = (0..PRODUCER_COUNT)
+        .map(|id| thread_create(producer as fn(usize) -> ! as usize, id))
+        .collect();
+    // every item arrives once, and the items of each producer in order
+    let mut received = [false; PRODUCER_COUNT * NUMBER_PER_PRODUCER];
+    let mut next = [0; PRODUCER_COUNT];
+    for _ in 0..received.len() {
+        let item = receive();
+        assert!(!received[item]);
+        received[item] = true;
+        let id = item / NUMBER_PER_PRODUCER;
+        assert_eq!(item % NUMBER_PER_PRODUCER, next[id]);
+        next[id] += 1;
+    }
+    for tid in producers {
+        assert_eq!(waittid(tid as usize), 0);
+    }
+    println!("mpsc_sem test passed!");
+    0
+}
@@ -0,0 +1,74 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, exit, fork, mq_open, mq_receive, mq_send, mq_unlink, waitpid, MqAttr, OpenFlags, EAGAIN,
+};
+
+const NAME: &str = "/mq_test\0";
+const ATTR: MqAttr = MqAttr {
+    max_msgs: 4,
+    msg_size: 16,
+};
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // left by an earlier run
+    mq_unlink(NAME);
+    let mqd = mq_open(
+        NAME,
+        OpenFlags::CREATE | OpenFlags::RDWR | OpenFlags::NONBLOCK,
+        Some(&ATTR),
+    );
+    assert!(mqd > 0);
+    let mqd = mqd as usize;
+    let mut buffer = [0u8; 16];
+    assert_eq!(mq_receive(mqd, &mut buffer, None), -EAGAIN);
+
+    // messages come out by priority, then in the order sent
+    for (msg, priority) in [(b"low1", 1), (b"high", 5), (b"low2", 1), (b"mid!", 3)] {
+        assert_eq!(mq_send(mqd, msg, priority), 0);
+    }
+    // the queue is full and a message may not be longer than msg_size
+    assert_eq!(mq_send(mqd, b"more", 1), -EAGAIN);
+    assert!(mq_send(mqd, &[0u8; 17], 1) < 0);
+    let mut priority = 0;
+    for (msg, expected) in [(b"high", 5), (b"mid!", 3), (b"low1", 1), (b"low2", 1)] {
+        assert_eq!(mq_receive(mqd, &mut buffer, Some(&mut priority)), 4);
+        assert_eq!(&buffer[..4], msg);
+        assert_eq!(priority, expected);
+    }
+    // a buffer shorter than msg_size is refused
+    assert!(mq_receive(mqd, &mut buffer[..8], None) < 0);
+
+    // a blocking receive waits for a process which opens the queue by name
+    let blocking = mq_open(NAME, OpenFlags::RDONLY, None);
+    assert!(blocking > 0);
+    let blocking = blocking as usize;
+    let pid = fork();
+    if pid == 0 {
+        let mqd = mq_open(NAME, OpenFlags::WRONLY, None);
+        assert!(mqd > 0);
+        for i in 0..10u8 {
+            assert_eq!(mq_send(mqd as usize, &[i; 8], 0), 0);
+        }
+        exit(0);
+    }
+    for i in 0..10u8 {
+        assert_eq!(mq_receive(blocking, &mut buffer, None), 8);
+        assert_eq!(&buffer[..8], &[i; 8]);
+    }
+    let mut exit_code: i32 = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+
+    close(blocking);
+    close(mqd);
+    assert_eq!(mq_unlink(NAME), 0);
+    assert!(mq_open(NAME, OpenFlags::RDONLY, None) < 0);
+    println!("mq_test passed!");
+    0
+}
@@ -0,0 +1,66 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    condvar_create, condvar_signal, condvar_timedwait, exit, get_time, mutex_blocking_create,
+    mutex_lock, mutex_unlock, nanosleep, sleep, thread_create, waittid, TimeSpec, ETIMEDOUT,
+};
+
+static mut MUTEX: usize = 0;
+static mut CONDVAR: usize = 0;
+
+fn signaller() -> ! {
+    sleep(50);
+    let (mutex, condvar) = unsafe { (MUTEX, CONDVAR) };
+    mutex_lock(mutex);
+    condvar_signal(condvar);
+    mutex_unlock(mutex);
+    exit(0);
+    unreachable!()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // sleeps last at least as long as asked
+    let start = get_time();
+    let req = TimeSpec {
+        sec: 0,
+        nsec: 200_000_000,
+    };
+    assert_eq!(nanosleep(&req, None), 0);
+    assert!(get_time() - start >= 200);
+    let start = get_time();
+    sleep(1100);
+    assert!(get_time() - start >= 1100);
+    assert_eq!(nanosleep(&TimeSpec::default(), None), 0);
+    let bad = TimeSpec {
+        sec: 0,
+        nsec: TimeSpec::NSEC_PER_SEC,
+    };
+    assert_eq!(nanosleep(&bad, None), -1);
+
+    // a timed wait without a signal times out holding the mutex
+    let mutex = mutex_blocking_create() as usize;
+    let condvar = condvar_create() as usize;
+    unsafe {
+        MUTEX = mutex;
+        CONDVAR = condvar;
+    }
+    mutex_lock(mutex);
+    let start = get_time();
+    assert_eq!(condvar_timedwait(condvar, mutex, 100), -ETIMEDOUT);
+    assert!(get_time() - start >= 100);
+    mutex_unlock(mutex);
+
+    // and returns early when signalled
+    mutex_lock(mutex);
+    let tid = thread_create(signaller as fn() -> ! as usize, 0);
+    assert_eq!(condvar_timedwait(condvar, mutex, 10_000), 0);
+    mutex_unlock(mutex);
+    assert_eq!(waittid(tid as usize), 0);
+    println!("nanosleep_test passed!");
+    0
+}
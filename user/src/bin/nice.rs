@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use user_lib::{execvp, getnice, setnice, NICE_MAX, NICE_MIN};
+
+/// Increment used when no `-n` is given
+const DEFAULT_ADJUSTMENT: isize = 10;
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let current = getnice(0).unwrap_or(0);
+    if argc < 2 {
+        // like the standard nice, print the current value
+        println!("{}", current);
+        return 0;
+    }
+    let (adjustment, args) = if argv[1] == "-n" {
+        match argv.get(2).and_then(|n| n.parse::<isize>().ok()) {
+            Some(n) => (n, &argv[3..argc]),
+            None => {
+                println!("usage: nice [-n ADJUSTMENT] PROGRAM [ARGS...]");
+                return -1;
+            }
+        }
+    } else {
+        (DEFAULT_ADJUSTMENT, &argv[1..argc])
+    };
+    if args.is_empty() {
+        println!("usage: nice [-n ADJUSTMENT] PROGRAM [ARGS...]");
+        return -1;
+    }
+    let nice = (current + adjustment).clamp(NICE_MIN, NICE_MAX);
+    if setnice(0, nice) != 0 {
+        println!("nice: cannot set nice value {}", nice);
+        return -1;
+    }
+    let args_copy: Vec<String> = args
+        .iter()
+        .map(|arg| {
+            let mut arg = String::from(*arg);
+            arg.push('\0');
+            arg
+        })
+        .collect();
+    let mut args_addr: Vec<*const u8> = args_copy.iter().map(|arg| arg.as_ptr()).collect();
+    args_addr.push(core::ptr::null::<u8>());
+    execvp(args_copy[0].as_str(), args_addr.as_slice());
+    println!("nice: cannot execute {}", args[0]);
+    -1
+}
@@ -0,0 +1,47 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{exit, fork, getnice, getpid, setnice, waitpid, NICE_MAX, NICE_MIN};
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(getnice(0), Some(0));
+    assert_eq!(getnice(getpid() as usize), Some(0));
+
+    // the whole range is accepted, values outside it are refused
+    for nice in [NICE_MIN, -1, NICE_MAX, 5] {
+        assert_eq!(setnice(0, nice), 0);
+        assert_eq!(getnice(0), Some(nice));
+    }
+    assert_eq!(setnice(0, NICE_MIN - 1), -1);
+    assert_eq!(setnice(0, NICE_MAX + 1), -1);
+    assert_eq!(getnice(0), Some(5));
+
+    // children inherit the value
+    let pid = fork();
+    if pid == 0 {
+        exit(if getnice(0) == Some(5) { 0 } else { -1 });
+    }
+    let mut exit_code = -1;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+
+    // and can be changed by the parent
+    let pid = fork();
+    if pid == 0 {
+        while getnice(0) != Some(NICE_MAX) {}
+        exit(0);
+    }
+    assert_eq!(setnice(pid as usize, NICE_MAX), 0);
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+
+    // there is no such process any more
+    assert_eq!(getnice(pid as usize), None);
+    assert_eq!(setnice(pid as usize, 0), -1);
+    println!("nice_test passed!");
+    0
+}
@@ -0,0 +1,63 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::fb::{rgb, Framebuffer};
+use user_lib::input::*;
+
+const BRUSH_SIZE: usize = 4;
+/// Pixels moved by an arrow key
+const KEY_STEP: isize = 8;
+
+/// Draw with the mouse or the arrow keys: the left button or Space paints,
+/// the right button or C clears and Esc or Q quits
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut fb = match Framebuffer::open() {
+        Some(fb) => fb,
+        None => {
+            println!("paint: no framebuffer, run QEMU with a virtio-gpu device");
+            return -1;
+        }
+    };
+    let mut input = match InputDevice::open() {
+        Some(input) => input,
+        None => {
+            println!("paint: no input device, run QEMU with virtio keyboard and mouse");
+            return -1;
+        }
+    };
+    let background = rgb(255, 255, 255);
+    let brush = rgb(0, 0, 160);
+    fb.clear(background);
+    fb.flush();
+    let (mut x, mut y) = ((fb.width() / 2) as isize, (fb.height() / 2) as isize);
+    let mut painting = false;
+    while let Some(event) = input.read_event() {
+        let pressed = event.value != 0;
+        match (event.kind, event.code) {
+            (EV_REL, REL_X) => x += event.value as isize,
+            (EV_REL, REL_Y) => y += event.value as isize,
+            (EV_KEY, KEY_LEFT) if pressed => x -= KEY_STEP,
+            (EV_KEY, KEY_RIGHT) if pressed => x += KEY_STEP,
+            (EV_KEY, KEY_UP) if pressed => y -= KEY_STEP,
+            (EV_KEY, KEY_DOWN) if pressed => y += KEY_STEP,
+            (EV_KEY, BTN_LEFT | KEY_SPACE) => painting = pressed,
+            (EV_KEY, BTN_RIGHT | KEY_C) if pressed => fb.clear(background),
+            (EV_KEY, KEY_ESC | KEY_Q) if pressed => break,
+            // the screen is updated once per group of events
+            (EV_SYN, _) => {
+                if painting {
+                    fb.fill_rect(x as usize, y as usize, BRUSH_SIZE, BRUSH_SIZE, brush);
+                }
+                fb.flush();
+            }
+            _ => {}
+        }
+        x = x.clamp(0, fb.width() as isize - 1);
+        y = y.clamp(0, fb.height() as isize - 1);
+    }
+    0
+}
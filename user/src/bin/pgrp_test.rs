@@ -0,0 +1,81 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, exit, fork, getpgid, getpid, pipe, read, setpgid, setsid, tcgetpgrp, waitpid, write,
+};
+
+/// Fork a child running `f`, wait for it and return its exit code
+fn run_child(f: impl FnOnce() -> i32) -> i32 {
+    let pid = fork();
+    if pid == 0 {
+        exit(f());
+    }
+    let mut exit_code: i32 = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    exit_code
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let pid = getpid();
+    let pgid = getpgid(0);
+    assert!(pgid > 0);
+    assert_eq!(getpgid(pid as usize), pgid);
+
+    // a child starts in the group of its parent and can lead a new one
+    assert_eq!(
+        run_child(|| {
+            assert_ne!(getpgid(0), getpid());
+            assert_eq!(setpgid(0, 0), 0);
+            assert_eq!(getpgid(0), getpid());
+            // a group leader cannot start a session
+            assert_eq!(setsid(), -1);
+            0
+        }),
+        0
+    );
+    println!("setpgid ok");
+
+    // a new session has a new group and no controlling terminal
+    assert_eq!(
+        run_child(|| {
+            let pid = getpid();
+            assert_eq!(setsid(), pid);
+            assert_eq!(getpgid(0), pid);
+            assert_eq!(tcgetpgrp(0), -1);
+            // a group of another session cannot be joined
+            assert_eq!(setpgid(0, pgid as usize), -1);
+            0
+        }),
+        0
+    );
+    println!("setsid ok");
+
+    // the parent can move a child into a new group, the child waits on a
+    // pipe until it has been checked
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let leader = fork();
+    if leader == 0 {
+        close(pipe_fd[1]);
+        let mut buf = [0u8; 1];
+        read(pipe_fd[0], &mut buf);
+        exit(0);
+    }
+    close(pipe_fd[0]);
+    assert_eq!(setpgid(leader as usize, 0), 0);
+    assert_eq!(getpgid(leader as usize), leader);
+    assert_ne!(getpgid(0), leader);
+    write(pipe_fd[1], b"x");
+    close(pipe_fd[1]);
+    let mut exit_code: i32 = 0;
+    assert_eq!(waitpid(leader as usize, &mut exit_code), leader);
+    // no such process
+    assert_eq!(getpgid(leader as usize), -1);
+    println!("pgrp_test passed!");
+    0
+}
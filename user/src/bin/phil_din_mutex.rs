@@ -0,0 +1,58 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec::Vec;
+use user_lib::{exit, get_time, mutex_blocking_create, mutex_lock, mutex_unlock, sleep};
+use user_lib::{thread_create, waittid};
+
+const N: usize = 5;
+const ROUNDS: usize = 4;
+/// How long a philosopher thinks and eats in milliseconds
+const THINK_TIME: usize = 30;
+const EAT_TIME: usize = 20;
+
+/// The mutex of the fork between philosopher `i - 1` and `i`
+static mut FORKS: [usize; N] = [0; N];
+static mut MEALS: [usize; N] = [0; N];
+
+fn philosopher(id: usize) -> ! {
+    let left = id;
+    let right = (id + 1) % N;
+    // taking the lower-numbered fork first breaks the circular wait
+    let (first, second) = (left.min(right), left.max(right));
+    let (first, second) = unsafe { (FORKS[first], FORKS[second]) };
+    for _ in 0..ROUNDS {
+        sleep(THINK_TIME);
+        mutex_lock(first);
+        mutex_lock(second);
+        sleep(EAT_TIME);
+        // only this philosopher counts its meals
+        unsafe { MEALS[id] += 1 };
+        mutex_unlock(second);
+        mutex_unlock(first);
+    }
+    exit(0);
+    unreachable!()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    let forks: [usize; N] = core::array::from_fn(|_| mutex_blocking_create() as usize);
+    unsafe { FORKS = forks };
+    let v: Vec<isize> = (0..N)
+        .map(|id| thread_create(philosopher as fn(usize) -> ! as usize, id))
+        .collect();
+    for tid in v {
+        assert_eq!(waittid(tid as usize), 0);
+    }
+    let meals = unsafe { MEALS };
+    assert!(meals.iter().all(|count| *count == ROUNDS));
+    println!("philosophers dined in {}ms", get_time() - start);
+    println!("phil_din_mutex test passed!");
+    0
+}
@@ -0,0 +1,71 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{close, exit, fork, pipe, read, set_nonblocking, sleep, waitpid, write, EAGAIN};
+
+const CHUNK: usize = 256;
+/// A full pipe must be found before writing this many bytes
+const WRITE_LIMIT: usize = 1 << 20;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let [read_end, write_end] = pipe_fd;
+    let mut buffer = [0u8; CHUNK];
+
+    // reading an empty non-blocking pipe fails at once
+    assert_eq!(set_nonblocking(read_end, true), 0);
+    assert_eq!(read(read_end, &mut buffer), -EAGAIN);
+    assert_eq!(write(write_end, b"hello"), 5);
+    assert_eq!(read(read_end, &mut buffer), 5);
+    assert_eq!(&buffer[..5], b"hello");
+
+    // writing a full non-blocking pipe fails at once, and succeeds again
+    // after some data is read
+    assert_eq!(set_nonblocking(write_end, true), 0);
+    let mut written = 0;
+    loop {
+        let len = write(write_end, &buffer);
+        if len == -EAGAIN {
+            break;
+        }
+        assert!(len > 0);
+        written += len as usize;
+        assert!(written < WRITE_LIMIT, "a non-blocking write never failed");
+    }
+    assert!(written > 0);
+    let mut drained = 0;
+    while drained < written {
+        let len = read(read_end, &mut buffer);
+        assert!(len > 0);
+        drained += len as usize;
+    }
+    assert_eq!(read(read_end, &mut buffer), -EAGAIN);
+
+    // a blocking read sleeps until the writer sends data
+    assert_eq!(set_nonblocking(read_end, false), 0);
+    let pid = fork();
+    if pid == 0 {
+        close(read_end);
+        sleep(100);
+        write(write_end, b"late");
+        exit(0);
+    }
+    assert_eq!(read(read_end, &mut buffer), 4);
+    assert_eq!(&buffer[..4], b"late");
+    let mut exit_code: i32 = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+
+    // with every write end closed, a non-blocking read sees the end of file
+    close(write_end);
+    assert_eq!(set_nonblocking(read_end, true), 0);
+    assert_eq!(read(read_end, &mut buffer), 0);
+    close(read_end);
+    println!("pipe_nonblock_test passed!");
+    0
+}
@@ -0,0 +1,59 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, exit, fork, get_time, pipe, poll, read, sleep, waitpid, write, PollFd, POLLHUP, POLLIN,
+    POLLOUT,
+};
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut first = [0usize; 2];
+    let mut second = [0usize; 2];
+    assert_eq!(pipe(&mut first), 0);
+    assert_eq!(pipe(&mut second), 0);
+    let mut fds = [
+        PollFd::new(first[0], POLLIN),
+        PollFd::new(second[0], POLLIN),
+    ];
+
+    // nothing to read, a zero timeout returns at once and a short one expires
+    assert_eq!(poll(&mut fds, 0), 0);
+    let start = get_time();
+    assert_eq!(poll(&mut fds, 50), 0);
+    assert!(get_time() - start >= 50);
+
+    // an empty pipe can be written
+    let mut out = [PollFd::new(first[1], POLLOUT)];
+    assert_eq!(poll(&mut out, 0), 1);
+    assert_eq!(out[0].revents, POLLOUT);
+
+    // wake up when the child writes to the second pipe only
+    let pid = fork();
+    if pid == 0 {
+        sleep(100);
+        write(second[1], b"ping");
+        exit(0);
+    }
+    assert_eq!(poll(&mut fds, -1), 1);
+    assert_eq!(fds[0].revents, 0);
+    assert_eq!(fds[1].revents & POLLIN, POLLIN);
+    let mut buffer = [0u8; 8];
+    assert_eq!(read(second[0], &mut buffer), 4);
+    assert_eq!(&buffer[..4], b"ping");
+    let mut exit_code: i32 = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+
+    // closing every write end reports a hang-up
+    close(first[1]);
+    close(second[1]);
+    assert_eq!(poll(&mut fds, -1), 2);
+    assert!(fds.iter().all(|fd| fd.revents & POLLHUP != 0));
+    close(first[0]);
+    close(second[0]);
+    println!("poll_test passed!");
+    0
+}
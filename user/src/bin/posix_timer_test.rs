@@ -0,0 +1,93 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::hint::black_box;
+use core::ptr::{addr_of, addr_of_mut};
+use user_lib::{
+    get_time, sigaction, sigreturn, timer_create, timer_delete, timer_gettime, timer_settime,
+    ITimerVal, SigEvent, SignalAction, SignalFlags, TimeSpec, CLOCK_MONOTONIC, SIGUSR1,
+};
+
+/// How long to compute at most while waiting for the timers
+const TIMEOUT_MS: isize = 5000;
+const PERIODIC: usize = 1;
+const ONE_SHOT: usize = 2;
+
+/// Expirations counted by the value of the timer
+static mut EXPIRED: [usize; 3] = [0; 3];
+
+extern "C" fn on_timer(_signum: i32, value: usize) {
+    unsafe { addr_of_mut!(EXPIRED[value]).write_volatile(expired(value) + 1) };
+    sigreturn();
+}
+
+fn expired(value: usize) -> usize {
+    unsafe { addr_of!(EXPIRED[value]).read_volatile() }
+}
+
+/// Compute until `done` returns true
+fn compute_until(mut done: impl FnMut() -> bool) {
+    let start = get_time();
+    let mut t = 2usize;
+    while !done() {
+        assert!(get_time() - start < TIMEOUT_MS, "the timers did not expire");
+        for _ in 0..1000 {
+            t = black_box(t * t % 10007);
+        }
+    }
+}
+
+fn timer(interval_ms: usize, value_ms: usize) -> ITimerVal {
+    ITimerVal {
+        interval: TimeSpec::from_millis(interval_ms),
+        value: TimeSpec::from_millis(value_ms),
+    }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let action = SignalAction {
+        handler: on_timer as extern "C" fn(i32, usize) as usize,
+        mask: SignalFlags::empty(),
+    };
+    assert_eq!(sigaction(SIGUSR1, Some(&action), None), 0);
+
+    // two timers deliver the same signal, each with its own value
+    let periodic = timer_create(CLOCK_MONOTONIC, Some(&SigEvent::signal(SIGUSR1, PERIODIC)));
+    let one_shot = timer_create(CLOCK_MONOTONIC, Some(&SigEvent::signal(SIGUSR1, ONE_SHOT)));
+    assert!(periodic >= 0 && one_shot >= 0 && periodic != one_shot);
+    let (periodic, one_shot) = (periodic as usize, one_shot as usize);
+    assert_eq!(timer_settime(periodic, 0, &timer(30, 30), None), 0);
+    assert_eq!(timer_settime(one_shot, 0, &timer(0, 100), None), 0);
+    compute_until(|| expired(PERIODIC) >= 5 && expired(ONE_SHOT) == 1);
+
+    // deleted timers stop and are gone
+    let mut curr = ITimerVal::default();
+    assert_eq!(timer_gettime(one_shot, &mut curr), 0);
+    assert_eq!(curr, ITimerVal::default());
+    assert_eq!(timer_delete(periodic), 0);
+    assert_eq!(timer_delete(one_shot), 0);
+    let fired = expired(PERIODIC);
+    let start = get_time();
+    compute_until(|| get_time() - start >= 100);
+    assert_eq!(expired(PERIODIC), fired);
+    assert_eq!(expired(ONE_SHOT), 1);
+    assert_eq!(timer_settime(periodic, 0, &timer(0, 10), None), -1);
+    assert_eq!(timer_gettime(one_shot, &mut curr), -1);
+
+    // a timer without a signal is polled
+    let polled = timer_create(CLOCK_MONOTONIC, Some(&SigEvent::none()));
+    assert!(polled >= 0);
+    assert_eq!(timer_settime(polled as usize, 0, &timer(0, 50), None), 0);
+    compute_until(|| {
+        assert_eq!(timer_gettime(polled as usize, &mut curr), 0);
+        curr.value == TimeSpec::default()
+    });
+    assert_eq!(timer_delete(polled as usize), 0);
+    assert_eq!(expired(0), 0);
+    println!("posix_timer_test passed!");
+    0
+}
@@ -0,0 +1,74 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec;
+use user_lib::{
+    close, fork, get_time, kill, open, read, waitpid, write, yield_, OpenFlags, SIGKILL,
+};
+
+const FILE: &str = "preempt_latency_file\0";
+/// Read by the child in a single system call
+const FILE_SIZE: usize = 1 << 20;
+const CHUNK_SIZE: usize = 4096;
+/// How long the latency is measured in milliseconds
+const MEASURE_TIME: isize = 3000;
+
+/// Read the whole file in one system call over and over, so the kernel is
+/// busy on behalf of this process for a long time
+fn reader() -> ! {
+    let mut buf = vec![0u8; FILE_SIZE];
+    loop {
+        let fd = open(FILE, OpenFlags::RDONLY);
+        assert!(fd >= 0);
+        let start = get_time();
+        assert_eq!(read(fd as usize, &mut buf), FILE_SIZE as isize);
+        println!("reader: one read took {}ms", get_time() - start);
+        close(fd as usize);
+    }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let fd = open(
+        FILE,
+        OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC,
+    );
+    assert!(fd >= 0);
+    let chunk = [0x5au8; CHUNK_SIZE];
+    for _ in 0..FILE_SIZE / CHUNK_SIZE {
+        assert_eq!(write(fd as usize, &chunk), CHUNK_SIZE as isize);
+    }
+    close(fd as usize);
+
+    let pid = fork();
+    if pid == 0 {
+        reader();
+    }
+    // the longest time this process waited to run again, which is about the
+    // time of a whole read unless the kernel can be preempted
+    let start = get_time();
+    let mut last = start;
+    let mut max_gap = 0;
+    let mut rounds = 0;
+    while last - start < MEASURE_TIME {
+        yield_();
+        let now = get_time();
+        max_gap = max_gap.max(now - last);
+        last = now;
+        rounds += 1;
+    }
+    kill(pid as usize, SIGKILL);
+    let mut exit_code = 0;
+    waitpid(pid as usize, &mut exit_code);
+    println!(
+        "max scheduling latency = {}ms over {} rounds in {}ms",
+        max_gap,
+        rounds,
+        last - start
+    );
+    0
+}
@@ -0,0 +1,88 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::hint::black_box;
+use user_lib::{
+    exit, get_time, mutex_blocking_create, mutex_lock, mutex_unlock, set_priority, thread_create,
+    waittid, yield_, MIN_PRIORITY,
+};
+
+const HIGH_PRIORITY: isize = 64;
+const MEDIUM_PRIORITY: isize = 32;
+/// Iterations of the work the low-priority thread does holding the mutex
+const LOW_WORK: usize = 2_000_000;
+/// The medium-priority thread works this many times longer
+const MEDIUM_FACTOR: usize = 20;
+/// How many times the time of the low work alone the high-priority thread
+/// may wait, without inheritance the wait is about `MEDIUM_FACTOR` times
+const WAIT_BOUND_FACTOR: isize = 4;
+
+static mut LOCKED: bool = false;
+
+fn work(iterations: usize) {
+    let mut t = 2usize;
+    for _ in 0..iterations {
+        t = black_box(t * t % 10007);
+    }
+}
+
+fn low(mutex_id: usize) -> ! {
+    set_priority(MIN_PRIORITY);
+    mutex_lock(mutex_id);
+    unsafe { core::ptr::addr_of_mut!(LOCKED).write_volatile(true) };
+    work(LOW_WORK);
+    mutex_unlock(mutex_id);
+    exit(0);
+    unreachable!()
+}
+
+fn medium() -> ! {
+    set_priority(MEDIUM_PRIORITY);
+    work(LOW_WORK * MEDIUM_FACTOR);
+    exit(0);
+    unreachable!()
+}
+
+/// Exit with how long it waited for the mutex in milliseconds
+fn high(mutex_id: usize) -> ! {
+    set_priority(HIGH_PRIORITY);
+    let start = get_time();
+    mutex_lock(mutex_id);
+    let waited = get_time() - start;
+    mutex_unlock(mutex_id);
+    exit(waited as i32);
+    unreachable!()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    work(LOW_WORK);
+    let alone = (get_time() - start).max(1);
+
+    let mutex_id = mutex_blocking_create() as usize;
+    let low = thread_create(low as fn(usize) -> ! as usize, mutex_id);
+    while !unsafe { core::ptr::addr_of!(LOCKED).read_volatile() } {
+        yield_();
+    }
+    // the high-priority thread blocks on the mutex while the
+    // medium-priority one competes with the holder for the CPU
+    let high = thread_create(high as fn(usize) -> ! as usize, mutex_id);
+    let medium = thread_create(medium as fn() -> ! as usize, 0);
+    let waited = waittid(high as usize);
+    assert_eq!(waittid(low as usize), 0);
+    assert_eq!(waittid(medium as usize), 0);
+    println!(
+        "the high-priority thread waited {}ms, the low work alone takes {}ms",
+        waited, alone
+    );
+    if waited > alone * WAIT_BOUND_FACTOR {
+        println!("priority inversion is not bounded!");
+        return -1;
+    }
+    println!("priority_inversion test passed!");
+    0
+}
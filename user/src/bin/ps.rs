@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{task_info, TaskInfo};
+
+/// The max number of tasks listed
+const MAX_TASKS: usize = 64;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut tasks = [TaskInfo::default(); MAX_TASKS];
+    let count = task_info(&mut tasks);
+    if count < 0 {
+        println!("ps: cannot get task information");
+        return -1;
+    }
+    println!("{:>5} {:>5} {:>1} {}", "PID", "PPID", "S", "NAME");
+    for task in tasks[..count as usize].iter() {
+        println!(
+            "{:>5} {:>5} {:>1} {}",
+            task.pid,
+            task.ppid,
+            task.status_char(),
+            task.name()
+        );
+    }
+    0
+}
@@ -0,0 +1,68 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use user_lib::{
+    exit, fork, getpid, kill, ptrace, ptrace_getregs, ptrace_peek, ptrace_poke, ptrace_setregs,
+    waitpid_options, UserRegs, WaitStatus, PTRACE_CONT, PTRACE_SINGLESTEP, PTRACE_TRACEME, SIGSTOP,
+    SIGTRAP,
+};
+
+/// Read by the tracee after the tracer has changed it
+static VALUE: AtomicUsize = AtomicUsize::new(1);
+
+/// Wait for the tracee and return how it changed state
+fn wait_tracee(pid: isize) -> WaitStatus {
+    let mut status = WaitStatus::Exited(0);
+    assert_eq!(waitpid_options(pid, &mut status, 0), pid);
+    status
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let pid = fork();
+    if pid == 0 {
+        assert_eq!(ptrace(PTRACE_TRACEME, 0, 0, 0), 0);
+        kill(getpid() as usize, SIGSTOP);
+        exit(VALUE.load(Ordering::Relaxed) as i32);
+    }
+    // the tracee stops on the signal it sends itself
+    assert_eq!(wait_tracee(pid), WaitStatus::Stopped(SIGSTOP));
+    let tracee = pid as usize;
+
+    // memory is shared with the fork, at the same addresses
+    let addr = VALUE.as_ptr() as usize;
+    assert_eq!(ptrace_peek(tracee, addr), Some(1));
+    assert_eq!(ptrace_poke(tracee, addr, 42), 0);
+    assert_eq!(ptrace_peek(tracee, addr), Some(42));
+    // the tracer's own copy is not changed
+    assert_eq!(VALUE.load(Ordering::Relaxed), 1);
+    println!("peek and poke ok");
+
+    let mut regs = UserRegs::default();
+    assert_eq!(ptrace_getregs(tracee, &mut regs), 0);
+    assert_ne!(regs.pc, 0);
+    assert_eq!(regs.x[0], 0);
+    // sp
+    assert_ne!(regs.x[2], 0);
+    assert_eq!(ptrace_setregs(tracee, &regs), 0);
+
+    // one instruction later the tracee stops again
+    assert_eq!(ptrace(PTRACE_SINGLESTEP, tracee, 0, 0), 0);
+    assert_eq!(wait_tracee(pid), WaitStatus::Stopped(SIGTRAP));
+    let mut stepped = UserRegs::default();
+    assert_eq!(ptrace_getregs(tracee, &mut stepped), 0);
+    assert_ne!(stepped.pc, regs.pc);
+    println!("single step ok");
+
+    // the tracee exits with the value written by the tracer
+    assert_eq!(ptrace(PTRACE_CONT, tracee, 0, 0), 0);
+    assert_eq!(wait_tracee(pid), WaitStatus::Exited(42));
+    // only tracees can be inspected
+    assert_eq!(ptrace_peek(tracee, addr), None);
+    println!("ptrace_test passed!");
+    0
+}
@@ -0,0 +1,47 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec::Vec;
+use user_lib::{exit, mutex_blocking_create, mutex_lock, mutex_unlock, thread_create, waittid};
+
+const PER_THREAD: usize = 1000;
+const THREAD_COUNT: usize = 16;
+
+static mut A: usize = 0;
+
+/// Add to `A` with a delay between reading and writing it, which loses
+/// updates unless the mutex is held
+fn adder(mutex_id: usize) -> ! {
+    let mut t = 2usize;
+    for _ in 0..PER_THREAD {
+        mutex_lock(mutex_id);
+        let cur = unsafe { core::ptr::addr_of!(A).read_volatile() };
+        for _ in 0..500 {
+            t = t * t % 10007;
+        }
+        unsafe { core::ptr::addr_of_mut!(A).write_volatile(cur + 1) };
+        mutex_unlock(mutex_id);
+    }
+    core::hint::black_box(t);
+    exit(0);
+    unreachable!()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mutex_id = mutex_blocking_create();
+    assert!(mutex_id >= 0);
+    let v: Vec<isize> = (0..THREAD_COUNT)
+        .map(|_| thread_create(adder as fn(usize) -> ! as usize, mutex_id as usize))
+        .collect();
+    for tid in v {
+        assert_eq!(waittid(tid as usize), 0);
+    }
+    assert_eq!(unsafe { A }, PER_THREAD * THREAD_COUNT);
+    println!("race_adder_mutex test passed!");
+    0
+}
@@ -0,0 +1,41 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{getnice, setnice};
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc < 3 {
+        println!("usage: renice NICE PID...");
+        return -1;
+    }
+    let nice = match argv[1].parse::<isize>() {
+        Ok(nice) => nice,
+        Err(_) => {
+            println!("renice: invalid nice value {}", argv[1]);
+            return -1;
+        }
+    };
+    let mut status = 0;
+    for arg in &argv[2..argc] {
+        let pid = match arg.parse::<usize>() {
+            Ok(pid) => pid,
+            Err(_) => {
+                println!("renice: invalid pid {}", arg);
+                status = -1;
+                continue;
+            }
+        };
+        let old = getnice(pid);
+        if old.is_none() || setnice(pid, nice) != 0 {
+            println!("renice: cannot set nice value of {}", pid);
+            status = -1;
+            continue;
+        }
+        println!("{}: old nice {}, new nice {}", pid, old.unwrap(), nice);
+    }
+    status
+}
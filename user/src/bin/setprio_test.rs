@@ -0,0 +1,21 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{set_priority, MIN_PRIORITY};
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(set_priority(10), 10);
+    assert_eq!(set_priority(isize::MAX), isize::MAX);
+    assert_eq!(set_priority(MIN_PRIORITY), MIN_PRIORITY);
+    // invalid priorities are refused
+    assert_eq!(set_priority(MIN_PRIORITY - 1), -1);
+    assert_eq!(set_priority(0), -1);
+    assert_eq!(set_priority(-10), -1);
+    assert_eq!(set_priority(isize::MIN), -1);
+    println!("setprio_test passed!");
+    0
+}
@@ -0,0 +1,68 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use user_lib::{
+    exit, fork, shmat, shmctl, shmdt, shmget, waitpid, yield_, IPC_CREAT, IPC_PRIVATE, IPC_RMID,
+};
+
+const SIZE: usize = 8192;
+const COUNT: u32 = 1000;
+
+/// Layout of the segment shared by parent and child
+#[repr(C)]
+struct Shared {
+    /// the last number written by the parent
+    request: AtomicU32,
+    /// the last number answered by the child
+    response: AtomicU32,
+    /// the sum of all numbers seen by the child
+    sum: AtomicU32,
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let id = shmget(IPC_PRIVATE, SIZE, IPC_CREAT);
+    assert!(id >= 0);
+    let id = id as usize;
+    let addr = shmat(id, 0);
+    assert!(addr > 0);
+    let shared = unsafe { &*(addr as *const Shared) };
+    // a new segment is zeroed
+    let bytes = unsafe { core::slice::from_raw_parts(addr as *const u8, SIZE) };
+    assert!(bytes.iter().all(|b| *b == 0));
+
+    // the child inherits the mapping, numbers go back and forth through it
+    let pid = fork();
+    if pid == 0 {
+        for i in 1..=COUNT {
+            while shared.request.load(Ordering::Acquire) != i {
+                yield_();
+            }
+            shared.sum.fetch_add(i, Ordering::Relaxed);
+            shared.response.store(i, Ordering::Release);
+        }
+        shmdt(addr as usize);
+        exit(0);
+    }
+    for i in 1..=COUNT {
+        shared.request.store(i, Ordering::Release);
+        while shared.response.load(Ordering::Acquire) != i {
+            yield_();
+        }
+    }
+    let mut exit_code: i32 = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    assert_eq!(shared.sum.load(Ordering::Relaxed), COUNT * (COUNT + 1) / 2);
+
+    // the segment stays valid in the parent after the child detached it
+    assert_eq!(shmdt(addr as usize), 0);
+    assert_eq!(shmctl(id, IPC_RMID), 0);
+    assert!(shmat(id, 0) < 0);
+    println!("shm_test passed!");
+    0
+}
@@ -0,0 +1,74 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::ptr::{addr_of, addr_of_mut};
+use user_lib::{
+    exit, fork, kill, sigaction, sigreturn, sleep, waitpid_options, SignalAction, WaitStatus,
+    SIGCHLD, SIGCONT, SIGSTOP, WNOHANG, WUNTRACED,
+};
+
+static mut CHILD_SIGNALS: usize = 0;
+
+fn on_sigchld() {
+    unsafe { addr_of_mut!(CHILD_SIGNALS).write_volatile(child_signals() + 1) };
+    sigreturn();
+}
+
+fn child_signals() -> usize {
+    unsafe { addr_of!(CHILD_SIGNALS).read_volatile() }
+}
+
+/// Fork a child which sleeps for `period_ms` and exits with `exit_code`
+fn spawn_sleeper(period_ms: usize, exit_code: i32) -> isize {
+    let pid = fork();
+    if pid == 0 {
+        sleep(period_ms);
+        exit(exit_code);
+    }
+    pid
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let action = SignalAction {
+        handler: on_sigchld as fn() as usize,
+        ..Default::default()
+    };
+    assert_eq!(sigaction(SIGCHLD, Some(&action), None), 0);
+    let mut status = WaitStatus::Exited(0);
+
+    // no children at all
+    assert_eq!(waitpid_options(-1, &mut status, WNOHANG), -1);
+
+    // WNOHANG returns 0 while the child runs, the pid once it has exited
+    let pid = spawn_sleeper(100, 3);
+    assert_eq!(waitpid_options(pid, &mut status, WNOHANG), 0);
+    while child_signals() == 0 {
+        sleep(10);
+    }
+    assert_eq!(waitpid_options(pid, &mut status, WNOHANG), pid);
+    assert_eq!(status, WaitStatus::Exited(3));
+    println!("SIGCHLD for an exited child ok");
+
+    // WUNTRACED reports a stopped child, which runs on after SIGCONT
+    let pid = spawn_sleeper(100, 7);
+    assert_eq!(kill(pid as usize, SIGSTOP), 0);
+    assert_eq!(waitpid_options(pid, &mut status, WUNTRACED), pid);
+    assert_eq!(status, WaitStatus::Stopped(SIGSTOP));
+    assert_eq!(waitpid_options(pid, &mut status, WNOHANG), 0);
+    assert_eq!(kill(pid as usize, SIGCONT), 0);
+    assert_eq!(waitpid_options(pid, &mut status, 0), pid);
+    assert_eq!(status, WaitStatus::Exited(7));
+
+    // an exit code looking like a stop is still reported as an exit
+    let pid = spawn_sleeper(10, 0x17f);
+    assert_eq!(waitpid_options(pid, &mut status, WUNTRACED), pid);
+    assert_eq!(status, WaitStatus::Exited(0x7f));
+    // one signal for each exit and one for the stop
+    assert_eq!(child_signals(), 4);
+    println!("sigchld_test passed!");
+    0
+}
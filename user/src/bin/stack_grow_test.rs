@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::hint::black_box;
+
+/// Bytes of stack each call takes at least
+const FRAME_SIZE: usize = 1024;
+/// Far beyond the initial user stack but within the stack limit
+const DEPTH: usize = 256;
+
+/// Fill a frame, recurse and check the frame survived the deeper calls
+fn f(depth: usize) -> usize {
+    let mut frame = [0u8; FRAME_SIZE];
+    for (i, b) in frame.iter_mut().enumerate() {
+        *b = (depth + i) as u8;
+    }
+    let frame = black_box(frame);
+    let sum = if depth == 0 { 0 } else { f(depth - 1) };
+    assert!(frame
+        .iter()
+        .enumerate()
+        .all(|(i, b)| *b == (depth + i) as u8));
+    sum + depth
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(f(DEPTH), DEPTH * (DEPTH + 1) / 2);
+    println!("stack_grow_test passed!");
+    0
+}
@@ -0,0 +1,47 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{exit, fork, get_time, set_priority, wait};
+
+const PRIORITIES: [isize; 6] = [5, 6, 7, 8, 9, 10];
+/// How long each child counts in milliseconds
+const RUN_TIME: isize = 1000;
+
+/// Count until `RUN_TIME` has passed since `start`
+fn count(start: isize) -> i32 {
+    let mut count = 0;
+    while get_time() - start < RUN_TIME {
+        for _ in 0..1000 {
+            count = core::hint::black_box(count + 1);
+        }
+    }
+    count / 1000
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // the children run with their priorities at the same time, so the counts
+    // should be proportional to the priorities
+    let start = get_time();
+    for prio in PRIORITIES {
+        if fork() == 0 {
+            assert_eq!(set_priority(prio), prio);
+            let count = count(start);
+            println!(
+                "priority = {}, count = {}, ratio = {}",
+                prio,
+                count,
+                count as isize / prio
+            );
+            exit(count);
+        }
+    }
+    let mut exit_code: i32 = 0;
+    for _ in PRIORITIES {
+        assert!(wait(&mut exit_code) > 0);
+    }
+    0
+}
@@ -0,0 +1,95 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use user_lib::{execvp, sched_getaffinity, sched_setaffinity};
+
+const USAGE: &str = "usage: taskset MASK PROGRAM [ARGS...] | taskset -p [MASK] PID";
+
+/// Parse a hexadecimal mask, with or without `0x`
+fn parse_mask(s: &str) -> Option<usize> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    usize::from_str_radix(s, 16).ok().filter(|mask| *mask != 0)
+}
+
+/// Show or change the mask of a running process
+fn taskset_pid(args: &[&str]) -> i32 {
+    let (mask, pid) = match args {
+        [pid] => (None, pid),
+        [mask, pid] => match parse_mask(mask) {
+            Some(mask) => (Some(mask), pid),
+            None => {
+                println!("taskset: invalid mask {}", mask);
+                return -1;
+            }
+        },
+        _ => {
+            println!("{}", USAGE);
+            return -1;
+        }
+    };
+    let pid = match pid.parse::<usize>() {
+        Ok(pid) => pid,
+        Err(_) => {
+            println!("taskset: invalid pid {}", pid);
+            return -1;
+        }
+    };
+    let mut old = 0;
+    if sched_getaffinity(pid, &mut old) != 0 {
+        println!("taskset: cannot get the affinity of {}", pid);
+        return -1;
+    }
+    match mask {
+        None => println!("pid {}'s current affinity mask: {:x}", pid, old),
+        Some(mask) => {
+            if sched_setaffinity(pid, mask) != 0 {
+                println!("taskset: cannot set the affinity of {}", pid);
+                return -1;
+            }
+            println!("pid {}'s current affinity mask: {:x}", pid, old);
+            println!("pid {}'s new affinity mask: {:x}", pid, mask);
+        }
+    }
+    0
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc >= 2 && argv[1] == "-p" {
+        return taskset_pid(&argv[2..argc]);
+    }
+    if argc < 3 {
+        println!("{}", USAGE);
+        return -1;
+    }
+    let mask = match parse_mask(argv[1]) {
+        Some(mask) => mask,
+        None => {
+            println!("taskset: invalid mask {}", argv[1]);
+            return -1;
+        }
+    };
+    if sched_setaffinity(0, mask) != 0 {
+        println!("taskset: cannot set the affinity to {:x}", mask);
+        return -1;
+    }
+    let args_copy: Vec<String> = argv[2..argc]
+        .iter()
+        .map(|arg| {
+            let mut arg = String::from(*arg);
+            arg.push('\0');
+            arg
+        })
+        .collect();
+    let mut args_addr: Vec<*const u8> = args_copy.iter().map(|arg| arg.as_ptr()).collect();
+    args_addr.push(core::ptr::null::<u8>());
+    execvp(args_copy[0].as_str(), args_addr.as_slice());
+    println!("taskset: cannot execute {}", argv[2]);
+    -1
+}
@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::console::getchar;
+use user_lib::term::*;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    clear_screen();
+    hide_cursor();
+    // the 16 standard colors
+    print_at(1, 1, WHITE | BRIGHT, BLACK, "standard colors");
+    for color in 0..16u8 {
+        print_at(2, 1 + color as usize * 3, BLACK, color, "   ");
+    }
+    // the 6x6x6 color cube, one 6x6 square per red level
+    print_at(4, 1, WHITE | BRIGHT, BLACK, "color cube");
+    for r in 0..6u8 {
+        for g in 0..6u8 {
+            for b in 0..6u8 {
+                let col = 1 + r as usize * 14 + b as usize * 2;
+                print_at(5 + g as usize, col, BLACK, rgb(r, g, b), "  ");
+            }
+        }
+    }
+    // the gray ramp
+    print_at(12, 1, WHITE | BRIGHT, BLACK, "gray ramp");
+    for level in 0..24u8 {
+        print_at(13, 1 + level as usize * 2, BLACK, gray(level), "  ");
+    }
+    // cursor control
+    draw_box(15, 1, 5, 40);
+    print_at(16, 3, YELLOW | BRIGHT, BLACK, "cursor positioning");
+    move_to(17, 3);
+    save_cursor();
+    move_to(1, 60);
+    restore_cursor();
+    bold();
+    print!("save/restore ok");
+    reset();
+    move_to(18, 3);
+    reverse();
+    print!("press any key to exit");
+    reset();
+    // without raw mode the key would only be read after Enter
+    let raw_mode = enable_raw_mode();
+    getchar();
+    drop(raw_mode);
+    show_cursor();
+    clear_screen();
+    println!("term_test passed!");
+    0
+}
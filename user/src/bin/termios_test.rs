@@ -0,0 +1,55 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::term::enable_raw_mode;
+use user_lib::{
+    close, pipe, tcgetattr, tcsetattr, LocalModes, Termios, VEOF, VERASE, VINTR, VSUSP,
+};
+
+fn console_termios() -> Termios {
+    let mut termios = Termios::default();
+    assert_eq!(tcgetattr(0, &mut termios), 0);
+    termios
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // the console starts in canonical mode with echo and signals
+    let cooked = console_termios();
+    assert!(cooked
+        .lflag
+        .contains(LocalModes::ICANON | LocalModes::ECHO | LocalModes::ISIG));
+    assert_eq!(cooked.cc[VINTR], 0x03);
+    assert_eq!(cooked.cc[VERASE], 0x7f);
+    assert_eq!(cooked.cc[VEOF], 0x04);
+    assert_eq!(cooked.cc[VSUSP], 0x1a);
+
+    // settings read back as they were set
+    let mut raw = cooked;
+    raw.make_raw();
+    assert_eq!(tcsetattr(0, &raw), 0);
+    assert_eq!(console_termios(), raw);
+    assert_eq!(tcsetattr(0, &cooked), 0);
+    assert_eq!(console_termios(), cooked);
+    println!("TCGETS and TCSETS ok");
+
+    // raw mode lasts as long as its guard
+    {
+        let _raw_mode = enable_raw_mode();
+        assert!(!console_termios().lflag.contains(LocalModes::ICANON));
+    }
+    assert_eq!(console_termios(), cooked);
+
+    // a pipe is not a terminal
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let mut termios = Termios::default();
+    assert_eq!(tcgetattr(pipe_fd[0], &mut termios), -1);
+    close(pipe_fd[0]);
+    close(pipe_fd[1]);
+    println!("termios_test passed!");
+    0
+}
@@ -0,0 +1,54 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{exit, gettid, thread_create, waittid};
+
+pub fn thread_a() -> ! {
+    for _ in 0..1000 {
+        print!("a");
+    }
+    exit(1);
+    unreachable!()
+}
+
+pub fn thread_b() -> ! {
+    for _ in 0..1000 {
+        print!("b");
+    }
+    exit(2);
+    unreachable!()
+}
+
+pub fn thread_c() -> ! {
+    for _ in 0..1000 {
+        print!("c");
+    }
+    exit(3);
+    unreachable!()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(gettid(), 0);
+    let v = [
+        thread_create(thread_a as fn() -> ! as usize, 0),
+        thread_create(thread_b as fn() -> ! as usize, 0),
+        thread_create(thread_c as fn() -> ! as usize, 0),
+    ];
+    println!("");
+    for (i, tid) in v.iter().enumerate() {
+        assert!(*tid > 0);
+        let exit_code = waittid(*tid as usize);
+        println!("thread#{} exited with code {}", tid, exit_code);
+        assert_eq!(exit_code, i as isize + 1);
+    }
+    // threads can only be waited for once, and never by themselves
+    assert_eq!(waittid(v[0] as usize), -1);
+    assert_eq!(waittid(0), -1);
+    println!("main thread exited.");
+    println!("threads test passed!");
+    0
+}
@@ -0,0 +1,50 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec::Vec;
+use user_lib::{exit, gettid, thread_create, waittid};
+
+struct Argument {
+    pub ch: char,
+    pub rc: i32,
+}
+
+fn thread_print(arg: *const Argument) -> ! {
+    let arg = unsafe { &*arg };
+    assert!(gettid() > 0);
+    for _ in 0..1000 {
+        print!("{}", arg.ch);
+    }
+    exit(arg.rc);
+    unreachable!()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut v = Vec::new();
+    // the arguments live on the stack of the main thread, which is shared
+    let args = [
+        Argument { ch: 'a', rc: 1 },
+        Argument { ch: 'b', rc: 2 },
+        Argument { ch: 'c', rc: 3 },
+    ];
+    for arg in args.iter() {
+        v.push(thread_create(
+            thread_print as fn(*const Argument) -> ! as usize,
+            arg as *const _ as usize,
+        ));
+    }
+    println!("");
+    for (tid, arg) in v.iter().zip(args.iter()) {
+        let exit_code = waittid(*tid as usize);
+        println!("thread#{} exited with code {}", tid, exit_code);
+        assert_eq!(exit_code, arg.rc as isize);
+    }
+    println!("main thread exited.");
+    println!("threads_arg test passed!");
+    0
+}
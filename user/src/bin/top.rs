@@ -0,0 +1,92 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec::Vec;
+use user_lib::term::clear_screen;
+use user_lib::{get_time, sleep, task_info, TaskInfo};
+
+/// The max number of tasks listed
+const MAX_TASKS: usize = 64;
+/// Refresh interval in milliseconds
+const INTERVAL: usize = 1000;
+/// Number of refreshes when not given on the command line
+const DEFAULT_ITERATIONS: usize = 10;
+
+/// Get the information of all tasks
+fn snapshot() -> Option<Vec<TaskInfo>> {
+    let mut tasks = [TaskInfo::default(); MAX_TASKS];
+    let count = task_info(&mut tasks);
+    if count < 0 {
+        return None;
+    }
+    Some(tasks[..count as usize].to_vec())
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let iterations = if argc > 1 {
+        match argv[1].parse::<usize>() {
+            Ok(n) => n,
+            Err(_) => {
+                println!("usage: top [ITERATIONS]");
+                return -1;
+            }
+        }
+    } else {
+        DEFAULT_ITERATIONS
+    };
+    let mut last = match snapshot() {
+        Some(tasks) => tasks,
+        None => {
+            println!("top: cannot get task information");
+            return -1;
+        }
+    };
+    let mut last_time = get_time() as usize;
+    for _ in 0..iterations {
+        sleep(INTERVAL);
+        let tasks = match snapshot() {
+            Some(tasks) => tasks,
+            None => return -1,
+        };
+        let now = get_time() as usize;
+        let elapsed = (now - last_time).max(1);
+        // CPU time used by each task since the last refresh
+        let mut usage: Vec<(usize, &TaskInfo)> = tasks
+            .iter()
+            .map(|task| {
+                let before = last
+                    .iter()
+                    .find(|t| t.pid == task.pid)
+                    .map_or(0, |t| t.cpu_time);
+                (task.cpu_time.saturating_sub(before), task)
+            })
+            .collect();
+        usage.sort_by(|a, b| b.0.cmp(&a.0));
+        clear_screen();
+        println!("top - {} tasks, refreshed every {} ms", tasks.len(), INTERVAL);
+        println!(
+            "{:>5} {:>5} {:>1} {:>5} {:>8} {:>8} {}",
+            "PID", "PPID", "S", "%CPU", "TIME", "MEM(K)", "NAME"
+        );
+        for (cpu, task) in usage.iter() {
+            println!(
+                "{:>5} {:>5} {:>1} {:>5} {:>8} {:>8} {}",
+                task.pid,
+                task.ppid,
+                task.status_char(),
+                cpu * 100 / elapsed,
+                task.cpu_time,
+                task.memory / 1024,
+                task.name()
+            );
+        }
+        last = tasks;
+        last_time = now;
+    }
+    0
+}
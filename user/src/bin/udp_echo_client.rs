@@ -0,0 +1,61 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, poll, recvfrom, sendto, socket, PollFd, SockAddrIn, AF_INET, POLLIN, SOCK_DGRAM,
+};
+
+const DEFAULT_PORT: u16 = 7;
+const DEFAULT_MESSAGE: &str = "hello from udp_echo_client";
+/// How long to wait for the echo in milliseconds
+const TIMEOUT_MS: isize = 1000;
+
+/// Usage: udp_echo_client [PORT] [MESSAGE]
+///
+/// Send a message to the echo server on the loopback address and check the reply
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let port = if argc > 1 {
+        match argv[1].parse() {
+            Ok(port) => port,
+            Err(_) => {
+                println!("udp_echo_client: invalid port {}", argv[1]);
+                return -1;
+            }
+        }
+    } else {
+        DEFAULT_PORT
+    };
+    let message = if argc > 2 { argv[2] } else { DEFAULT_MESSAGE };
+    let fd = socket(AF_INET, SOCK_DGRAM, 0);
+    if fd < 0 {
+        println!("udp_echo_client: cannot create a socket");
+        return -1;
+    }
+    let fd = fd as usize;
+    let server = SockAddrIn::new([127, 0, 0, 1], port);
+    if sendto(fd, message.as_bytes(), Some(&server)) != message.len() as isize {
+        println!("udp_echo_client: send failed");
+        close(fd);
+        return -1;
+    }
+    let mut fds = [PollFd::new(fd, POLLIN)];
+    if poll(&mut fds, TIMEOUT_MS) <= 0 {
+        println!("udp_echo_client: no reply from port {}", port);
+        close(fd);
+        return -1;
+    }
+    let mut buffer = [0u8; 1024];
+    let mut peer = SockAddrIn::default();
+    let len = recvfrom(fd, &mut buffer, Some(&mut peer));
+    close(fd);
+    if len < 0 || peer != server || &buffer[..len as usize] != message.as_bytes() {
+        println!("udp_echo_client: wrong reply");
+        return -1;
+    }
+    println!("udp_echo_client: {}", message);
+    0
+}
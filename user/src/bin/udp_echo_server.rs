@@ -0,0 +1,49 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{bind, recvfrom, sendto, socket, SockAddrIn, AF_INET, SOCK_DGRAM};
+
+/// The port of the echo protocol
+const DEFAULT_PORT: u16 = 7;
+
+/// Usage: udp_echo_server [PORT]
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let port = if argc > 1 {
+        match argv[1].parse() {
+            Ok(port) => port,
+            Err(_) => {
+                println!("udp_echo_server: invalid port {}", argv[1]);
+                return -1;
+            }
+        }
+    } else {
+        DEFAULT_PORT
+    };
+    let fd = socket(AF_INET, SOCK_DGRAM, 0);
+    if fd < 0 {
+        println!("udp_echo_server: cannot create a socket");
+        return -1;
+    }
+    let fd = fd as usize;
+    if bind(fd, &SockAddrIn::new([0, 0, 0, 0], port)) != 0 {
+        println!("udp_echo_server: cannot bind port {}", port);
+        return -1;
+    }
+    println!("udp_echo_server: listening on port {}", port);
+    let mut buffer = [0u8; 1024];
+    loop {
+        let mut peer = SockAddrIn::default();
+        let len = recvfrom(fd, &mut buffer, Some(&mut peer));
+        if len < 0 {
+            println!("udp_echo_server: receive failed");
+            return -1;
+        }
+        let [a, b, c, d] = peer.addr;
+        println!("{}.{}.{}.{}:{} sent {} bytes", a, b, c, d, peer.port(), len);
+        sendto(fd, &buffer[..len as usize], Some(&peer));
+    }
+}
@@ -0,0 +1,24 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::string::String;
+use user_lib::umount;
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc != 2 {
+        println!("usage: umount TARGET");
+        return -1;
+    }
+    let mut target = String::from(argv[1]);
+    target.push('\0');
+    if umount(target.as_str()) != 0 {
+        println!("umount: cannot unmount {}", argv[1]);
+        return -1;
+    }
+    0
+}
@@ -0,0 +1,65 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec::Vec;
+use user_lib::{uname, UtsName};
+
+const USAGE: &str = "usage: uname [-asnrvmf] [-F FEATURE]";
+
+/// Print the fields chosen by the options in order, the kernel name by
+/// default. `uname -F FEATURE` prints nothing and exits with 0 if the
+/// kernel has the feature, so scripts can test for it
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let mut name = UtsName::default();
+    if uname(&mut name) != 0 {
+        println!("uname: cannot get the kernel name");
+        return -1;
+    }
+    if argc == 3 && argv[1] == "-F" {
+        return if name.has_feature(argv[2]) { 0 } else { 1 };
+    }
+    let fields = [
+        ('s', &name.sysname),
+        ('n', &name.nodename),
+        ('r', &name.release),
+        ('v', &name.version),
+        ('m', &name.machine),
+        ('f', &name.features),
+    ];
+    let mut chosen = [false; 6];
+    for arg in argv[1..argc].iter() {
+        let options = match arg.strip_prefix('-') {
+            Some(options) if !options.is_empty() => options,
+            _ => {
+                println!("{}", USAGE);
+                return -1;
+            }
+        };
+        for option in options.chars() {
+            match fields.iter().position(|(c, _)| *c == option) {
+                Some(i) => chosen[i] = true,
+                None if option == 'a' => chosen = [true; 6],
+                None => {
+                    println!("{}", USAGE);
+                    return -1;
+                }
+            }
+        }
+    }
+    if !chosen.contains(&true) {
+        chosen[0] = true;
+    }
+    let words: Vec<&str> = fields
+        .iter()
+        .zip(chosen)
+        .filter(|(_, chosen)| *chosen)
+        .map(|((_, field), _)| UtsName::field(field))
+        .collect();
+    println!("{}", words.join(" "));
+    0
+}
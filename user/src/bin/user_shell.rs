@@ -7,208 +7,1080 @@ extern crate alloc;
 #[macro_use]
 extern crate user_lib;
 
-const LF: u8 = 0x0au8;
-const CR: u8 = 0x0du8;
-const DL: u8 = 0x7fu8;
-const BS: u8 = 0x08u8;
 const LINE_START: &str = ">> ";
+/// Commands run by every interactive shell at startup
+const SYSTEM_RC_FILE: &str = "/etc/shellrc";
+/// Exit status of a foreground command stopped by `SIGTSTP`
+const STOPPED_STATUS: i32 = 128 + 20;
+/// Characters ending the token being completed
+const TOKEN_SEPARATORS: &[char] = &[' ', '\t', '|', '<', '>', '&'];
 
+use alloc::collections::BTreeMap;
+use alloc::format;
 use alloc::string::String;
 use alloc::vec::Vec;
-use user_lib::console::getchar;
-use user_lib::{close, dup, exec, fork, open, pipe, waitpid, OpenFlags};
+use core::iter::Peekable;
+use core::str::Chars;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use user_lib::glob::glob;
+use user_lib::readline::Readline;
+use user_lib::{
+    close, dup2, execvp, exit, fork, get_time, getenv, getpgid, kill, open, pipe, read, read_dir,
+    reboot, setenv, setpgid, sigaction, sigreturn, tcsetpgrp, unsetenv, vars, waitpid_options,
+    OpenFlags, SignalAction, WaitStatus, REBOOT_CMD_HALT, REBOOT_CMD_POWER_OFF, REBOOT_CMD_RESTART,
+    SIGCHLD, SIGCONT, WNOHANG, WUNTRACED,
+};
 
 #[derive(Debug)]
 struct ProcessArguments {
     input: String,
     output: String,
+    /// whether output is appended to the file instead of replacing it
+    append: bool,
     args_copy: Vec<String>,
     args_addr: Vec<*const u8>,
 }
 
-impl ProcessArguments {
-    pub fn new(command: &str) -> Self {
-        let args: Vec<_> = command.split(' ').collect();
-        let mut args_copy: Vec<String> = args
-            .iter()
-            .filter(|&arg| !arg.is_empty())
-            .map(|&arg| {
-                let mut string = String::new();
-                string.push_str(arg);
-                string.push('\0');
-                string
-            })
-            .collect();
-
-        // redirect input
-        let mut input = String::new();
-        if let Some((idx, _)) = args_copy
-            .iter()
-            .enumerate()
-            .find(|(_, arg)| arg.as_str() == "<\0")
-        {
-            input = args_copy[idx + 1].clone();
-            args_copy.drain(idx..=idx + 1);
+/// A token of a command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// a word with quotes and escapes removed
+    Word(String),
+    /// a word with unquoted wildcards, quoted characters escaped by `\`
+    Pattern(String),
+    /// `|`
+    Pipe,
+    /// `<`
+    Input,
+    /// `>`
+    Output,
+    /// `>>`
+    Append,
+    /// `&`
+    Background,
+}
+
+/// Whether a character can be part of a variable name
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// A word being built by `tokenize`
+#[derive(Default)]
+struct WordBuilder {
+    text: String,
+    /// the word as a glob pattern
+    pattern: String,
+    /// whether the word has an unquoted wildcard
+    wild: bool,
+}
+
+impl WordBuilder {
+    /// Add a character taken literally
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+        if matches!(c, '*' | '?' | '\\') {
+            self.pattern.push('\\');
+        }
+        self.pattern.push(c);
+    }
+    fn push_str(&mut self, s: &str) {
+        s.chars().for_each(|c| self.push(c));
+    }
+    /// Add an unquoted character, which is a wildcard if it is `*` or `?`
+    fn push_unquoted(&mut self, c: char) {
+        if matches!(c, '*' | '?') {
+            self.text.push(c);
+            self.pattern.push(c);
+            self.wild = true;
+        } else {
+            self.push(c);
         }
+    }
+    fn take(&mut self) -> Token {
+        let word = core::mem::take(self);
+        if word.wild {
+            Token::Pattern(word.pattern)
+        } else {
+            Token::Word(word.text)
+        }
+    }
+}
 
-        // redirect output
+/// Expand the variable after a `$` as `NAME` or `${NAME}`, or `$?` as the
+/// exit status of the last command. An unset variable expands to nothing
+/// and a `$` not followed by a name is kept
+fn expand_variable(
+    chars: &mut Peekable<Chars>,
+    word: &mut WordBuilder,
+    status: i32,
+) -> Result<(), String> {
+    let mut name = String::new();
+    if chars.peek() == Some(&'?') {
+        chars.next();
+        word.push_str(format!("{}", status).as_str());
+        return Ok(());
+    }
+    if chars.peek() == Some(&'{') {
+        chars.next();
+        loop {
+            match chars.next() {
+                Some('}') => break,
+                Some(c) if is_name_char(c) => name.push(c),
+                _ => return Err(String::from("Invalid command: bad ${} substitution")),
+            }
+        }
+    } else {
+        while let Some(&c) = chars.peek() {
+            if !is_name_char(c) {
+                break;
+            }
+            name.push(c);
+            chars.next();
+        }
+        if name.is_empty() {
+            word.push('$');
+            return Ok(());
+        }
+    }
+    word.push_str(getenv(name.as_str()).as_deref().unwrap_or(""));
+    Ok(())
+}
+
+/// Split a command line into tokens. Words are separated by whitespace and
+/// operators, single quotes keep everything literally, double quotes only
+/// let a backslash escape `"`, `\` and `$`, and a backslash outside quotes
+/// escapes any character. Variables are expanded outside single quotes and
+/// the value always stays in one word. Unquoted `*` and `?` make the word
+/// a `Token::Pattern`. A `#` starting a word begins a comment to the end
+/// of the line
+fn tokenize(line: &str, status: i32) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut word = WordBuilder::default();
+    // whether a word is being built, so that `''` gives an empty word
+    let mut in_word = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let operator = match c {
+            '|' => Some(Token::Pipe),
+            '&' => Some(Token::Background),
+            '<' => Some(Token::Input),
+            '>' if chars.peek() == Some(&'>') => {
+                chars.next();
+                Some(Token::Append)
+            }
+            '>' => Some(Token::Output),
+            _ => None,
+        };
+        if operator.is_some() || c.is_whitespace() {
+            if in_word {
+                tokens.push(word.take());
+                in_word = false;
+            }
+            tokens.extend(operator);
+            continue;
+        }
+        if c == '#' && !in_word {
+            break;
+        }
+        in_word = true;
+        match c {
+            '\'' => loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some(c) => word.push(c),
+                    None => return Err(String::from("Invalid command: unterminated \'")),
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') if matches!(chars.peek(), Some('"') | Some('\\') | Some('$')) => {
+                        word.push(chars.next().unwrap())
+                    }
+                    Some('$') => expand_variable(&mut chars, &mut word, status)?,
+                    Some(c) => word.push(c),
+                    None => return Err(String::from("Invalid command: unterminated \"")),
+                }
+            },
+            '\\' => match chars.next() {
+                Some(c) => word.push(c),
+                None => return Err(String::from("Invalid command: nothing to escape after \\")),
+            },
+            '$' => expand_variable(&mut chars, &mut word, status)?,
+            c => word.push_unquoted(c),
+        }
+    }
+    if in_word {
+        tokens.push(word.take());
+    }
+    Ok(tokens)
+}
+
+impl ProcessArguments {
+    /// Build the arguments of one stage of a pipeline, `tokens` contains no `Token::Pipe`
+    pub fn new(tokens: &[Token]) -> Result<Self, String> {
+        let mut input = String::new();
         let mut output = String::new();
-        if let Some((idx, _)) = args_copy
-            .iter()
-            .enumerate()
-            .find(|(_, arg)| arg.as_str() == ">\0")
-        {
-            output = args_copy[idx + 1].clone();
-            args_copy.drain(idx..=idx + 1);
+        let mut append = false;
+        let mut args_copy: Vec<String> = Vec::new();
+        let mut tokens = tokens.iter();
+        while let Some(token) = tokens.next() {
+            match token {
+                Token::Word(arg) => {
+                    let mut string = arg.clone();
+                    string.push('\0');
+                    args_copy.push(string);
+                    continue;
+                }
+                Token::Pattern(pattern) => {
+                    let paths = glob(pattern.as_str());
+                    if paths.is_empty() {
+                        return Err(format!("no matches found: {}", pattern));
+                    }
+                    for mut path in paths {
+                        path.push('\0');
+                        args_copy.push(path);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            let mut file = match tokens.next() {
+                Some(Token::Word(file)) => file.clone(),
+                // a pattern names a file only if it matches exactly one
+                Some(Token::Pattern(pattern)) => match glob(pattern.as_str()).as_slice() {
+                    [file] => file.clone(),
+                    [] => return Err(format!("no matches found: {}", pattern)),
+                    _ => return Err(format!("ambiguous redirect: {}", pattern)),
+                },
+                _ => {
+                    return Err(String::from(
+                        "Invalid command: missing file after redirection",
+                    ))
+                }
+            };
+            file.push('\0');
+            match token {
+                // redirect input
+                Token::Input => input = file,
+                // redirect output, `>` replaces the file and `>>` appends to it
+                _ => {
+                    if !output.is_empty() {
+                        return Err(String::from("Invalid command: more than one output file"));
+                    }
+                    output = file;
+                    append = *token == Token::Append;
+                }
+            }
         }
 
         let mut args_addr: Vec<*const u8> = args_copy.iter().map(|arg| arg.as_ptr()).collect();
         args_addr.push(core::ptr::null::<u8>());
 
-        Self {
+        Ok(Self {
             input,
             output,
+            append,
             args_copy,
             args_addr,
+        })
+    }
+}
+
+/// A pipeline started by the shell and not yet waited for
+struct Job {
+    /// number shown as `[id]` and given to `fg` and `bg`, 0 for a foreground
+    /// pipeline which never was a job
+    id: usize,
+    /// process group of the pipeline
+    pgid: usize,
+    /// processes of the pipeline which have not exited
+    pids: Vec<isize>,
+    command: String,
+    /// whether a process of the pipeline has been stopped
+    stopped: bool,
+}
+
+/// Set by the `SIGCHLD` handler, so jobs are polled at once after a child changed
+static CHILD_CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// Calls of `reap_jobs` after which jobs are polled even without `SIGCHLD`,
+/// in case the signal was lost or is not delivered by the kernel
+const REAP_FALLBACK_CALLS: usize = 8;
+
+/// Calls of `reap_jobs` since jobs were last polled
+static CALLS_SINCE_POLL: AtomicUsize = AtomicUsize::new(0);
+
+fn on_sigchld() {
+    CHILD_CHANGED.store(true, Ordering::Relaxed);
+    sigreturn();
+}
+
+/// Forget the processes of background jobs which have exited, report jobs
+/// which are done or have been stopped
+fn reap_jobs(jobs: &mut Vec<Job>) {
+    let poll_due = CALLS_SINCE_POLL.fetch_add(1, Ordering::Relaxed) + 1 >= REAP_FALLBACK_CALLS;
+    if !CHILD_CHANGED.swap(false, Ordering::Relaxed) && !poll_due {
+        return;
+    }
+    CALLS_SINCE_POLL.store(0, Ordering::Relaxed);
+    let mut status = WaitStatus::Exited(0);
+    for job in jobs.iter_mut() {
+        let mut stopped = false;
+        job.pids.retain(
+            |&pid| match waitpid_options(pid, &mut status, WNOHANG | WUNTRACED) {
+                // still running
+                0 => true,
+                ret if ret > 0 && matches!(status, WaitStatus::Stopped(_)) => {
+                    stopped = true;
+                    true
+                }
+                _ => false,
+            },
+        );
+        if job.pids.is_empty() {
+            println!("[{}] Done    {}", job.id, job.command);
+        } else if stopped && !job.stopped {
+            job.stopped = true;
+            println!("[{}] Stopped    {}", job.id, job.command);
         }
     }
+    jobs.retain(|job| !job.pids.is_empty());
 }
 
-#[no_mangle]
-pub fn main() -> i32 {
-    println!("Rust user shell");
-    let mut line: String = String::new();
-    print!("{}", LINE_START);
+/// Find the job given as `%id` or `id`, the most recent job if no argument is given
+fn find_job(jobs: &[Job], arg: Option<&String>) -> Result<usize, String> {
+    let id = match arg {
+        None => {
+            return jobs
+                .len()
+                .checked_sub(1)
+                .ok_or(String::from("no current job"))
+        }
+        Some(arg) => arg.trim_start_matches('%').trim_end_matches('\0'),
+    };
+    jobs.iter()
+        .position(|job| id.parse() == Ok(job.id))
+        .ok_or(format!("no such job: {}", id))
+}
+
+/// Complete the last token of a line against the names in its directory,
+/// return the text to append and all candidates
+fn complete(line: &str) -> (String, Vec<String>) {
+    let token = &line[line.rfind(TOKEN_SEPARATORS).map_or(0, |i| i + 1)..];
+    let (dir, prefix) = match token.rfind('/') {
+        Some(i) => (&token[..=i], &token[i + 1..]),
+        None => ("/", token),
+    };
+    let mut dir_path = String::from(dir);
+    dir_path.push('\0');
+    let mut candidates: Vec<String> = read_dir(dir_path.as_str())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+    candidates.sort();
+    let addition = match candidates.len() {
+        0 => String::new(),
+        // a unique name is completed with a space to start the next token
+        1 => format!("{} ", &candidates[0][prefix.len()..]),
+        _ => {
+            let mut common = candidates[0].as_str();
+            for candidate in candidates.iter() {
+                let len = common
+                    .char_indices()
+                    .zip(candidate.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(common.len().min(candidate.len()), |((i, _), _)| i);
+                common = &common[..len];
+            }
+            String::from(&common[prefix.len()..])
+        }
+    };
+    (addition, candidates)
+}
+
+/// Wait for all processes of a foreground pipeline, return the exit code of
+/// the last one, or the processes left when one of them is stopped
+fn wait_all(pids: &[isize]) -> Result<i32, Vec<isize>> {
+    let mut exit_code: i32 = 0;
+    let mut status = WaitStatus::Exited(0);
+    for (i, &pid) in pids.iter().enumerate() {
+        // a child which can not be waited for has already been reaped
+        if waitpid_options(pid, &mut status, WUNTRACED) != pid {
+            continue;
+        }
+        match status {
+            WaitStatus::Exited(code) => exit_code = code,
+            WaitStatus::Stopped(_) => return Err(pids[i..].to_vec()),
+        }
+    }
+    Ok(exit_code)
+}
+
+/// Fork a process for every stage of a pipeline connected by pipes, return their pids.
+/// With job control the pipeline gets a process group led by its first process
+fn spawn(process_arguments_list: &[ProcessArguments], job_control: bool) -> Vec<isize> {
+    // create pipes
+    let mut pipes_fd: Vec<[usize; 2]> = Vec::new();
+    if !process_arguments_list.is_empty() {
+        for _ in 0..process_arguments_list.len() - 1 {
+            let mut pipe_fd = [0usize; 2];
+            pipe(&mut pipe_fd);
+            pipes_fd.push(pipe_fd);
+        }
+    }
+    let mut children: Vec<_> = Vec::new();
+    for (i, process_argument) in process_arguments_list.iter().enumerate() {
+        let pid = fork();
+        // both the parent and the child (pid 0) set the group, whichever runs
+        // first, and pgid 0 makes the first process the leader
+        if job_control {
+            let pgid = children.first().map_or(0, |&leader| leader as usize);
+            setpgid(pid as usize, pgid);
+        }
+        if pid == 0 {
+            let input = &process_argument.input;
+            let output = &process_argument.output;
+            let args_copy = &process_argument.args_copy;
+            let args_addr = &process_argument.args_addr;
+            // redirect input
+            if !input.is_empty() {
+                let input_fd = open(input.as_str(), OpenFlags::RDONLY);
+                if input_fd == -1 {
+                    println!("Error when opening file {}", input);
+                    exit(-4);
+                }
+                let input_fd = input_fd as usize;
+                assert_eq!(dup2(input_fd, 0), 0);
+                close(input_fd);
+            }
+            // redirect output
+            if !output.is_empty() {
+                let mode = if process_argument.append {
+                    OpenFlags::APPEND
+                } else {
+                    OpenFlags::TRUNC
+                };
+                let output_fd = open(
+                    output.as_str(),
+                    OpenFlags::CREATE | OpenFlags::WRONLY | mode,
+                );
+                if output_fd == -1 {
+                    println!("Error when opening file {}", output);
+                    exit(-4);
+                }
+                let output_fd = output_fd as usize;
+                assert_eq!(dup2(output_fd, 1), 1);
+                close(output_fd);
+            }
+            // receive input from the previous process
+            if i > 0 {
+                let read_end = pipes_fd.get(i - 1).unwrap()[0];
+                assert_eq!(dup2(read_end, 0), 0);
+            }
+            // send output to the next process
+            if i < process_arguments_list.len() - 1 {
+                let write_end = pipes_fd.get(i).unwrap()[1];
+                assert_eq!(dup2(write_end, 1), 1);
+            }
+            // close all pipe ends inherited from the parent process
+            for pipe_fd in pipes_fd.iter() {
+                close(pipe_fd[0]);
+                close(pipe_fd[1]);
+            }
+            // execute new application
+            if execvp(args_copy[0].as_str(), args_addr.as_slice()) == -1 {
+                println!("Error when executing!");
+                exit(-4);
+            }
+            unreachable!();
+        } else {
+            children.push(pid);
+        }
+    }
+    for pipe_fd in pipes_fd.iter() {
+        close(pipe_fd[0]);
+        close(pipe_fd[1]);
+    }
+    children
+}
+
+/// A statement of a script
+enum Statement {
+    /// a command line, expanded when it runs
+    Command(String),
+    /// `if CONDITION` / `then` / `else` / `fi`, the condition is a command line
+    /// and succeeds if it exits with `0`
+    If {
+        condition: String,
+        then_branch: Vec<Statement>,
+        else_branch: Vec<Statement>,
+    },
+    /// `for NAME in WORDS` / `do` / `done`, the words are expanded once
+    /// before the loop
+    For {
+        name: String,
+        words: String,
+        body: Vec<Statement>,
+    },
+}
+
+/// Lines of a script with their line numbers, comments and blank lines skipped
+type ScriptLines<'a> = dyn Iterator<Item = (usize, &'a str)> + 'a;
+
+/// Split the first word of a line from the rest
+fn split_keyword(line: &str) -> (&str, &str) {
+    match line.split_once(char::is_whitespace) {
+        Some((keyword, rest)) => (keyword, rest.trim()),
+        None => (line, ""),
+    }
+}
+
+/// Strip `; KEYWORD` from the end of a line, return whether it is there
+fn strip_trailing_keyword<'a>(line: &'a str, keyword: &str) -> (&'a str, bool) {
+    match line
+        .strip_suffix(keyword)
+        .and_then(|rest| rest.trim_end().strip_suffix(';'))
+    {
+        Some(rest) => (rest.trim_end(), true),
+        None => (line, false),
+    }
+}
+
+/// Take the next line, which must be exactly `keyword`
+fn expect_keyword(lines: &mut ScriptLines, keyword: &str) -> Result<(), String> {
+    match lines.next() {
+        Some((_, line)) if line == keyword => Ok(()),
+        Some((number, line)) => Err(format!(
+            "line {}: expected `{}`, found `{}`",
+            number, keyword, line
+        )),
+        None => Err(format!("unexpected end of file, expected `{}`", keyword)),
+    }
+}
+
+/// Parse statements until a line which is one of `ends`, return the
+/// statements and the end found. With no `ends` parse to the end of the file
+fn parse_block(
+    lines: &mut ScriptLines,
+    ends: &[&'static str],
+) -> Result<(Vec<Statement>, &'static str), String> {
+    let mut statements = Vec::new();
+    while let Some((number, line)) = lines.next() {
+        if let Some(end) = ends.iter().find(|end| **end == line) {
+            return Ok((statements, end));
+        }
+        let (keyword, rest) = split_keyword(line);
+        match keyword {
+            "if" => {
+                let (condition, then) = strip_trailing_keyword(rest, "then");
+                if condition.is_empty() {
+                    return Err(format!("line {}: missing condition after `if`", number));
+                }
+                if !then {
+                    expect_keyword(lines, "then")?;
+                }
+                let (then_branch, end) = parse_block(lines, &["else", "fi"])?;
+                let else_branch = match end {
+                    "else" => parse_block(lines, &["fi"])?.0,
+                    _ => Vec::new(),
+                };
+                statements.push(Statement::If {
+                    condition: String::from(condition),
+                    then_branch,
+                    else_branch,
+                });
+            }
+            "for" => {
+                let (rest, do_found) = strip_trailing_keyword(rest, "do");
+                let (name, words) = split_keyword(rest);
+                let words = match split_keyword(words) {
+                    ("in", words) => words,
+                    _ => return Err(format!("line {}: expected `for NAME in WORDS`", number)),
+                };
+                if name.is_empty() || !name.chars().all(is_name_char) {
+                    return Err(format!("line {}: invalid loop variable `{}`", number, name));
+                }
+                if !do_found {
+                    expect_keyword(lines, "do")?;
+                }
+                let (body, _) = parse_block(lines, &["done"])?;
+                statements.push(Statement::For {
+                    name: String::from(name),
+                    words: String::from(words),
+                    body,
+                });
+            }
+            "then" | "else" | "fi" | "do" | "done" => {
+                return Err(format!("line {}: unexpected `{}`", number, keyword));
+            }
+            _ => statements.push(Statement::Command(String::from(line))),
+        }
+    }
+    match ends.first() {
+        Some(end) => Err(format!("unexpected end of file, expected `{}`", end)),
+        None => Ok((statements, "")),
+    }
+}
+
+/// Expand the words of a `for` loop like the arguments of a command
+fn expand_words(words: &str, status: i32) -> Result<Vec<String>, String> {
+    let mut expanded = Vec::new();
+    for token in tokenize(words, status)? {
+        match token {
+            Token::Word(word) => expanded.push(word),
+            Token::Pattern(pattern) => {
+                let paths = glob(pattern.as_str());
+                if paths.is_empty() {
+                    return Err(format!("no matches found: {}", pattern));
+                }
+                expanded.extend(paths);
+            }
+            _ => {
+                return Err(String::from(
+                    "Invalid command: operator in the words of `for`",
+                ))
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+/// Read the whole file at `path`, which ends with `\0`
+fn read_file(path: &str) -> Option<String> {
+    let fd = open(path, OpenFlags::RDONLY);
+    if fd < 0 {
+        return None;
+    }
+    let mut content = Vec::new();
+    let mut buf = [0u8; 512];
     loop {
-        let c = getchar();
-        match c {
-            LF | CR => {
-                println!("");
-                if !line.is_empty() {
-                    let splited: Vec<_> = line.as_str().split('|').collect();
-                    let process_arguments_list: Vec<_> = splited
-                        .iter()
-                        .map(|&cmd| ProcessArguments::new(cmd))
-                        .collect();
-                    let mut valid = true;
-                    for (i, process_args) in process_arguments_list.iter().enumerate() {
-                        if i == 0 {
-                            if !process_args.output.is_empty() {
-                                valid = false;
-                            }
-                        } else if i == process_arguments_list.len() - 1 {
-                            if !process_args.input.is_empty() {
-                                valid = false;
-                            }
-                        } else if !process_args.output.is_empty() || !process_args.input.is_empty()
+        let len = read(fd as usize, &mut buf);
+        if len <= 0 {
+            close(fd as usize);
+            return if len == 0 {
+                String::from_utf8(content).ok()
+            } else {
+                None
+            };
+        }
+        content.extend_from_slice(&buf[..len as usize]);
+    }
+}
+
+/// State of the shell kept between commands
+struct Shell {
+    jobs: Vec<Job>,
+    /// alias names and the text they stand for
+    aliases: BTreeMap<String, String>,
+    editor: Readline,
+    /// exit status of the last command, given by `$?`
+    status: i32,
+    /// whether pipelines get their own process groups and are put in the
+    /// foreground of the terminal, only for an interactive shell
+    job_control: bool,
+}
+
+impl Shell {
+    fn new() -> Self {
+        let mut editor = Readline::new(LINE_START);
+        editor.set_completer(complete);
+        Self {
+            jobs: Vec::new(),
+            aliases: BTreeMap::new(),
+            editor,
+            status: 0,
+            job_control: false,
+        }
+    }
+    /// Run the `jobs`, `fg`, `bg`, `history`, `export`, `unset`, `alias`,
+    /// `unalias`, `exit`, `halt`, `poweroff` and `reboot` builtins, return the
+    /// exit status or `None` if the command is not a builtin
+    fn run_builtin(&mut self, args: &[String]) -> Option<i32> {
+        let jobs = &mut self.jobs;
+        let mut status = 0;
+        match args[0].as_str() {
+            "alias\0" => {
+                if args.len() == 1 {
+                    for (name, text) in self.aliases.iter() {
+                        println!("alias {}='{}'", name, text);
+                    }
+                }
+                for arg in args[1..].iter() {
+                    let arg = arg.trim_end_matches('\0');
+                    match arg.split_once('=') {
+                        Some((name, text))
+                            if !name.is_empty() && !name.contains(TOKEN_SEPARATORS) =>
                         {
-                            valid = false;
+                            self.aliases.insert(String::from(name), String::from(text));
                         }
-                    }
-                    if process_arguments_list.len() == 1 {
-                        valid = true;
-                    }
-                    if !valid {
-                        println!("Invalid command: Inputs/Outputs cannot be correctly binded!");
-                    } else {
-                        // create pipes
-                        let mut pipes_fd: Vec<[usize; 2]> = Vec::new();
-                        if !process_arguments_list.is_empty() {
-                            for _ in 0..process_arguments_list.len() - 1 {
-                                let mut pipe_fd = [0usize; 2];
-                                pipe(&mut pipe_fd);
-                                pipes_fd.push(pipe_fd);
-                            }
+                        Some(_) => {
+                            println!("alias: invalid name: {}", arg);
+                            status = 1;
                         }
-                        let mut children: Vec<_> = Vec::new();
-                        for (i, process_argument) in process_arguments_list.iter().enumerate() {
-                            let pid = fork();
-                            if pid == 0 {
-                                let input = &process_argument.input;
-                                let output = &process_argument.output;
-                                let args_copy = &process_argument.args_copy;
-                                let args_addr = &process_argument.args_addr;
-                                // redirect input
-                                if !input.is_empty() {
-                                    let input_fd = open(input.as_str(), OpenFlags::RDONLY);
-                                    if input_fd == -1 {
-                                        println!("Error when opening file {}", input);
-                                        return -4;
-                                    }
-                                    let input_fd = input_fd as usize;
-                                    close(0);
-                                    assert_eq!(dup(input_fd), 0);
-                                    close(input_fd);
-                                }
-                                // redirect output
-                                if !output.is_empty() {
-                                    let output_fd = open(
-                                        output.as_str(),
-                                        OpenFlags::CREATE | OpenFlags::WRONLY,
-                                    );
-                                    if output_fd == -1 {
-                                        println!("Error when opening file {}", output);
-                                        return -4;
-                                    }
-                                    let output_fd = output_fd as usize;
-                                    close(1);
-                                    assert_eq!(dup(output_fd), 1);
-                                    close(output_fd);
-                                }
-                                // receive input from the previous process
-                                if i > 0 {
-                                    close(0);
-                                    let read_end = pipes_fd.get(i - 1).unwrap()[0];
-                                    assert_eq!(dup(read_end), 0);
-                                }
-                                // send output to the next process
-                                if i < process_arguments_list.len() - 1 {
-                                    close(1);
-                                    let write_end = pipes_fd.get(i).unwrap()[1];
-                                    assert_eq!(dup(write_end), 1);
-                                }
-                                // close all pipe ends inherited from the parent process
-                                for pipe_fd in pipes_fd.iter() {
-                                    close(pipe_fd[0]);
-                                    close(pipe_fd[1]);
-                                }
-                                // execute new application
-                                if exec(args_copy[0].as_str(), args_addr.as_slice()) == -1 {
-                                    println!("Error when executing!");
-                                    return -4;
-                                }
-                                unreachable!();
-                            } else {
-                                children.push(pid);
+                        None => match self.aliases.get(arg) {
+                            Some(text) => println!("alias {}='{}'", arg, text),
+                            None => {
+                                println!("alias: {}: not found", arg);
+                                status = 1;
                             }
+                        },
+                    }
+                }
+            }
+            "unalias\0" => {
+                for arg in args[1..].iter() {
+                    if self.aliases.remove(arg.trim_end_matches('\0')).is_none() {
+                        println!("unalias: {}: not found", arg.trim_end_matches('\0'));
+                        status = 1;
+                    }
+                }
+            }
+            "exit\0" => match args.get(1).map(|arg| arg.trim_end_matches('\0').parse()) {
+                None => {
+                    exit(0);
+                }
+                Some(Ok(code)) => {
+                    exit(code);
+                }
+                Some(Err(_)) => {
+                    println!("exit: numeric argument required");
+                    status = 1;
+                }
+            },
+            "halt\0" | "poweroff\0" | "reboot\0" => {
+                let cmd = match args[0].as_str() {
+                    "halt\0" => REBOOT_CMD_HALT,
+                    "poweroff\0" => REBOOT_CMD_POWER_OFF,
+                    _ => REBOOT_CMD_RESTART,
+                };
+                reboot(cmd);
+                println!("{}: not supported", args[0].trim_end_matches('\0'));
+                status = 1;
+            }
+            "export\0" => {
+                if args.len() == 1 {
+                    for (name, value) in vars() {
+                        println!("export {}={}", name, value);
+                    }
+                }
+                for arg in args[1..].iter() {
+                    let arg = arg.trim_end_matches('\0');
+                    match arg.split_once('=') {
+                        Some((name, value))
+                            if !name.is_empty() && name.chars().all(is_name_char) =>
+                        {
+                            setenv(name, value);
                         }
-                        for pipe_fd in pipes_fd.iter() {
-                            close(pipe_fd[0]);
-                            close(pipe_fd[1]);
-                        }
-                        let mut exit_code: i32 = 0;
-                        for pid in children.into_iter() {
-                            let exit_pid = waitpid(pid as usize, &mut exit_code);
-                            assert_eq!(pid, exit_pid);
-                            //println!("Shell: Process {} exited with code {}", pid, exit_code);
+                        _ => {
+                            println!("export: invalid assignment: {}", arg);
+                            status = 1;
                         }
                     }
-                    line.clear();
                 }
-                print!("{}", LINE_START);
             }
-            BS | DL => {
-                if !line.is_empty() {
-                    print!("{}", BS as char);
-                    print!(" ");
-                    print!("{}", BS as char);
-                    line.pop();
+            "unset\0" => {
+                for arg in args[1..].iter() {
+                    unsetenv(arg.trim_end_matches('\0'));
                 }
             }
-            _ => {
-                print!("{}", c as char);
-                line.push(c as char);
+            "history\0" => {
+                for (i, line) in self.editor.history().enumerate() {
+                    println!("{:>4}  {}", i + 1, line);
+                }
+            }
+            "jobs\0" => {
+                for job in jobs.iter() {
+                    let state = if job.stopped { "Stopped" } else { "Running" };
+                    println!("[{}] {}    {}", job.id, state, job.command);
+                }
+            }
+            "fg\0" | "bg\0" => {
+                let idx = match find_job(jobs, args.get(1)) {
+                    Ok(idx) => idx,
+                    Err(message) => {
+                        println!("{}: {}", &args[0][..2], message);
+                        return Some(1);
+                    }
+                };
+                // resume the job in case it was stopped
+                for &pid in jobs[idx].pids.iter() {
+                    kill(pid as usize, SIGCONT);
+                }
+                jobs[idx].stopped = false;
+                if args[0].as_str() == "bg\0" {
+                    println!("[{}] {} &", jobs[idx].id, jobs[idx].command);
+                } else {
+                    let job = jobs.remove(idx);
+                    println!("{}", job.command);
+                    status = self.foreground(job);
+                }
             }
+            _ => return None,
+        }
+        Some(status)
+    }
+
+    /// Replace an alias at the start of every stage of a pipeline with the
+    /// tokens of its text, which is not searched for aliases again
+    fn expand_aliases(&self, tokens: Vec<Token>) -> Result<Vec<Token>, String> {
+        let mut expanded = Vec::new();
+        let mut command_start = true;
+        for token in tokens {
+            let alias = match &token {
+                Token::Word(word) if command_start => self.aliases.get(word),
+                _ => None,
+            };
+            command_start = token == Token::Pipe;
+            match alias {
+                Some(text) => expanded.extend(tokenize(text, self.status)?),
+                None => expanded.push(token),
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// Parse and run a command line, return its exit status. The status of the
+    /// previous command is kept for a line with nothing to run
+    fn run_line(&mut self, line: &str) -> i32 {
+        let mut tokens = match tokenize(line, self.status) {
+            Ok(tokens) => tokens,
+            Err(message) => {
+                println!("{}", message);
+                return 1;
+            }
+        };
+        // `time` before a pipeline measures how long it runs
+        let timed = tokens.first() == Some(&Token::Word(String::from("time")));
+        if timed {
+            tokens.remove(0);
+        }
+        let tokens = match self.expand_aliases(tokens) {
+            Ok(tokens) => tokens,
+            Err(message) => {
+                println!("{}", message);
+                return 1;
+            }
+        };
+        if !timed {
+            return self.run_tokens(tokens, line);
+        }
+        let start = get_time();
+        let status = self.run_tokens(tokens, line);
+        let elapsed = get_time() - start;
+        println!("real    {}.{:03}s", elapsed / 1000, elapsed % 1000);
+        status
+    }
+
+    fn next_job_id(&self) -> usize {
+        self.jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1
+    }
+
+    /// Give the terminal to a pipeline, wait for it and return its exit
+    /// status. A stopped pipeline becomes a stopped job, keeping its id if it
+    /// already was one
+    fn foreground(&mut self, mut job: Job) -> i32 {
+        if self.job_control {
+            tcsetpgrp(0, job.pgid);
+        }
+        let result = wait_all(&job.pids);
+        if self.job_control {
+            tcsetpgrp(0, getpgid(0) as usize);
+        }
+        match result {
+            Ok(exit_code) => exit_code,
+            Err(pids) => {
+                if job.id == 0 {
+                    job.id = self.next_job_id();
+                }
+                println!("[{}] Stopped    {}", job.id, job.command);
+                job.pids = pids;
+                job.stopped = true;
+                self.jobs.push(job);
+                self.jobs.sort_by_key(|job| job.id);
+                STOPPED_STATUS
+            }
+        }
+    }
+
+    /// Run the tokens of a command line, `line` is shown for a background job
+    fn run_tokens(&mut self, mut tokens: Vec<Token>, line: &str) -> i32 {
+        // a trailing `&` runs the command in the background
+        let background = tokens.last() == Some(&Token::Background);
+        if background {
+            tokens.pop();
+        }
+        if tokens.contains(&Token::Background) {
+            println!("Invalid command: & is only allowed at the end!");
+            return 1;
+        }
+        let process_arguments_list: Vec<_> = match tokens
+            .split(|token| *token == Token::Pipe)
+            .map(ProcessArguments::new)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(list) => list,
+            Err(message) => {
+                println!("{}", message);
+                return 1;
+            }
+        };
+        let mut valid = true;
+        for (i, process_args) in process_arguments_list.iter().enumerate() {
+            if i == 0 {
+                if !process_args.output.is_empty() {
+                    valid = false;
+                }
+            } else if i == process_arguments_list.len() - 1 {
+                if !process_args.input.is_empty() {
+                    valid = false;
+                }
+            } else if !process_args.output.is_empty() || !process_args.input.is_empty() {
+                valid = false;
+            }
+        }
+        if process_arguments_list.len() == 1 {
+            valid = true;
+        }
+        if process_arguments_list.len() == 1 && process_arguments_list[0].args_copy.is_empty() {
+            // a line of spaces, nothing to run
+            self.status
+        } else if process_arguments_list
+            .iter()
+            .any(|process_args| process_args.args_copy.is_empty())
+        {
+            // e.g. `cat file |` or `cat file || wc`
+            println!("Invalid command: empty command in pipeline!");
+            1
+        } else if !valid {
+            println!("Invalid command: Inputs/Outputs cannot be correctly binded!");
+            1
+        } else if let Some(status) = (process_arguments_list.len() == 1 && !background)
+            .then(|| self.run_builtin(&process_arguments_list[0].args_copy))
+            .flatten()
+        {
+            status
+        } else {
+            let children = spawn(&process_arguments_list, self.job_control);
+            let mut job = Job {
+                id: 0,
+                pgid: children[0] as usize,
+                pids: children,
+                command: String::from(line.trim()),
+                stopped: false,
+            };
+            if background {
+                job.id = self.next_job_id();
+                println!("[{}] {}", job.id, job.pids.last().unwrap());
+                self.jobs.push(job);
+                0
+            } else {
+                self.foreground(job)
+            }
+        }
+    }
+
+    /// Run the statements of a script. The loop variable of `for` is set in the environment
+    fn run_statements(&mut self, statements: &[Statement]) {
+        for statement in statements.iter() {
+            match statement {
+                Statement::Command(line) => self.status = self.run_line(line),
+                Statement::If {
+                    condition,
+                    then_branch,
+                    else_branch,
+                } => {
+                    self.status = self.run_line(condition);
+                    let branch = if self.status == 0 {
+                        then_branch
+                    } else {
+                        else_branch
+                    };
+                    self.run_statements(branch);
+                }
+                Statement::For { name, words, body } => match expand_words(words, self.status) {
+                    Ok(words) => {
+                        for word in words.iter() {
+                            setenv(name, word);
+                            self.run_statements(body);
+                        }
+                    }
+                    Err(message) => {
+                        println!("{}", message);
+                        self.status = 1;
+                    }
+                },
+            }
+            reap_jobs(&mut self.jobs);
+        }
+    }
+
+    /// Run the commands in a script file, `None` if the file can not be read
+    fn run_script(&mut self, path: &str) -> Option<()> {
+        let mut file = String::from(path);
+        file.push('\0');
+        let script = read_file(file.as_str())?;
+        let mut lines = script
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        match parse_block(&mut lines, &[]) {
+            Ok((statements, _)) => self.run_statements(&statements),
+            Err(message) => {
+                println!("{}: {}", path, message);
+                self.status = 2;
+            }
+        }
+        Some(())
+    }
+
+    /// Show the prompt in the PS1 environment variable
+    fn update_prompt(&mut self) {
+        self.editor.set_prompt(getenv("PS1").as_deref().unwrap_or(LINE_START));
+    }
+}
+
+/// Define aliases and the prompt from the rc files which exist, first
+/// `/etc/shellrc` and then `.shellrc` in HOME
+fn load_rc_files(shell: &mut Shell) {
+    let mut home_rc = String::from(getenv("HOME").as_deref().unwrap_or("/").trim_end_matches('/'));
+    home_rc.push_str("/.shellrc");
+    for path in [SYSTEM_RC_FILE, home_rc.as_str()] {
+        shell.run_script(path);
+    }
+    shell.update_prompt();
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    let mut shell = Shell::new();
+    let action = SignalAction {
+        handler: on_sigchld as fn() as usize,
+        ..Default::default()
+    };
+    sigaction(SIGCHLD, Some(&action), None);
+    // `user_shell SCRIPT` runs a script instead of reading commands
+    if argc > 1 {
+        if shell.run_script(argv[1]).is_none() {
+            println!("{}: cannot read the script", argv[1]);
+            return -1;
+        }
+        return shell.status;
+    }
+    println!("Rust user shell");
+    // Ctrl-C and Ctrl-Z only reach the group in the foreground, not the shell
+    shell.job_control = true;
+    load_rc_files(&mut shell);
+    loop {
+        let line = shell.editor.read_line();
+        if !line.is_empty() {
+            shell.editor.add_history(line.as_str());
+            shell.status = shell.run_line(line.as_str());
+            shell.update_prompt();
         }
+        reap_jobs(&mut shell.jobs);
     }
 }
@@ -5,7 +5,7 @@
 extern crate user_lib;
 
 // not in SUCC_TESTS & FAIL_TESTS
-// count_lines, infloop, user_shell, usertests
+// count_lines, fb_demo, infloop, mini_gdb, paint, preempt_latency, priority_inversion, stride, user_shell, usertests
 
 // item of TESTS : app_name(argv_0), argv_1, argv_2, argv_3, exit_code
 static SUCC_TESTS: &[(&str, &str, &str, &str, i32)] = &[
@@ -13,6 +13,7 @@ static SUCC_TESTS: &[(&str, &str, &str, &str, i32)] = &[
     ("cat\0", "filea\0", "\0", "\0", 0),
     ("cmdline_args\0", "1\0", "2\0", "3\0", 0),
     ("exit\0", "\0", "\0", "\0", 0),
+    ("fd_offset_test\0", "\0", "\0", "\0", 0),
     ("fantastic_text\0", "\0", "\0", "\0", 0),
     ("forktest_simple\0", "\0", "\0", "\0", 0),
     ("forktest\0", "\0", "\0", "\0", 0),
@@ -34,11 +35,52 @@ static SUCC_TESTS: &[(&str, &str, &str, &str, i32)] = &[
 
 static FAIL_TESTS: &[(&str, &str, &str, &str, i32)] = &[
     ("stack_overflow\0", "\0", "\0", "\0", -11),
+    ("inject_code\0", "\0", "\0", "\0", -11),
+    ("write_text\0", "\0", "\0", "\0", -11),
     ("priv_csr\0", "\0", "\0", "\0", -4),
     ("priv_inst\0", "\0", "\0", "\0", -4),
     ("store_fault\0", "\0", "\0", "\0", -11),
 ];
 
+/// Tests needing kernel features which are not in this tree, run and reported
+/// but not counted towards the result
+static FEATURE_TESTS: &[(&str, &str, &str, &str, i32)] = &[
+    ("affinity_test\0", "\0", "\0", "\0", 0),
+    ("clock_test\0", "\0", "\0", "\0", 0),
+    ("condvar_test\0", "\0", "\0", "\0", 0),
+    ("dmesg\0", "\0", "\0", "\0", 0),
+    ("epoll_test\0", "\0", "\0", "\0", 0),
+    ("fifo_test\0", "\0", "\0", "\0", 0),
+    ("getrandom_test\0", "\0", "\0", "\0", 0),
+    ("heap_grow_test\0", "\0", "\0", "\0", 0),
+    ("itimer_test\0", "\0", "\0", "\0", 0),
+    ("mmap_file_test\0", "\0", "\0", "\0", 0),
+    ("mmap_test\0", "\0", "\0", "\0", 0),
+    ("mprotect_test\0", "\0", "\0", "\0", 0),
+    ("mpsc_sem\0", "\0", "\0", "\0", 0),
+    ("mq_test\0", "\0", "\0", "\0", 0),
+    ("nanosleep_test\0", "\0", "\0", "\0", 0),
+    ("nice_test\0", "\0", "\0", "\0", 0),
+    ("pgrp_test\0", "\0", "\0", "\0", 0),
+    ("phil_din_mutex\0", "\0", "\0", "\0", 0),
+    ("pipe_nonblock_test\0", "\0", "\0", "\0", 0),
+    ("poll_test\0", "\0", "\0", "\0", 0),
+    ("posix_timer_test\0", "\0", "\0", "\0", 0),
+    ("ptrace_test\0", "\0", "\0", "\0", 0),
+    ("race_adder_mutex\0", "\0", "\0", "\0", 0),
+    ("setprio_test\0", "\0", "\0", "\0", 0),
+    ("shm_test\0", "\0", "\0", "\0", 0),
+    ("sigchld_test\0", "\0", "\0", "\0", 0),
+    ("stack_grow_test\0", "\0", "\0", "\0", 0),
+    ("termios_test\0", "\0", "\0", "\0", 0),
+    ("threads\0", "\0", "\0", "\0", 0),
+    ("threads_arg\0", "\0", "\0", "\0", 0),
+    ("uname\0", "-a\0", "\0", "\0", 0),
+    ("mmap_readonly\0", "\0", "\0", "\0", -11),
+    ("mmap_unmapped\0", "\0", "\0", "\0", -11),
+    ("mprotect_guard\0", "\0", "\0", "\0", -11),
+];
+
 use user_lib::{exec, fork, waitpid};
 
 fn run_tests(tests: &[(&str, &str, &str, &str, i32)]) -> i32 {
@@ -100,6 +142,12 @@ fn run_tests(tests: &[(&str, &str, &str, &str, i32)]) -> i32 {
 pub fn main() -> i32 {
     let succ_num = run_tests(SUCC_TESTS);
     let err_num = run_tests(FAIL_TESTS);
+    let feature_num = run_tests(FEATURE_TESTS);
+    println!(
+        "{} of {} apps needing kernel features not in this tree run correctly",
+        feature_num,
+        FEATURE_TESTS.len()
+    );
     if succ_num == SUCC_TESTS.len() as i32 && err_num == FAIL_TESTS.len() as i32 {
         println!(
             "{} of sueecssed apps, {} of failed apps run correctly. \nUsertests passed!",
@@ -0,0 +1,14 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("It should trigger segmentation fault!");
+    // the code segment must not be writable
+    let text = main as fn() -> i32 as usize as *mut u32;
+    unsafe { text.write_volatile(0) };
+    0
+}
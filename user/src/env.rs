@@ -0,0 +1,95 @@
+//! Environment variables of the current process, inherited through exec
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use spin::Mutex;
+
+/// `NAME=value` strings. Values are handed out as copies, so changing a
+/// variable never invalidates a value read before
+static ENVIRON: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Load the environment from the `\0`-terminated strings in the null-terminated
+/// array at `envp`, an address of `0` means an empty environment
+pub(crate) fn init(envp: usize) {
+    if envp == 0 {
+        return;
+    }
+    let mut environ = ENVIRON.lock();
+    for i in 0.. {
+        let str_start =
+            unsafe { ((envp + i * core::mem::size_of::<usize>()) as *const usize).read_volatile() };
+        if str_start == 0 {
+            break;
+        }
+        let len = (0usize..)
+            .find(|i| unsafe { ((str_start + *i) as *const u8).read_volatile() == 0 })
+            .unwrap();
+        let bytes = unsafe { core::slice::from_raw_parts(str_start as *const u8, len) };
+        if let Ok(var) = core::str::from_utf8(bytes) {
+            if var.contains('=') {
+                environ.push(String::from(var));
+            }
+        }
+    }
+}
+
+/// Find the position of a variable in the environment
+fn position(environ: &[String], name: &str) -> Option<usize> {
+    environ
+        .iter()
+        .position(|var| var.split_once('=').map(|(n, _)| n) == Some(name))
+}
+
+/// Get the value of an environment variable
+pub fn getenv(name: &str) -> Option<String> {
+    let environ = ENVIRON.lock();
+    position(&environ, name).map(|idx| String::from(&environ[idx][name.len() + 1..]))
+}
+
+/// Set an environment variable, a name containing `=` or `\0` is rejected
+pub fn setenv(name: &str, value: &str) -> bool {
+    if name.is_empty() || name.contains('=') || name.contains('\0') || value.contains('\0') {
+        return false;
+    }
+    let mut var = String::from(name);
+    var.push('=');
+    var.push_str(value);
+    let mut environ = ENVIRON.lock();
+    match position(&environ, name) {
+        Some(idx) => environ[idx] = var,
+        None => environ.push(var),
+    }
+    true
+}
+
+/// Remove an environment variable
+pub fn unsetenv(name: &str) {
+    let mut environ = ENVIRON.lock();
+    if let Some(idx) = position(&environ, name) {
+        environ.remove(idx);
+    }
+}
+
+/// Get all environment variables as `(name, value)` pairs
+pub fn vars() -> Vec<(String, String)> {
+    ENVIRON
+        .lock()
+        .iter()
+        .filter_map(|var| var.split_once('='))
+        .map(|(name, value)| (String::from(name), String::from(value)))
+        .collect()
+}
+
+/// Build the `\0`-terminated `NAME=value` strings of the environment, which
+/// must be kept alive while their addresses are used
+pub(crate) fn envp_strings() -> Vec<String> {
+    ENVIRON
+        .lock()
+        .iter()
+        .map(|var| {
+            let mut var = var.clone();
+            var.push('\0');
+            var
+        })
+        .collect()
+}
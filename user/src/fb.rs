@@ -0,0 +1,107 @@
+//! Drawing on the screen through the framebuffer device `/dev/fb0`
+
+use crate::{close, ioctl, mmap_file, msync, munmap, open, MapFlags, OpenFlags, ProtFlags};
+
+/// The framebuffer of the first display
+pub const FB_PATH: &str = "/dev/fb0\0";
+/// `ioctl` command storing the `FbInfo` of a framebuffer device at `arg`
+pub const FBIOGET_INFO: usize = 0x4600;
+
+/// Mode of a framebuffer
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FbInfo {
+    /// visible pixels in a row
+    pub width: u32,
+    /// visible rows
+    pub height: u32,
+    /// always 32, a pixel is a `0x00RRGGBB` word
+    pub bits_per_pixel: u32,
+    /// bytes from the start of a row to the start of the next one
+    pub stride: u32,
+}
+
+/// Pack a color into a pixel
+pub fn rgb(r: u8, g: u8, b: u8) -> u32 {
+    (r as u32) << 16 | (g as u32) << 8 | b as u32
+}
+
+/// A framebuffer mapped into the process, unmapped and closed when dropped
+pub struct Framebuffer {
+    fd: usize,
+    info: FbInfo,
+    pixels: &'static mut [u32],
+}
+
+impl Framebuffer {
+    /// Open and map `/dev/fb0`, `None` if there is no display
+    pub fn open() -> Option<Self> {
+        let fd = open(FB_PATH, OpenFlags::RDWR);
+        if fd < 0 {
+            return None;
+        }
+        let fd = fd as usize;
+        let mut info = FbInfo::default();
+        let len = match ioctl(fd, FBIOGET_INFO, &mut info as *mut _ as usize) {
+            0 => (info.stride * info.height) as usize,
+            _ => 0,
+        };
+        let addr = match len {
+            0 => -1,
+            len => mmap_file(
+                fd,
+                0,
+                len,
+                ProtFlags::READ | ProtFlags::WRITE,
+                MapFlags::SHARED,
+            ),
+        };
+        if addr < 0 {
+            close(fd);
+            return None;
+        }
+        let pixels = unsafe { core::slice::from_raw_parts_mut(addr as *mut u32, len / 4) };
+        Some(Self { fd, info, pixels })
+    }
+    pub fn info(&self) -> FbInfo {
+        self.info
+    }
+    pub fn width(&self) -> usize {
+        self.info.width as usize
+    }
+    pub fn height(&self) -> usize {
+        self.info.height as usize
+    }
+    /// Set a pixel, nothing is drawn outside of the screen
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: u32) {
+        if x < self.width() && y < self.height() {
+            let row = self.info.stride as usize / 4;
+            self.pixels[y * row + x] = color;
+        }
+    }
+    /// Fill a rectangle with its top left corner at `x` and `y`, clipped to the screen
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: u32) {
+        let row = self.info.stride as usize / 4;
+        let x_end = (x + width).min(self.width());
+        for y in y..(y + height).min(self.height()) {
+            if x < x_end {
+                self.pixels[y * row + x..y * row + x_end].fill(color);
+            }
+        }
+    }
+    /// Fill the whole screen
+    pub fn clear(&mut self, color: u32) {
+        self.fill_rect(0, 0, self.width(), self.height(), color);
+    }
+    /// Show what has been drawn, the device only updates the screen when asked
+    pub fn flush(&self) -> isize {
+        msync(self.pixels.as_ptr() as usize, self.pixels.len() * 4)
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        munmap(self.pixels.as_ptr() as usize, self.pixels.len() * 4);
+        close(self.fd);
+    }
+}
@@ -0,0 +1,118 @@
+//! Wildcard patterns matching file names
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::read_dir;
+
+/// A part of a parsed pattern
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Piece {
+    /// a literal character
+    Char(char),
+    /// `?`, any one character
+    Any,
+    /// `*`, any characters
+    Many,
+}
+
+fn parse(pattern: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        pieces.push(match c {
+            '*' => Piece::Many,
+            '?' => Piece::Any,
+            // a trailing backslash stands for itself
+            '\\' => Piece::Char(chars.next().unwrap_or('\\')),
+            c => Piece::Char(c),
+        });
+    }
+    pieces
+}
+
+/// Whether a name matches a pattern, where `*` matches any characters, `?`
+/// matches one character and `\` makes the next character literal. A
+/// leading `.` in the name must be matched literally
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pieces = parse(pattern);
+    let name: Vec<char> = name.chars().collect();
+    if name.first() == Some(&'.') && pieces.first() != Some(&Piece::Char('.')) {
+        return false;
+    }
+    let (mut p, mut n) = (0, 0);
+    // position after the last `*` and the name position it is tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pieces.get(p) {
+            Some(Piece::Many) => {
+                p += 1;
+                backtrack = Some((p, n));
+                continue;
+            }
+            Some(Piece::Any) => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            Some(Piece::Char(c)) if *c == name[n] => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            _ => {}
+        }
+        // let the last `*` take one more character
+        match backtrack {
+            Some((star_p, star_n)) => {
+                backtrack = Some((star_p, star_n + 1));
+                p = star_p;
+                n = star_n + 1;
+            }
+            None => return false,
+        }
+    }
+    pieces[p..].iter().all(|piece| *piece == Piece::Many)
+}
+
+/// Whether a pattern contains an unescaped wildcard
+pub fn is_pattern(pattern: &str) -> bool {
+    parse(pattern)
+        .iter()
+        .any(|piece| !matches!(piece, Piece::Char(_)))
+}
+
+/// Get the sorted paths matching a pattern. Only the last component may
+/// contain wildcards, a relative pattern is matched in the root directory
+pub fn glob(pattern: &str) -> Vec<String> {
+    let (dir, name_pattern) = match pattern.rfind('/') {
+        Some(i) => (&pattern[..=i], &pattern[i + 1..]),
+        None => ("", pattern),
+    };
+    let dir: String = parse(dir)
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Char(c) => c,
+            Piece::Any => '?',
+            Piece::Many => '*',
+        })
+        .collect();
+    let mut dir_path = if dir.is_empty() {
+        String::from("/")
+    } else {
+        dir.clone()
+    };
+    dir_path.push('\0');
+    let mut paths: Vec<String> = read_dir(dir_path.as_str())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| matches(name_pattern, name))
+        .map(|name| {
+            let mut path = dir.clone();
+            path.push_str(&name);
+            path
+        })
+        .collect();
+    paths.sort();
+    paths
+}
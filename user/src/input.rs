@@ -0,0 +1,86 @@
+//! Reading key and pointer events from the input device `/dev/input/event0`
+
+use crate::{close, open, read, OpenFlags};
+
+/// The device of the keyboard and mouse
+pub const INPUT_PATH: &str = "/dev/input/event0\0";
+
+/// Event type ending a group of events which happened together
+pub const EV_SYN: u16 = 0x00;
+/// Event type of a key or button, `value` is 1 when pressed, 0 when released
+/// and 2 when repeated
+pub const EV_KEY: u16 = 0x01;
+/// Event type of a relative pointer move, `value` is the distance
+pub const EV_REL: u16 = 0x02;
+
+/// Codes of `EV_REL` events
+pub const REL_X: u16 = 0x00;
+pub const REL_Y: u16 = 0x01;
+pub const REL_WHEEL: u16 = 0x08;
+
+/// Codes of `EV_KEY` events, the values follow Linux's `KEY_*` and `BTN_*`
+pub const KEY_ESC: u16 = 1;
+pub const KEY_Q: u16 = 16;
+pub const KEY_ENTER: u16 = 28;
+pub const KEY_C: u16 = 46;
+pub const KEY_SPACE: u16 = 57;
+pub const KEY_UP: u16 = 103;
+pub const KEY_LEFT: u16 = 105;
+pub const KEY_RIGHT: u16 = 106;
+pub const KEY_DOWN: u16 = 108;
+pub const BTN_LEFT: u16 = 0x110;
+pub const BTN_RIGHT: u16 = 0x111;
+
+/// An event read from the device, the layout follows Linux's `struct input_event`
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    /// time of the event since boot
+    pub sec: u64,
+    pub usec: u64,
+    /// `EV_SYN`, `EV_KEY` or `EV_REL`
+    pub kind: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+/// The input device, closed when dropped
+pub struct InputDevice {
+    fd: usize,
+}
+
+impl InputDevice {
+    /// Open `/dev/input/event0`, `None` if there is no input device
+    pub fn open() -> Option<Self> {
+        let fd = open(INPUT_PATH, OpenFlags::RDONLY);
+        if fd < 0 {
+            return None;
+        }
+        Some(Self { fd: fd as usize })
+    }
+    /// The fd of the device, to wait for events with `poll`
+    pub fn fd(&self) -> usize {
+        self.fd
+    }
+    /// Read the next event, waiting for one unless the fd was made
+    /// non-blocking with `set_nonblocking`. `None` if no event could be read
+    pub fn read_event(&mut self) -> Option<InputEvent> {
+        let mut event = InputEvent::default();
+        let buf = unsafe {
+            core::slice::from_raw_parts_mut(
+                &mut event as *mut _ as *mut u8,
+                core::mem::size_of::<InputEvent>(),
+            )
+        };
+        match read(self.fd, buf) {
+            len if len as usize == buf.len() => Some(event),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for InputDevice {
+    fn drop(&mut self) {
+        close(self.fd);
+    }
+}
@@ -9,20 +9,61 @@ pub mod console;
 mod log;
 mod lang_items;
 mod syscall;
+mod env;
+pub mod fb;
+pub mod glob;
+pub mod input;
+pub mod net;
+pub mod rand;
+pub mod readline;
+pub mod term;
 extern crate alloc;
 #[macro_use]
 extern crate bitflags;
 
+use alloc::string::String;
 use alloc::vec::Vec;
 use buddy_system_allocator::LockedHeap;
+use core::alloc::{GlobalAlloc, Layout};
 use syscall::*;
+pub use env::{getenv, setenv, unsetenv, vars};
 
 const USER_HEAP_SIZE: usize = 0x4000;
+/// The least the heap grows by when it runs out
+const USER_HEAP_GROW_SIZE: usize = 0x4000;
 
 static mut HEAP_SPACE: [u8; USER_HEAP_SIZE] = [0; USER_HEAP_SIZE];
 
+/// A heap starting in `HEAP_SPACE` which moves the program break to get
+/// more memory when an allocation fails
+struct GrowableHeap(LockedHeap);
+
+unsafe impl GlobalAlloc for GrowableHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.0.alloc(layout);
+        if !ptr.is_null() {
+            return ptr;
+        }
+        // the buddy allocator hands out blocks of the size rounded up to a power
+        // of two and aligned to it, such a block surely fits in twice the size
+        let size = layout.size().max(layout.align()).next_power_of_two() * 2;
+        let size = (size.max(USER_HEAP_GROW_SIZE) + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+        let start = sbrk(size as isize);
+        if start < 0 {
+            return core::ptr::null_mut();
+        }
+        let start = start as usize;
+        self.0.lock().add_to_heap(start, start + size);
+        self.0.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+}
+
 #[global_allocator]
-static HEAP: LockedHeap = LockedHeap::empty();
+static HEAP: GrowableHeap = GrowableHeap(LockedHeap::empty());
 
 #[alloc_error_handler]
 pub fn handle_alloc_error(layout: core::alloc::Layout) -> ! {
@@ -31,9 +72,9 @@ pub fn handle_alloc_error(layout: core::alloc::Layout) -> ! {
 
 #[no_mangle]
 #[link_section = ".text.entry"]
-pub extern "C" fn _start(argc: usize, argv: usize) -> ! {
+pub extern "C" fn _start(argc: usize, argv: usize, envp: usize) -> ! {
     unsafe {
-        HEAP.lock()
+        HEAP.0.lock()
             .init(HEAP_SPACE.as_ptr() as usize, USER_HEAP_SIZE);
     }
     let mut v: Vec<&'static str> = Vec::new();
@@ -50,6 +91,7 @@ pub extern "C" fn _start(argc: usize, argv: usize) -> ! {
             .unwrap()
         )
     }
+    env::init(envp);
     exit(main(argc, v.as_slice()));
     panic!("Unreachable after sys_exit!");
 }
@@ -67,6 +109,22 @@ bitflags! {
         const RDWR = 1 << 1;
         const CREATE = 1 << 9;
         const TRUNC = 1 << 10;
+        /// every write goes to the end of the file
+        const APPEND = 1 << 11;
+        /// reads and writes which would block fail with `-EAGAIN` instead
+        const NONBLOCK = 1 << 12;
+    }
+}
+
+bitflags! {
+    /// flags of a mount, the values follow Linux's `MS_*`
+    pub struct MountFlags: u32 {
+        /// files can not be written
+        const RDONLY = 1 << 0;
+        /// files can not be executed
+        const NOEXEC = 1 << 3;
+        /// make a directory visible at another path, fstype is ignored
+        const BIND = 1 << 12;
     }
 }
 
@@ -74,6 +132,272 @@ pub fn dup(fd: usize) -> isize {
     sys_dup(fd)
 }
 
+/// Duplicate `oldfd` onto `newfd`, closing the file `newfd` referred to
+/// first. Return `newfd`, or -1 if `oldfd` is not open or equals `newfd`.
+/// `flags` is reserved and must be 0
+pub fn dup3(oldfd: usize, newfd: usize, flags: u32) -> isize {
+    sys_dup3(oldfd, newfd, flags)
+}
+
+/// Like `dup3` without flags, but duplicating an fd onto itself does nothing
+pub fn dup2(oldfd: usize, newfd: usize) -> isize {
+    if oldfd == newfd {
+        return newfd as isize;
+    }
+    sys_dup3(oldfd, newfd, 0)
+}
+
+/// Error returned by a sleep interrupted by a signal, negated
+pub const EINTR: isize = 4;
+/// Error returned by a non-blocking read or write which would block, negated
+pub const EAGAIN: isize = 11;
+/// Error returned by a wait whose timeout expired, negated
+pub const ETIMEDOUT: isize = 110;
+
+/// `fcntl` command duplicating the fd onto the lowest free fd not below `arg`
+pub const F_DUPFD: usize = 0;
+/// `fcntl` command returning the `OpenFlags` of the fd
+pub const F_GETFL: usize = 3;
+/// `fcntl` command setting the `OpenFlags` in `arg`, only `NONBLOCK` can be changed
+pub const F_SETFL: usize = 4;
+
+pub fn fcntl(fd: usize, cmd: usize, arg: usize) -> isize {
+    sys_fcntl(fd, cmd, arg)
+}
+
+/// Turn non-blocking mode of an fd on or off
+pub fn set_nonblocking(fd: usize, nonblocking: bool) -> isize {
+    let flags = sys_fcntl(fd, F_GETFL, 0);
+    if flags < 0 {
+        return flags;
+    }
+    let mut flags = OpenFlags::from_bits_truncate(flags as u32);
+    flags.set(OpenFlags::NONBLOCK, nonblocking);
+    sys_fcntl(fd, F_SETFL, flags.bits as usize)
+}
+
+/// `ioctl` command making a terminal the controlling terminal of the calling
+/// session leader, with the caller's group in the foreground
+pub const TIOCSCTTY: usize = 0x540e;
+/// `ioctl` command storing the foreground process group of a terminal in the
+/// `usize` pointed to by `arg`
+pub const TIOCGPGRP: usize = 0x540f;
+/// `ioctl` command making the process group pointed to by `arg` the
+/// foreground group of a terminal, which gets the signals typed on it
+pub const TIOCSPGRP: usize = 0x5410;
+
+pub fn ioctl(fd: usize, cmd: usize, arg: usize) -> isize {
+    sys_ioctl(fd, cmd, arg)
+}
+
+/// Return the foreground process group of the terminal `fd`, or -1 if it is
+/// not the controlling terminal of the caller
+pub fn tcgetpgrp(fd: usize) -> isize {
+    let mut pgid: usize = 0;
+    match sys_ioctl(fd, TIOCGPGRP, &mut pgid as *mut _ as usize) {
+        0 => pgid as isize,
+        err => err,
+    }
+}
+
+/// Make `pgid`, a group of the caller's session, the foreground process group
+/// of its controlling terminal `fd`
+pub fn tcsetpgrp(fd: usize, pgid: usize) -> isize {
+    sys_ioctl(fd, TIOCSPGRP, &pgid as *const _ as usize)
+}
+
+/// `ioctl` command storing the `Termios` of a terminal at `arg`
+pub const TCGETS: usize = 0x5401;
+/// `ioctl` command changing the `Termios` of a terminal to the one at `arg`
+pub const TCSETS: usize = 0x5402;
+
+bitflags! {
+    /// input modes of a terminal, the values follow Linux's `termios.c_iflag`
+    pub struct InputModes: u32 {
+        /// translate a carriage return into a newline
+        const ICRNL = 0o400;
+    }
+}
+
+bitflags! {
+    /// local modes of a terminal, the values follow Linux's `termios.c_lflag`
+    pub struct LocalModes: u32 {
+        /// the interrupt and suspend characters send `SIGINT` and `SIGTSTP`
+        const ISIG = 0o1;
+        /// canonical mode, input is read a line at a time after erase and
+        /// kill characters have edited it
+        const ICANON = 0o2;
+        /// echo the characters typed
+        const ECHO = 0o10;
+        /// erase the character before the cursor on screen for the erase character
+        const ECHOE = 0o20;
+    }
+}
+
+/// Number of control characters in `Termios`
+pub const NCCS: usize = 19;
+/// Index of the interrupt character, Ctrl-C, in `Termios::cc`
+pub const VINTR: usize = 0;
+/// Index of the erase character, Backspace, in `Termios::cc`
+pub const VERASE: usize = 2;
+/// Index of the kill character erasing the line, Ctrl-U, in `Termios::cc`
+pub const VKILL: usize = 3;
+/// Index of the end of file character, Ctrl-D, in `Termios::cc`
+pub const VEOF: usize = 4;
+/// Index of the least number of bytes a read returns out of canonical mode
+pub const VMIN: usize = 6;
+/// Index of the suspend character, Ctrl-Z, in `Termios::cc`
+pub const VSUSP: usize = 10;
+
+/// Settings of a terminal, the layout follows Linux's `struct termios`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Termios {
+    pub iflag: InputModes,
+    /// output modes, not used by the console
+    pub oflag: u32,
+    /// control modes, not used by the console
+    pub cflag: u32,
+    pub lflag: LocalModes,
+    /// line discipline, always 0
+    pub line: u8,
+    /// control characters indexed by `VINTR`, `VERASE` and the others
+    pub cc: [u8; NCCS],
+}
+
+impl Default for Termios {
+    fn default() -> Self {
+        Self {
+            iflag: InputModes::empty(),
+            oflag: 0,
+            cflag: 0,
+            lflag: LocalModes::empty(),
+            line: 0,
+            cc: [0; NCCS],
+        }
+    }
+}
+
+impl Termios {
+    /// Turn off canonical mode, echo, signals and newline translation, so
+    /// every key is read as typed as soon as it is pressed
+    pub fn make_raw(&mut self) {
+        self.iflag.remove(InputModes::ICRNL);
+        self.lflag
+            .remove(LocalModes::ICANON | LocalModes::ECHO | LocalModes::ECHOE | LocalModes::ISIG);
+        self.cc[VMIN] = 1;
+    }
+}
+
+/// Get the settings of the terminal `fd`, return -1 if it is not a terminal
+pub fn tcgetattr(fd: usize, termios: &mut Termios) -> isize {
+    sys_ioctl(fd, TCGETS, termios as *mut _ as usize)
+}
+
+/// Change the settings of the terminal `fd` at once
+pub fn tcsetattr(fd: usize, termios: &Termios) -> isize {
+    sys_ioctl(fd, TCSETS, termios as *const _ as usize)
+}
+
+/// `PollFd` event: data can be read without blocking
+pub const POLLIN: i16 = 0x1;
+/// `PollFd` event: data can be written without blocking
+pub const POLLOUT: i16 = 0x4;
+/// `PollFd` event: the fd is not open, only returned
+pub const POLLERR: i16 = 0x8;
+/// `PollFd` event: the other end of a pipe is closed, only returned
+pub const POLLHUP: i16 = 0x10;
+
+/// An fd to wait for with `poll`
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PollFd {
+    /// a negative fd is ignored
+    pub fd: i32,
+    /// events waited for, `POLLIN` and `POLLOUT`
+    pub events: i16,
+    /// events which happened, filled in by `poll`
+    pub revents: i16,
+}
+
+impl PollFd {
+    pub fn new(fd: usize, events: i16) -> Self {
+        Self {
+            fd: fd as i32,
+            events,
+            revents: 0,
+        }
+    }
+}
+
+/// Wait until one of the fds is ready or `timeout_ms` milliseconds pass, a
+/// negative timeout waits forever and `0` only checks. Return the number of
+/// fds with events, `0` on timeout
+pub fn poll(fds: &mut [PollFd], timeout_ms: isize) -> isize {
+    sys_ppoll(fds, timeout_ms)
+}
+
+/// `EpollEvent` event: data can be read without blocking
+pub const EPOLLIN: u32 = 0x1;
+/// `EpollEvent` event: data can be written without blocking
+pub const EPOLLOUT: u32 = 0x4;
+/// `EpollEvent` event: an error happened on the fd, always waited for
+pub const EPOLLERR: u32 = 0x8;
+/// `EpollEvent` event: the other end of a pipe is closed, always waited for
+pub const EPOLLHUP: u32 = 0x10;
+/// Report an event only when the fd becomes ready instead of while it is ready
+pub const EPOLLET: u32 = 1 << 31;
+
+/// `epoll_ctl` operation adding an fd to the interest list
+pub const EPOLL_CTL_ADD: usize = 1;
+/// `epoll_ctl` operation removing an fd from the interest list
+pub const EPOLL_CTL_DEL: usize = 2;
+/// `epoll_ctl` operation changing the events waited for on an fd
+pub const EPOLL_CTL_MOD: usize = 3;
+
+/// An event waited for or reported by an epoll instance
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EpollEvent {
+    /// `EPOLLIN`, `EPOLLOUT` and `EPOLLET` when waiting, the ready events when reported
+    pub events: u32,
+    /// given back unchanged with every event of the fd
+    pub data: u64,
+}
+
+/// Create an epoll instance, return its fd
+pub fn epoll_create() -> isize {
+    sys_epoll_create1(0)
+}
+
+/// Add, change or remove an fd of the interest list, `event` is ignored by `EPOLL_CTL_DEL`
+pub fn epoll_ctl(epfd: usize, op: usize, fd: usize, event: Option<&EpollEvent>) -> isize {
+    sys_epoll_ctl(epfd, op, fd, event.map_or(core::ptr::null(), |e| e))
+}
+
+/// Wait until an fd of the interest list has events or `timeout_ms`
+/// milliseconds pass, a negative timeout waits forever. Return the number
+/// of events stored at the start of `events`, `0` on timeout
+pub fn epoll_wait(epfd: usize, events: &mut [EpollEvent], timeout_ms: isize) -> isize {
+    sys_epoll_pwait(epfd, events, timeout_ms)
+}
+
+pub fn statfs(path: &str, st: &mut StatFs) -> isize {
+    sys_statfs(path, st as *mut _)
+}
+
+pub fn mount(source: &str, target: &str, fstype: &str, flags: MountFlags) -> isize {
+    sys_mount(source, target, fstype, flags.bits)
+}
+
+pub fn umount(target: &str) -> isize {
+    sys_umount(target, 0)
+}
+
+pub fn chroot(path: &str) -> isize {
+    sys_chroot(path)
+}
+
 pub fn open(path: &str, flags: OpenFlags) -> isize {
     sys_open(path, flags.bits)
 }
@@ -86,10 +410,306 @@ pub fn pipe(pipe_fd: &mut [usize; 2]) -> isize {
     sys_pipe(pipe_fd)
 }
 
+bitflags! {
+    /// access allowed to mapped pages, the values follow Linux's `PROT_*`
+    pub struct ProtFlags: u32 {
+        const READ = 1 << 0;
+        const WRITE = 1 << 1;
+        const EXEC = 1 << 2;
+    }
+}
+
+bitflags! {
+    /// kind of a mapping, the values follow Linux's `MAP_*`
+    pub struct MapFlags: u32 {
+        /// writes are seen by other processes mapping the same pages
+        const SHARED = 1 << 0;
+        /// writes are private to the process
+        const PRIVATE = 1 << 1;
+        /// map exactly at `addr` instead of using it as a hint
+        const FIXED = 1 << 4;
+        /// zeroed memory not backed by a file, `fd` is ignored
+        const ANONYMOUS = 1 << 5;
+    }
+}
+
+/// Size of a page, mappings are made of whole pages
+pub const PAGE_SIZE: usize = 4096;
+
+/// Map `len` bytes, return the page-aligned address or -1. The pages are
+/// only given frames when they are first accessed. Misaligned addresses, an
+/// empty length and fixed mappings overlapping existing ones are refused
+pub fn mmap(
+    addr: usize,
+    len: usize,
+    prot: ProtFlags,
+    flags: MapFlags,
+    fd: usize,
+    offset: usize,
+) -> isize {
+    sys_mmap(addr, len, prot.bits, flags.bits, fd, offset)
+}
+
+/// Map `len` bytes of zeroed private memory at `addr`, or anywhere if it is 0
+pub fn mmap_anonymous(addr: usize, len: usize, prot: ProtFlags) -> isize {
+    let mut flags = MapFlags::PRIVATE | MapFlags::ANONYMOUS;
+    if addr != 0 {
+        flags |= MapFlags::FIXED;
+    }
+    sys_mmap(addr, len, prot.bits, flags.bits, usize::MAX, 0)
+}
+
+/// Map `len` bytes of a file from `offset`, which must be page-aligned.
+/// Pages are read from the file when first accessed, and with
+/// `MapFlags::SHARED` written back by `msync` and `munmap` if they are dirty
+pub fn mmap_file(fd: usize, offset: usize, len: usize, prot: ProtFlags, flags: MapFlags) -> isize {
+    sys_mmap(0, len, prot.bits, flags.bits, fd, offset)
+}
+
+/// Change the permissions of the pages in `addr..addr + len`, which may be
+/// part of a larger mapping. `addr` must be page-aligned and every page in
+/// the range mapped by the process, otherwise nothing changes and -1 is
+/// returned. With empty `prot` the pages fault on any access
+pub fn mprotect(addr: usize, len: usize, prot: ProtFlags) -> isize {
+    sys_mprotect(addr, len, prot.bits)
+}
+
+/// `msync` flag waiting until the pages are written
+pub const MS_SYNC: u32 = 4;
+
+/// Write the dirty pages of a shared file mapping back to the file
+pub fn msync(addr: usize, len: usize) -> isize {
+    sys_msync(addr, len, MS_SYNC)
+}
+
+/// Remove the mappings of the pages in `addr..addr + len` and free their
+/// frames, `addr` must be page-aligned and the pages mapped
+pub fn munmap(addr: usize, len: usize) -> isize {
+    sys_munmap(addr, len)
+}
+
+/// Move the program break to `addr` and return the new break, which stays
+/// where it was if the move is refused. `brk(0)` gets the current break
+pub fn brk(addr: usize) -> isize {
+    sys_brk(addr)
+}
+
+/// Move the program break by `increment` bytes and return the old break, or
+/// -1 if the heap cannot grow or shrink that far. The break starts right
+/// above the program image and the heap is freed when the process exits
+pub fn sbrk(increment: isize) -> isize {
+    let old = brk(0);
+    if old < 0 {
+        return -1;
+    }
+    if increment == 0 {
+        return old;
+    }
+    let new = old + increment;
+    if new < 0 || brk(new as usize) != new {
+        return -1;
+    }
+    old
+}
+
+/// Address family of IPv4
+pub const AF_INET: usize = 2;
+/// Socket type of a TCP connection
+pub const SOCK_STREAM: usize = 1;
+/// Socket type of UDP datagrams
+pub const SOCK_DGRAM: usize = 2;
+
+/// An IPv4 address and port, laid out like Linux's `sockaddr_in`
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SockAddrIn {
+    /// always `AF_INET`
+    pub family: u16,
+    /// port in network byte order
+    pub port: u16,
+    pub addr: [u8; 4],
+    zero: [u8; 8],
+}
+
+impl SockAddrIn {
+    pub fn new(addr: [u8; 4], port: u16) -> Self {
+        Self {
+            family: AF_INET as u16,
+            port: port.to_be(),
+            addr,
+            zero: [0; 8],
+        }
+    }
+    /// Get the port in host byte order
+    pub fn port(&self) -> u16 {
+        u16::from_be(self.port)
+    }
+}
+
+const SOCK_ADDR_LEN: usize = core::mem::size_of::<SockAddrIn>();
+
+/// Create a socket, return its fd
+pub fn socket(domain: usize, type_: usize, protocol: usize) -> isize {
+    sys_socket(domain, type_, protocol)
+}
+
+pub fn bind(fd: usize, addr: &SockAddrIn) -> isize {
+    sys_bind(fd, addr, SOCK_ADDR_LEN)
+}
+
+/// Accept connections on a stream socket, at most `backlog` of them waiting
+pub fn listen(fd: usize, backlog: usize) -> isize {
+    sys_listen(fd, backlog)
+}
+
+/// Wait for a connection, return the fd of a new socket connected to the
+/// peer, whose address is stored in `addr`
+pub fn accept(fd: usize, addr: Option<&mut SockAddrIn>) -> isize {
+    let mut addr_len = SOCK_ADDR_LEN;
+    match addr {
+        Some(addr) => sys_accept(fd, addr, &mut addr_len),
+        None => sys_accept(fd, core::ptr::null_mut(), core::ptr::null_mut()),
+    }
+}
+
+/// Connect a stream socket, or set the default peer of a datagram socket
+pub fn connect(fd: usize, addr: &SockAddrIn) -> isize {
+    sys_connect(fd, addr, SOCK_ADDR_LEN)
+}
+
+/// Send data to `addr`, or to the connected peer if it is `None`
+pub fn sendto(fd: usize, buf: &[u8], addr: Option<&SockAddrIn>) -> isize {
+    match addr {
+        Some(addr) => sys_sendto(fd, buf, 0, addr, SOCK_ADDR_LEN),
+        None => sys_sendto(fd, buf, 0, core::ptr::null(), 0),
+    }
+}
+
+/// Receive data, the address of the sender is stored in `addr`
+pub fn recvfrom(fd: usize, buf: &mut [u8], addr: Option<&mut SockAddrIn>) -> isize {
+    let mut addr_len = SOCK_ADDR_LEN;
+    match addr {
+        Some(addr) => sys_recvfrom(fd, buf, 0, addr, &mut addr_len),
+        None => sys_recvfrom(fd, buf, 0, core::ptr::null_mut(), core::ptr::null_mut()),
+    }
+}
+
+/// Attributes of a message queue
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MqAttr {
+    /// number of messages the queue holds before senders block
+    pub max_msgs: usize,
+    /// max size of a message in bytes
+    pub msg_size: usize,
+}
+
+/// Open the message queue named `name`, which starts with `/` and ends with
+/// `\0`. With `OpenFlags::CREATE` a missing queue is created with `attr`, or
+/// default attributes if it is `None`. With `OpenFlags::NONBLOCK` sending to
+/// a full queue and receiving from an empty one fail with `-EAGAIN`
+pub fn mq_open(name: &str, flags: OpenFlags, attr: Option<&MqAttr>) -> isize {
+    sys_mq_open(name, flags.bits, attr.map_or(core::ptr::null(), |a| a))
+}
+
+/// Remove the name of a message queue, it is freed once closed everywhere
+pub fn mq_unlink(name: &str) -> isize {
+    sys_mq_unlink(name)
+}
+
+/// Send a message, which is received after all messages of higher priority
+/// and the earlier ones of the same priority
+pub fn mq_send(mqd: usize, msg: &[u8], priority: u32) -> isize {
+    sys_mq_send(mqd, msg, priority)
+}
+
+/// Receive the oldest message of the highest priority, return its length.
+/// `buf` must hold `msg_size` bytes
+pub fn mq_receive(mqd: usize, buf: &mut [u8], priority: Option<&mut u32>) -> isize {
+    sys_mq_receive(mqd, buf, priority.map_or(core::ptr::null_mut(), |p| p))
+}
+
+/// `shmget` key always creating a new segment
+pub const IPC_PRIVATE: usize = 0;
+/// `shmget` flag creating the segment of the key if there is none
+pub const IPC_CREAT: u32 = 0o1000;
+/// `shmget` flag failing if the segment of the key exists
+pub const IPC_EXCL: u32 = 0o2000;
+/// `shmctl` command removing the segment once it is detached everywhere
+pub const IPC_RMID: usize = 0;
+
+/// Get the id of the shared memory segment of `key`, which has at least
+/// `size` bytes. The segment is kept across fork and freed after `IPC_RMID`
+pub fn shmget(key: usize, size: usize, flags: u32) -> isize {
+    sys_shmget(key, size, flags)
+}
+
+pub fn shmctl(id: usize, cmd: usize) -> isize {
+    sys_shmctl(id, cmd)
+}
+
+/// Map a shared memory segment into the address space, at an address chosen
+/// by the kernel if `addr` is 0. Return the address or -1
+pub fn shmat(id: usize, addr: usize) -> isize {
+    sys_shmat(id, addr)
+}
+
+/// Unmap the shared memory segment mapped at `addr`
+pub fn shmdt(addr: usize) -> isize {
+    sys_shmdt(addr)
+}
+
+/// Create a named pipe at `path`, which ends with `\0`. Opening it for
+/// reading and for writing connects the processes through one pipe
+pub fn mkfifo(path: &str) -> isize {
+    sys_mkfifo(path)
+}
+
+/// Read the next entries of the directory opened as `fd`, return the number
+/// of entries filled in, `0` at the end of the directory
+pub fn getdents(fd: usize, dirents: &mut [Dirent]) -> isize {
+    sys_getdents(fd, dirents)
+}
+
+/// Get the names in a directory, `path` ends with `\0`
+pub fn read_dir(path: &str) -> Option<Vec<String>> {
+    let fd = sys_open(path, OpenFlags::RDONLY.bits);
+    if fd < 0 {
+        return None;
+    }
+    let mut names = Vec::new();
+    let mut dirents = [Dirent::default(); 16];
+    loop {
+        let count = getdents(fd as usize, &mut dirents);
+        if count <= 0 {
+            sys_close(fd as usize);
+            return if count == 0 { Some(names) } else { None };
+        }
+        names.extend(dirents[..count as usize].iter().map(|d| String::from(d.name())));
+    }
+}
+
 pub fn write(fd: usize, buffer: &[u8]) -> isize {
     sys_write(fd, buffer)
 }
 
+pub fn copy_file_range(
+    fd_in: usize,
+    off_in: Option<&mut usize>,
+    fd_out: usize,
+    off_out: Option<&mut usize>,
+    len: usize,
+) -> isize {
+    sys_copy_file_range(
+        fd_in,
+        off_in.map_or(core::ptr::null_mut(), |off| off),
+        fd_out,
+        off_out.map_or(core::ptr::null_mut(), |off| off),
+        len,
+        0,
+    )
+}
+
 pub fn exit(exit_code: i32) -> isize {
     sys_exit(exit_code)
 }
@@ -98,25 +718,293 @@ pub fn yield_() -> isize {
     sys_yield()
 }
 
+/// Get the time since boot in milliseconds, from the older `get_time`
+/// syscall if `clock_gettime` fails
 pub fn get_time() -> isize {
-    sys_get_time()
+    let mut tp = TimeSpec::default();
+    match clock_gettime(CLOCK_MONOTONIC, &mut tp) {
+        0 => tp.as_millis() as isize,
+        _ => sys_get_time(),
+    }
+}
+
+/// Get the time since boot in microseconds, only to the millisecond if
+/// `clock_gettime` fails
+pub fn get_time_us() -> isize {
+    let mut tp = TimeSpec::default();
+    match clock_gettime(CLOCK_MONOTONIC, &mut tp) {
+        0 => tp.as_micros() as isize,
+        _ => match sys_get_time() {
+            ms if ms < 0 => ms,
+            ms => ms * 1000,
+        },
+    }
+}
+
+/// `getrandom` flag returning `-EAGAIN` instead of waiting while the
+/// entropy pool has not been seeded yet
+pub const GRND_NONBLOCK: u32 = 1;
+
+/// Fill `buf` with random bytes, return how many were written, which may be
+/// fewer than asked for if a signal interrupts a large request
+pub fn getrandom(buf: &mut [u8], flags: u32) -> isize {
+    sys_getrandom(buf, flags)
 }
 
 pub fn getpid() -> isize {
     sys_getpid()
 }
 
+/// Move the process `pid`, or the caller if it is 0, into the process group
+/// `pgid` of the same session, or into a new group led by it if `pgid` is 0
+pub fn setpgid(pid: usize, pgid: usize) -> isize {
+    sys_setpgid(pid, pgid)
+}
+
+/// Return the process group of the process `pid`, or of the caller if it is 0
+pub fn getpgid(pid: usize) -> isize {
+    sys_getpgid(pid)
+}
+
+/// Start a new session and process group led by the caller, which has no
+/// controlling terminal until it claims one with `TIOCSCTTY`. Return the
+/// session id, or -1 if the caller already leads a process group
+pub fn setsid() -> isize {
+    sys_setsid()
+}
+
+/// Length of every field of `UtsName`, including the terminating NUL
+pub const UTSNAME_LEN: usize = 65;
+
+/// Name and version of the kernel, every field is a NUL-terminated string
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct UtsName {
+    /// name of the kernel
+    pub sysname: [u8; UTSNAME_LEN],
+    /// name of the machine on the network
+    pub nodename: [u8; UTSNAME_LEN],
+    /// version of the kernel, like `0.3.0`
+    pub release: [u8; UTSNAME_LEN],
+    /// build of the kernel, like its build date
+    pub version: [u8; UTSNAME_LEN],
+    /// the hardware, like `riscv64`
+    pub machine: [u8; UTSNAME_LEN],
+    /// optional parts the kernel was built with, separated by spaces
+    pub features: [u8; UTSNAME_LEN],
+}
+
+impl Default for UtsName {
+    fn default() -> Self {
+        Self {
+            sysname: [0; UTSNAME_LEN],
+            nodename: [0; UTSNAME_LEN],
+            release: [0; UTSNAME_LEN],
+            version: [0; UTSNAME_LEN],
+            machine: [0; UTSNAME_LEN],
+            features: [0; UTSNAME_LEN],
+        }
+    }
+}
+
+impl UtsName {
+    /// The text of a field up to its NUL
+    pub fn field(field: &[u8; UTSNAME_LEN]) -> &str {
+        let len = field.iter().position(|&b| b == 0).unwrap_or(UTSNAME_LEN);
+        core::str::from_utf8(&field[..len]).unwrap_or("")
+    }
+    /// Whether the kernel was built with an optional part, like `net`
+    pub fn has_feature(&self, feature: &str) -> bool {
+        Self::field(&self.features).split(' ').any(|f| f == feature)
+    }
+}
+
+/// Get the name and version of the kernel
+pub fn uname(name: &mut UtsName) -> isize {
+    sys_uname(name)
+}
+
+/// The lowest priority a process can have, processes start with priority 16
+pub const MIN_PRIORITY: isize = 2;
+
+/// Set the scheduling priority of the current thread, the share of the CPU
+/// a thread gets is proportional to its priority. A thread holding a mutex
+/// runs with the highest priority of the threads waiting for it. Return the
+/// priority, or -1 without changing it if it is below `MIN_PRIORITY`
+pub fn set_priority(prio: isize) -> isize {
+    sys_set_priority(prio)
+}
+
+/// Start a thread running `entry(arg)` in the current process, return its
+/// tid. Threads share the address space and the fd table but have their own
+/// stacks. `entry` must not return, it ends the thread with `exit`, which
+/// ends the whole process when called by the main thread (tid 0)
+pub fn thread_create(entry: usize, arg: usize) -> isize {
+    sys_thread_create(entry, arg)
+}
+
+pub fn gettid() -> isize {
+    sys_gettid()
+}
+
+/// Sleep until a thread of the current process exits and return its exit
+/// code, or -1 if there is no such thread or it is the calling one
+pub fn waittid(tid: usize) -> isize {
+    sys_waittid(tid)
+}
+
+/// Create a mutex shared by the threads of the process, return its id.
+/// Threads waiting for it sleep in a wait queue
+pub fn mutex_blocking_create() -> isize {
+    sys_mutex_create(true)
+}
+
+/// Create a mutex which threads waiting for it spin on, yielding in between
+pub fn mutex_create() -> isize {
+    sys_mutex_create(false)
+}
+
+pub fn mutex_lock(mutex_id: usize) {
+    sys_mutex_lock(mutex_id);
+}
+
+pub fn mutex_unlock(mutex_id: usize) {
+    sys_mutex_unlock(mutex_id);
+}
+
+/// Create a semaphore with `res_count` resources, return its id
+pub fn semaphore_create(res_count: usize) -> isize {
+    sys_semaphore_create(res_count)
+}
+
+/// Release a resource, waking a thread waiting for one
+pub fn semaphore_up(sem_id: usize) {
+    sys_semaphore_up(sem_id);
+}
+
+/// Acquire a resource, sleeping until one is available
+pub fn semaphore_down(sem_id: usize) {
+    sys_semaphore_down(sem_id);
+}
+
+/// Create a condition variable, return its id
+pub fn condvar_create() -> isize {
+    sys_condvar_create()
+}
+
+/// Wake one thread waiting on the condition variable, if any
+pub fn condvar_signal(condvar_id: usize) {
+    sys_condvar_signal(condvar_id);
+}
+
+/// Unlock the mutex and sleep until the condition variable is signalled,
+/// the mutex is locked again before returning
+pub fn condvar_wait(condvar_id: usize, mutex_id: usize) {
+    sys_condvar_wait(condvar_id, mutex_id);
+}
+
+/// Like `condvar_wait`, but stop waiting after `timeout_ms`. Return 0 if
+/// the condition variable was signalled or `-ETIMEDOUT`, the mutex is locked
+/// again either way
+pub fn condvar_timedwait(condvar_id: usize, mutex_id: usize, timeout_ms: usize) -> isize {
+    sys_condvar_timedwait(condvar_id, mutex_id, &TimeSpec::from_millis(timeout_ms))
+}
+
+/// Restrict a process, or the current one if `pid` is 0, to the harts whose
+/// bits are set in `mask`, children inherit the mask. Return 0, or -1 if
+/// there is no such process or `mask` contains no existing hart
+pub fn sched_setaffinity(pid: usize, mask: usize) -> isize {
+    sys_sched_setaffinity(pid, &mask)
+}
+
+/// Get the mask of harts a process, or the current one if `pid` is 0, may
+/// run on. Return 0, or -1 if there is no such process
+pub fn sched_getaffinity(pid: usize, mask: &mut usize) -> isize {
+    // the kernel returns the size of the mask it wrote
+    match sys_sched_getaffinity(pid, mask) {
+        ret if ret > 0 => 0,
+        _ => -1,
+    }
+}
+
+/// The nice value giving the largest share of the CPU
+pub const NICE_MIN: isize = -20;
+/// The nice value giving the smallest share of the CPU
+pub const NICE_MAX: isize = 19;
+
+/// Set the nice value of a process, or of the current one if `pid` is 0.
+/// A higher nice value makes the process accumulate virtual runtime faster
+/// so it runs less often, children inherit it. Return 0, or -1 if there is
+/// no such process or `nice` is out of `NICE_MIN..=NICE_MAX`
+pub fn setnice(pid: usize, nice: isize) -> isize {
+    sys_set_nice(pid, nice)
+}
+
+/// Get the nice value of a process, or of the current one if `pid` is 0
+pub fn getnice(pid: usize) -> Option<isize> {
+    // the kernel returns `20 - nice` so that errors can not be mistaken for
+    // nice values
+    match sys_get_nice(pid) {
+        ret if ret > 0 => Some(20 - ret),
+        _ => None,
+    }
+}
+
 pub fn fork() -> isize {
     sys_fork()
 }
 
+/// Execute a program with the environment of the current process
 pub fn exec(path: &str, args: &[*const u8]) -> isize {
-    sys_exec(path, args)
+    let envs = env::envp_strings();
+    let mut envp: Vec<*const u8> = envs.iter().map(|var| var.as_ptr()).collect();
+    envp.push(core::ptr::null::<u8>());
+    sys_exec(path, args, &envp)
+}
+
+/// Execute a program with the given environment, `envp` is a null-terminated
+/// array of `\0`-terminated `NAME=value` strings
+pub fn execve(path: &str, args: &[*const u8], envp: &[*const u8]) -> isize {
+    sys_exec(path, args, envp)
 }
 
+/// Directories searched by `execvp` when PATH is not set
+pub const DEFAULT_PATH: &str = "/bin:/";
+
+/// Execute a program like `exec`, a name without `/` is searched in the
+/// directories listed in the PATH environment variable, separated by `:`,
+/// and then given to `exec` unchanged, so that a kernel looking names up
+/// in the root directory still finds it
+pub fn execvp(name: &str, args: &[*const u8]) -> isize {
+    let name = name.trim_end_matches('\0');
+    if name.is_empty() {
+        return -1;
+    }
+    if name.contains('/') {
+        let mut path = String::from(name);
+        path.push('\0');
+        return exec(path.as_str(), args);
+    }
+    let dirs = getenv("PATH");
+    for dir in dirs.as_deref().unwrap_or(DEFAULT_PATH).split(':').filter(|dir| !dir.is_empty()) {
+        let mut path = String::from(dir);
+        if !path.ends_with('/') {
+            path.push('/');
+        }
+        path.push_str(name);
+        path.push('\0');
+        // exec only returns if the program can not be loaded
+        exec(path.as_str(), args);
+    }
+    let mut path = String::from(name);
+    path.push('\0');
+    exec(path.as_str(), args)
+}
+
+/// Sleep until any child exits and return its pid, or -1 if there is no child
 pub fn wait(exit_code: &mut i32) -> isize {
     loop {
-        match sys_waitpid(-1, exit_code as *mut _) {
+        match sys_waitpid(-1, exit_code as *mut _, 0) {
             -2 => {
                 yield_();
             }
@@ -125,13 +1013,17 @@ pub fn wait(exit_code: &mut i32) -> isize {
     }
 }
 
+/// Return the pid of the child if it has exited, 0 if it is still running
+/// or -1 if there is no such child
 pub fn waitpid_nb(pid: usize, exit_code: &mut i32) -> isize {
-    sys_waitpid(pid as isize, exit_code as *mut _)
+    sys_waitpid(pid as isize, exit_code as *mut _, WNOHANG)
 }
 
+/// Sleep until the child `pid` exits and return its pid, or -1 if there is
+/// no such child
 pub fn waitpid(pid: usize, exit_code: &mut i32) -> isize {
     loop {
-        match sys_waitpid(pid as isize, exit_code as *mut _) {
+        match sys_waitpid(pid as isize, exit_code as *mut _, 0) {
             -2 => {
                 yield_();
             }
@@ -140,18 +1032,214 @@ pub fn waitpid(pid: usize, exit_code: &mut i32) -> isize {
     }
 }
 
+/// `waitpid_options` flag returning 0 at once if no child has changed state
+pub const WNOHANG: usize = 1;
+/// `waitpid_options` flag also reporting children which have been stopped
+pub const WUNTRACED: usize = 2;
+/// `waitpid` flag asking for a wait status instead of the bare exit code
+const WSTATUS: usize = 4;
+
+/// How a child changed state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitStatus {
+    /// the child exited with the low byte of its code, sign extended so that
+    /// small negative codes are kept
+    Exited(i32),
+    /// the child was stopped by the signal, only reported with `WUNTRACED`
+    Stopped(i32),
+}
+
+/// Sleep until the child `pid`, or any child if it is -1, exits, or with
+/// `WUNTRACED` is stopped. A traced child is also reported when it stops
+/// without `WUNTRACED`. Return the pid of the child, 0 if `WNOHANG` is given
+/// and no child has changed state, or -1 if there is no such child. Every
+/// change is reported once, the parent also gets `SIGCHLD` for it
+pub fn waitpid_options(pid: isize, status: &mut WaitStatus, options: usize) -> isize {
+    let mut code: i32 = 0;
+    let ret = loop {
+        match sys_waitpid(pid, &mut code as *mut _, options | WSTATUS) {
+            -2 if options & WNOHANG == 0 => {
+                yield_();
+            }
+            -2 => break 0,
+            ret => break ret,
+        }
+    };
+    if ret > 0 {
+        // with `WSTATUS` an exit is reported as `(code & 0xff) << 8` and a stop
+        // as `(signal << 8) | 0x7f`, so the low byte tells them apart
+        *status = match code & 0xff {
+            0x7f => WaitStatus::Stopped((code >> 8) & 0xff),
+            _ => WaitStatus::Exited(((code >> 8) & 0xff) as u8 as i8 as i32),
+        };
+    }
+    ret
+}
+
+/// `ptrace` request letting the parent trace the caller, which stops with
+/// `SIGTRAP` after its next successful `exec`
+pub const PTRACE_TRACEME: usize = 0;
+/// `ptrace` request reading the word at `addr`, stored at `data`
+pub const PTRACE_PEEKDATA: usize = 2;
+/// `ptrace` request writing the word `data` at `addr`, even into code
+pub const PTRACE_POKEDATA: usize = 5;
+/// `ptrace` request resuming the tracee, delivering the signal `data` if it is not 0
+pub const PTRACE_CONT: usize = 7;
+pub const PTRACE_KILL: usize = 8;
+/// `ptrace` request resuming the tracee for one instruction, after which it
+/// stops with `SIGTRAP`
+pub const PTRACE_SINGLESTEP: usize = 9;
+/// `ptrace` request storing the `UserRegs` of the tracee at `data`
+pub const PTRACE_GETREGS: usize = 12;
+/// `ptrace` request changing the `UserRegs` of the tracee to those at `data`
+pub const PTRACE_SETREGS: usize = 13;
+/// `ptrace` request tracing a process of the same user, which is sent `SIGSTOP`
+pub const PTRACE_ATTACH: usize = 16;
+/// `ptrace` request resuming the tracee and ending the tracing
+pub const PTRACE_DETACH: usize = 17;
+
+/// Registers of a stopped tracee
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UserRegs {
+    /// address of the next instruction
+    pub pc: usize,
+    /// general purpose registers by number, `x[0]` is always 0
+    pub x: [usize; 32],
+}
+
+/// Do a `PTRACE_*` request on the process `pid`. The tracee stops on every
+/// signal and breakpoint, which the tracer sees with `waitpid_options`;
+/// requests other than `PTRACE_TRACEME` and `PTRACE_ATTACH` need it stopped
+pub fn ptrace(request: usize, pid: usize, addr: usize, data: usize) -> isize {
+    sys_ptrace(request, pid, addr, data)
+}
+
+/// Read a word of the memory of a stopped tracee
+pub fn ptrace_peek(pid: usize, addr: usize) -> Option<usize> {
+    let mut word: usize = 0;
+    match sys_ptrace(PTRACE_PEEKDATA, pid, addr, &mut word as *mut _ as usize) {
+        0 => Some(word),
+        _ => None,
+    }
+}
+
+/// Write a word into the memory of a stopped tracee
+pub fn ptrace_poke(pid: usize, addr: usize, word: usize) -> isize {
+    sys_ptrace(PTRACE_POKEDATA, pid, addr, word)
+}
+
+pub fn ptrace_getregs(pid: usize, regs: &mut UserRegs) -> isize {
+    sys_ptrace(PTRACE_GETREGS, pid, 0, regs as *mut _ as usize)
+}
+
+pub fn ptrace_setregs(pid: usize, regs: &UserRegs) -> isize {
+    sys_ptrace(PTRACE_SETREGS, pid, 0, regs as *const _ as usize)
+}
+
 pub fn read(fd: usize, buf: &mut [u8]) -> isize {
     sys_read(fd, buf)
 }
 
+/// A duration in seconds and nanoseconds
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSpec {
+    pub sec: usize,
+    /// below one second
+    pub nsec: usize,
+}
+
+impl TimeSpec {
+    pub const NSEC_PER_SEC: usize = 1_000_000_000;
+
+    pub fn from_millis(ms: usize) -> Self {
+        Self {
+            sec: ms / 1000,
+            nsec: ms % 1000 * 1_000_000,
+        }
+    }
+
+    pub fn as_millis(&self) -> usize {
+        self.sec * 1000 + self.nsec / 1_000_000
+    }
+
+    pub fn as_micros(&self) -> usize {
+        self.sec * 1_000_000 + self.nsec / 1000
+    }
+}
+
+/// Wall-clock time since the Unix epoch, read from the RTC
+pub const CLOCK_REALTIME: usize = 0;
+/// Time since boot, which never goes backwards
+pub const CLOCK_MONOTONIC: usize = 1;
+
+/// Read a clock into `tp` with microsecond resolution, return 0 or -1 for
+/// an unknown clock
+pub fn clock_gettime(clock_id: usize, tp: &mut TimeSpec) -> isize {
+    sys_clock_gettime(clock_id, tp)
+}
+
+/// Sleep for `req` without using the CPU. Return 0, `-EINTR` if a signal
+/// ended the sleep early, with the time left stored in `rem`, or -1 if
+/// `req.nsec` is not below one second
+pub fn nanosleep(req: &TimeSpec, rem: Option<&mut TimeSpec>) -> isize {
+    sys_nanosleep(req, rem.map_or(core::ptr::null_mut(), |rem| rem))
+}
+
+/// Sleep for `period_ms` milliseconds, continuing after signals
 pub fn sleep(period_ms: usize) {
-    let start = sys_get_time();
-    let end = start + period_ms as isize;
-    while sys_get_time() < end {
-        sys_yield();
+    let mut req = TimeSpec::from_millis(period_ms);
+    let mut rem = TimeSpec::default();
+    while nanosleep(&req, Some(&mut rem)) == -EINTR {
+        req = rem;
     }
 }
 
+/// Start reading `buf.len()` bytes from `fd` in the background, return the request id.
+///
+/// # Safety
+///
+/// The kernel writes into `buf` after this returns, so `buf` must not be
+/// dropped, moved or used in any other way until `aio_wait` or `aio_poll`
+/// has returned the result of the request
+pub unsafe fn aio_read(fd: usize, buf: &mut [u8]) -> isize {
+    let request = AioRequest {
+        fd,
+        opcode: AIO_READ,
+        buf: buf.as_mut_ptr() as usize,
+        len: buf.len(),
+    };
+    sys_aio_submit(&request as *const _)
+}
+
+/// Start writing `buf` to `fd` in the background, return the request id.
+///
+/// # Safety
+///
+/// The kernel reads `buf` after this returns, so `buf` must not be dropped,
+/// moved or changed until `aio_wait` or `aio_poll` has returned the result
+/// of the request
+pub unsafe fn aio_write(fd: usize, buf: &[u8]) -> isize {
+    let request = AioRequest {
+        fd,
+        opcode: AIO_WRITE,
+        buf: buf.as_ptr() as usize,
+        len: buf.len(),
+    };
+    sys_aio_submit(&request as *const _)
+}
+
+/// Return the result of an asynchronous request, or -2 if it is still in flight
+pub fn aio_poll(id: usize) -> isize {
+    sys_aio_getevents(id, true)
+}
+
+/// Wait for an asynchronous request and return its result
+pub fn aio_wait(id: usize) -> isize {
+    sys_aio_getevents(id, false)
+}
+
 pub fn kill(pid: usize, signum: i32) -> isize {
     sys_kill(pid, signum)
 }
@@ -168,6 +1256,75 @@ pub fn sigaction(
     )
 }
 
+/// `syslog` action copying the kernel log ring buffer into `buf`, oldest
+/// line first, and returning the bytes copied
+pub const SYSLOG_ACTION_READ_ALL: usize = 3;
+/// `syslog` action like `SYSLOG_ACTION_READ_ALL` which then clears the buffer
+pub const SYSLOG_ACTION_READ_CLEAR: usize = 4;
+/// `syslog` action clearing the kernel log ring buffer
+pub const SYSLOG_ACTION_CLEAR: usize = 5;
+/// `syslog` action setting the least important level of kernel log lines
+/// printed on the console, given in `len`
+pub const SYSLOG_ACTION_CONSOLE_LEVEL: usize = 8;
+/// `syslog` action returning the size of the kernel log ring buffer
+pub const SYSLOG_ACTION_SIZE_BUFFER: usize = 10;
+
+/// Levels of kernel log lines, a line is printed if its level is at most the
+/// console level
+pub const LOG_ERROR: usize = 1;
+pub const LOG_WARN: usize = 2;
+pub const LOG_INFO: usize = 3;
+pub const LOG_DEBUG: usize = 4;
+pub const LOG_TRACE: usize = 5;
+
+/// Do a `SYSLOG_ACTION_*` on the kernel log with `buf`, which is empty for
+/// actions not reading the log. `SYSLOG_ACTION_CONSOLE_LEVEL` takes a level
+/// instead of a buffer and is done by `set_console_log_level`
+pub fn syslog(action: usize, buf: &mut [u8]) -> isize {
+    sys_syslog(action, buf.as_mut_ptr(), buf.len())
+}
+
+/// Get the lines kept in the kernel log ring buffer, oldest first. With
+/// `clear` the buffer is emptied after reading
+pub fn read_kernel_log(clear: bool) -> Option<String> {
+    let size = syslog(SYSLOG_ACTION_SIZE_BUFFER, &mut []);
+    if size < 0 {
+        return None;
+    }
+    let action = if clear {
+        SYSLOG_ACTION_READ_CLEAR
+    } else {
+        SYSLOG_ACTION_READ_ALL
+    };
+    let mut buf = alloc::vec![0u8; size as usize];
+    let len = syslog(action, &mut buf);
+    if len < 0 {
+        return None;
+    }
+    buf.truncate(len as usize);
+    Some(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Print kernel log lines up to `level` on the console from now on, the
+/// level given at boot by `log=LEVEL` is replaced
+pub fn set_console_log_level(level: usize) -> isize {
+    sys_syslog(SYSLOG_ACTION_CONSOLE_LEVEL, core::ptr::null_mut(), level)
+}
+
+/// `reboot` command stopping every hart without powering off, the values
+/// follow Linux's `LINUX_REBOOT_CMD_*`
+pub const REBOOT_CMD_HALT: u32 = 0xcdef_0123;
+/// `reboot` command powering off the machine
+pub const REBOOT_CMD_POWER_OFF: u32 = 0x4321_fedc;
+/// `reboot` command restarting the machine through an SBI system reset
+pub const REBOOT_CMD_RESTART: u32 = 0x0123_4567;
+
+/// Halt, power off or restart the machine, only returns -1 if the command is
+/// unknown or the firmware can not do it
+pub fn reboot(cmd: u32) -> isize {
+    sys_reboot(cmd)
+}
+
 pub fn shutdown(failure: usize) -> ! {
     sys_shutdown(failure)
 }
@@ -180,6 +1337,224 @@ pub fn sigreturn() -> isize {
     sys_sigreturn()
 }
 
+/// Timer counting real time, which delivers `SIGALRM`
+pub const ITIMER_REAL: usize = 0;
+/// Timer counting the time the process runs in user mode, which delivers
+/// `SIGVTALRM`
+pub const ITIMER_VIRTUAL: usize = 1;
+
+/// Setting of an interval timer or a POSIX timer
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ITimerVal {
+    /// the time the timer is rearmed with after it expires, zero for a
+    /// one-shot timer
+    pub interval: TimeSpec,
+    /// the time until the timer expires, zero when it is disarmed
+    pub value: TimeSpec,
+}
+
+/// Get the time left on an interval timer of the current process
+pub fn getitimer(which: usize, curr_value: &mut ITimerVal) -> isize {
+    sys_getitimer(which, curr_value)
+}
+
+/// Arm an interval timer of the current process, or disarm it with a zero
+/// `value`. The previous setting is stored in `old_value`. Return 0, or -1
+/// for an unknown timer
+pub fn setitimer(which: usize, new_value: &ITimerVal, old_value: Option<&mut ITimerVal>) -> isize {
+    sys_setitimer(
+        which,
+        new_value,
+        old_value.map_or(core::ptr::null_mut(), |old| old),
+    )
+}
+
+/// Deliver `SIGALRM` after `seconds`, or cancel the alarm if it is 0.
+/// Return the seconds left of the previous alarm, rounded up
+pub fn alarm(seconds: usize) -> usize {
+    let new_value = ITimerVal {
+        interval: TimeSpec::default(),
+        value: TimeSpec {
+            sec: seconds,
+            nsec: 0,
+        },
+    };
+    let mut old_value = ITimerVal::default();
+    setitimer(ITIMER_REAL, &new_value, Some(&mut old_value));
+    old_value.value.sec + usize::from(old_value.value.nsec > 0)
+}
+
+/// Deliver a signal when a timer expires
+pub const SIGEV_SIGNAL: u32 = 0;
+/// Deliver nothing, the timer is polled with `timer_gettime`
+pub const SIGEV_NONE: u32 = 1;
+
+/// How a timer reports that it expired
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigEvent {
+    /// passed to the signal handler as its second argument
+    pub value: usize,
+    pub signo: i32,
+    /// `SIGEV_SIGNAL` or `SIGEV_NONE`
+    pub notify: u32,
+}
+
+impl SigEvent {
+    /// Deliver `signo` to a handler taking `(signum, value)`
+    pub fn signal(signo: i32, value: usize) -> Self {
+        Self {
+            value,
+            signo,
+            notify: SIGEV_SIGNAL,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self {
+            value: 0,
+            signo: 0,
+            notify: SIGEV_NONE,
+        }
+    }
+}
+
+/// `timer_settime` flag making `value` an absolute time of the timer's clock
+pub const TIMER_ABSTIME: usize = 1;
+
+/// Create a disarmed timer counting on `clock_id`, return its id or -1. The
+/// expirations of all timers are queued, so a timer's signal is not lost
+/// when another timer delivers the same signal, and the handler can tell
+/// the timers apart by their `value`. `None` delivers `SIGALRM` with the
+/// timer id as the value
+pub fn timer_create(clock_id: usize, sevp: Option<&SigEvent>) -> isize {
+    let mut timer_id = 0;
+    match sys_timer_create(clock_id, sevp.map_or(core::ptr::null(), |sevp| sevp), &mut timer_id) {
+        0 => timer_id as isize,
+        _ => -1,
+    }
+}
+
+/// Get the time left on a timer
+pub fn timer_gettime(timer_id: usize, curr_value: &mut ITimerVal) -> isize {
+    sys_timer_gettime(timer_id, curr_value)
+}
+
+/// Arm a timer, or disarm it with a zero `value`. The previous setting is
+/// stored in `old_value`. Return 0, or -1 if there is no such timer
+pub fn timer_settime(
+    timer_id: usize,
+    flags: usize,
+    new_value: &ITimerVal,
+    old_value: Option<&mut ITimerVal>,
+) -> isize {
+    sys_timer_settime(
+        timer_id,
+        flags,
+        new_value,
+        old_value.map_or(core::ptr::null_mut(), |old| old),
+    )
+}
+
+/// Disarm and delete a timer, its pending expirations are dropped
+pub fn timer_delete(timer_id: usize) -> isize {
+    sys_timer_delete(timer_id)
+}
+
+/// Get the information of at most `tasks.len()` tasks, return the number
+/// of tasks filled in
+pub fn task_info(tasks: &mut [TaskInfo]) -> isize {
+    sys_task_info(tasks)
+}
+
+/// structure for filesystem usage
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StatFs {
+    /// size of a block in bytes
+    pub block_size: u64,
+    /// number of data blocks
+    pub total_blocks: u64,
+    /// number of free data blocks
+    pub free_blocks: u64,
+    /// number of inodes
+    pub total_inodes: u64,
+    /// number of free inodes
+    pub free_inodes: u64,
+}
+
+pub const AIO_READ: u32 = 0;
+pub const AIO_WRITE: u32 = 1;
+
+/// structure for directory entry, the same layout as easy-fs uses on disk
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Dirent {
+    /// name terminated by `\0`
+    pub name: [u8; 28],
+    pub inode: u32,
+}
+
+impl Dirent {
+    /// Get the name of the entry
+    pub fn name(&self) -> &str {
+        let len = self.name.iter().position(|c| *c == 0).unwrap_or(self.name.len());
+        core::str::from_utf8(&self.name[..len]).unwrap_or("?")
+    }
+}
+
+/// structure for asynchronous I/O request
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct AioRequest {
+    pub fd: usize,
+    /// `AIO_READ` or `AIO_WRITE`
+    pub opcode: u32,
+    /// address of the user buffer
+    pub buf: usize,
+    pub len: usize,
+}
+
+pub const TASK_READY: u32 = 0;
+pub const TASK_RUNNING: u32 = 1;
+pub const TASK_BLOCKED: u32 = 2;
+pub const TASK_ZOMBIE: u32 = 3;
+
+/// structure for task information
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TaskInfo {
+    pub pid: usize,
+    /// `0` for initproc
+    pub ppid: usize,
+    /// one of `TASK_READY`, `TASK_RUNNING`, `TASK_BLOCKED` and `TASK_ZOMBIE`
+    pub status: u32,
+    /// CPU time used in milliseconds
+    pub cpu_time: usize,
+    /// memory mapped in the address space in bytes
+    pub memory: usize,
+    /// name of the program, terminated by `\0` if shorter than 16 bytes
+    pub name: [u8; 16],
+}
+
+impl TaskInfo {
+    /// Get the name of the program
+    pub fn name(&self) -> &str {
+        let len = self.name.iter().position(|c| *c == 0).unwrap_or(self.name.len());
+        core::str::from_utf8(&self.name[..len]).unwrap_or("?")
+    }
+    /// Get the status as a single letter like ps does
+    pub fn status_char(&self) -> char {
+        match self.status {
+            TASK_READY | TASK_RUNNING => 'R',
+            TASK_BLOCKED => 'S',
+            TASK_ZOMBIE => 'Z',
+            _ => '?',
+        }
+    }
+}
+
 /// structure for signal action
 #[repr(C, align(16))]
 #[derive(Debug, Clone, Copy)]
@@ -0,0 +1,172 @@
+//! Helpers over the socket syscalls: address parsing, a stub DNS resolver and TCP connections
+
+use alloc::vec::Vec;
+
+use crate::{
+    close, connect, get_time, poll, rand, recvfrom, sendto, socket, write, PollFd, SockAddrIn,
+    AF_INET, POLLIN, SOCK_DGRAM, SOCK_STREAM,
+};
+
+/// The DNS server of QEMU user networking
+pub const DEFAULT_NAMESERVER: [u8; 4] = [10, 0, 2, 3];
+const DNS_PORT: u16 = 53;
+/// How long to wait for the answer of the nameserver in milliseconds
+const DNS_TIMEOUT_MS: isize = 2000;
+/// Recursion desired
+const DNS_FLAG_RD: u16 = 0x0100;
+/// Set in answers
+const DNS_FLAG_QR: u16 = 0x8000;
+const DNS_RCODE_MASK: u16 = 0x000f;
+const DNS_TYPE_A: u16 = 1;
+const DNS_CLASS_IN: u16 = 1;
+const DNS_HEADER_LEN: usize = 12;
+/// Top bits of a length byte which make it a pointer to an earlier name
+const DNS_POINTER: u8 = 0xc0;
+
+/// Parse an IPv4 address written as `a.b.c.d`
+pub fn parse_ipv4(s: &str) -> Option<[u8; 4]> {
+    let mut addr = [0u8; 4];
+    let mut parts = s.split('.');
+    for byte in addr.iter_mut() {
+        *byte = parts.next()?.parse().ok()?;
+    }
+    match parts.next() {
+        Some(_) => None,
+        None => Some(addr),
+    }
+}
+
+/// Get the IPv4 address of a host name, an address literal or `localhost`
+/// is returned directly and other names are asked to `DEFAULT_NAMESERVER`
+pub fn resolve(host: &str) -> Option<[u8; 4]> {
+    if let Some(addr) = parse_ipv4(host) {
+        return Some(addr);
+    }
+    if host == "localhost" {
+        return Some([127, 0, 0, 1]);
+    }
+    dns_query(host, DEFAULT_NAMESERVER)
+}
+
+/// Build a query for the A record of `host`
+fn build_query(id: u16, host: &str) -> Option<Vec<u8>> {
+    let mut query = Vec::new();
+    query.extend_from_slice(&id.to_be_bytes());
+    query.extend_from_slice(&DNS_FLAG_RD.to_be_bytes());
+    // one question, no answers or other records
+    query.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in host.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return None;
+        }
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&DNS_TYPE_A.to_be_bytes());
+    query.extend_from_slice(&DNS_CLASS_IN.to_be_bytes());
+    Some(query)
+}
+
+fn be16(buf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([
+        *buf.get(offset)?,
+        *buf.get(offset + 1)?,
+    ]))
+}
+
+/// Return the offset after the name at `offset`
+fn skip_name(buf: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        let len = *buf.get(offset)?;
+        if len & DNS_POINTER == DNS_POINTER {
+            // a pointer ends the name
+            return Some(offset + 2);
+        }
+        offset += 1;
+        if len == 0 {
+            return Some(offset);
+        }
+        offset += len as usize;
+    }
+}
+
+/// Find the first A record in the answer to the query `id`
+fn parse_answer(id: u16, buf: &[u8]) -> Option<[u8; 4]> {
+    let flags = be16(buf, 2)?;
+    if be16(buf, 0)? != id || flags & DNS_FLAG_QR == 0 || flags & DNS_RCODE_MASK != 0 {
+        return None;
+    }
+    let questions = be16(buf, 4)?;
+    let answers = be16(buf, 6)?;
+    let mut offset = DNS_HEADER_LEN;
+    for _ in 0..questions {
+        // the name, type and class
+        offset = skip_name(buf, offset)? + 4;
+    }
+    for _ in 0..answers {
+        offset = skip_name(buf, offset)?;
+        let type_ = be16(buf, offset)?;
+        let class = be16(buf, offset + 2)?;
+        let len = be16(buf, offset + 8)? as usize;
+        // after the type, class, TTL and length
+        offset += 10;
+        let data = buf.get(offset..offset + len)?;
+        if type_ == DNS_TYPE_A && class == DNS_CLASS_IN && len == 4 {
+            return Some([data[0], data[1], data[2], data[3]]);
+        }
+        offset += len;
+    }
+    None
+}
+
+/// Ask a nameserver for the A record of `host`
+pub fn dns_query(host: &str, nameserver: [u8; 4]) -> Option<[u8; 4]> {
+    let id = rand::random_u64().unwrap_or(get_time() as u64) as u16;
+    let query = build_query(id, host)?;
+    let fd = socket(AF_INET, SOCK_DGRAM, 0);
+    if fd < 0 {
+        return None;
+    }
+    let fd = fd as usize;
+    let server = SockAddrIn::new(nameserver, DNS_PORT);
+    let mut answer = None;
+    if sendto(fd, &query, Some(&server)) == query.len() as isize {
+        let mut fds = [PollFd::new(fd, POLLIN)];
+        let mut buf = [0u8; 512];
+        if poll(&mut fds, DNS_TIMEOUT_MS) > 0 {
+            let len = recvfrom(fd, &mut buf, None);
+            if len > 0 {
+                answer = parse_answer(id, &buf[..len as usize]);
+            }
+        }
+    }
+    close(fd);
+    answer
+}
+
+/// Open a TCP connection, return the fd of the socket
+pub fn tcp_connect(addr: [u8; 4], port: u16) -> Option<usize> {
+    let fd = socket(AF_INET, SOCK_STREAM, 0);
+    if fd < 0 {
+        return None;
+    }
+    let fd = fd as usize;
+    if connect(fd, &SockAddrIn::new(addr, port)) != 0 {
+        close(fd);
+        return None;
+    }
+    Some(fd)
+}
+
+/// Write the whole buffer, return false if a write fails
+pub fn write_all(fd: usize, mut buf: &[u8]) -> bool {
+    while !buf.is_empty() {
+        let len = write(fd, buf);
+        if len <= 0 {
+            return false;
+        }
+        buf = &buf[len as usize..];
+    }
+    true
+}
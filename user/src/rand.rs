@@ -0,0 +1,62 @@
+//! Random numbers from the kernel entropy pool, and a fast generator seeded from it
+
+use crate::{getrandom, EINTR};
+
+/// Fill `buf` with random bytes from the kernel, waiting until the entropy
+/// pool is seeded. A request interrupted by a signal is continued, any other
+/// failure returns what `getrandom` returned
+pub fn fill(buf: &mut [u8]) -> Result<(), isize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match getrandom(&mut buf[filled..], 0) {
+            len if len > 0 => filled += len as usize,
+            err if err == -EINTR => {}
+            err => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// A random number from the kernel, `None` if it cannot give one
+pub fn random_u64() -> Option<u64> {
+    let mut bytes = [0u8; 8];
+    fill(&mut bytes).ok()?;
+    Some(u64::from_le_bytes(bytes))
+}
+
+/// A random number in `0..n` from the kernel, `n` must not be 0
+pub fn below(n: usize) -> Option<usize> {
+    Some((random_u64()? % n as u64) as usize)
+}
+
+/// xorshift64* generator for programs needing many numbers, it is not
+/// suitable for secrets
+pub struct Rng(u64);
+
+impl Rng {
+    /// A generator seeded by the kernel, `None` if it cannot give a seed
+    pub fn new() -> Option<Self> {
+        random_u64().map(Self::from_seed)
+    }
+    /// A generator giving the same numbers for the same seed
+    pub fn from_seed(seed: u64) -> Self {
+        // the state must not be 0
+        Self(seed | 1)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+    /// A number in `0..n`, `n` must not be 0
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+    pub fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
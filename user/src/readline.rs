@@ -0,0 +1,283 @@
+//! Line editor for interactive programs, with cursor movement, history and completion
+
+#![allow(clippy::println_empty_string)]
+
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::console::getchar;
+use crate::term::{clear_to_eol, enable_raw_mode, move_left};
+
+const LF: u8 = 0x0au8;
+const CR: u8 = 0x0du8;
+const DL: u8 = 0x7fu8;
+const BS: u8 = 0x08u8;
+const TAB: u8 = 0x09u8;
+const ESC: u8 = 0x1bu8;
+const CTRL_A: u8 = 0x01u8;
+const CTRL_C: u8 = 0x03u8;
+const CTRL_E: u8 = 0x05u8;
+const CTRL_K: u8 = 0x0bu8;
+const CTRL_U: u8 = 0x15u8;
+const CTRL_W: u8 = 0x17u8;
+
+/// The max number of lines kept in the history
+pub const HISTORY_SIZE: usize = 64;
+
+/// Complete the text before the cursor, return the text to insert and all candidates
+pub type Completer = fn(&str) -> (String, Vec<String>);
+
+/// A decoded key press
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Tab,
+    Interrupt,
+    KillToEnd,
+    KillToStart,
+    KillWord,
+    Unknown,
+}
+
+/// Read one key press, decoding the escape sequences of special keys
+fn read_key() -> Key {
+    match getchar() {
+        LF | CR => Key::Enter,
+        BS | DL => Key::Backspace,
+        TAB => Key::Tab,
+        CTRL_A => Key::Home,
+        CTRL_E => Key::End,
+        CTRL_C => Key::Interrupt,
+        CTRL_K => Key::KillToEnd,
+        CTRL_U => Key::KillToStart,
+        CTRL_W => Key::KillWord,
+        ESC => {
+            if getchar() != b'[' {
+                return Key::Unknown;
+            }
+            match getchar() {
+                b'A' => Key::Up,
+                b'B' => Key::Down,
+                b'C' => Key::Right,
+                b'D' => Key::Left,
+                b'H' => Key::Home,
+                b'F' => Key::End,
+                // `ESC [ n ~` form of Home, Delete and End
+                c @ b'1'..=b'8' => {
+                    if getchar() != b'~' {
+                        return Key::Unknown;
+                    }
+                    match c {
+                        b'1' | b'7' => Key::Home,
+                        b'3' => Key::Delete,
+                        b'4' | b'8' => Key::End,
+                        _ => Key::Unknown,
+                    }
+                }
+                _ => Key::Unknown,
+            }
+        }
+        c if (0x20..0x7f).contains(&c) => Key::Char(c as char),
+        _ => Key::Unknown,
+    }
+}
+
+/// Line editor keeping the history of the lines read
+pub struct Readline {
+    prompt: String,
+    /// previous lines, oldest first
+    history: VecDeque<String>,
+    completer: Option<Completer>,
+    /// the line being edited
+    line: Vec<char>,
+    /// position of the cursor in `line`
+    cursor: usize,
+}
+
+impl Readline {
+    pub fn new(prompt: &str) -> Self {
+        Self {
+            prompt: String::from(prompt),
+            history: VecDeque::new(),
+            completer: None,
+            line: Vec::new(),
+            cursor: 0,
+        }
+    }
+    /// Change the prompt shown before the line
+    pub fn set_prompt(&mut self, prompt: &str) {
+        self.prompt = String::from(prompt);
+    }
+    /// Use a function to complete the text before the cursor when Tab is pressed
+    pub fn set_completer(&mut self, completer: Completer) {
+        self.completer = Some(completer);
+    }
+    /// Record a line, blank lines and repeats of the last line are skipped
+    pub fn add_history(&mut self, line: &str) {
+        if line.trim().is_empty() || self.history.back().map(String::as_str) == Some(line) {
+            return;
+        }
+        if self.history.len() == HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        self.history.push_back(String::from(line));
+    }
+    /// Get the recorded lines, oldest first
+    pub fn history(&self) -> impl Iterator<Item = &str> {
+        self.history.iter().map(String::as_str)
+    }
+    /// Show the prompt and read a line with the console in raw mode. Ctrl-C
+    /// discards the line and returns an empty one
+    pub fn read_line(&mut self) -> String {
+        let _raw_mode = enable_raw_mode();
+        self.line.clear();
+        self.cursor = 0;
+        // the history entry shown, `None` when editing a new line
+        let mut shown: Option<usize> = None;
+        // the line being typed before moving through the history
+        let mut draft: Vec<char> = Vec::new();
+        let mut last_tab = false;
+        print!("{}", self.prompt);
+        loop {
+            let key = read_key();
+            let tab = matches!(key, Key::Tab);
+            match key {
+                Key::Enter => {
+                    println!("");
+                    return self.line.iter().collect();
+                }
+                Key::Interrupt => {
+                    println!("^C");
+                    return String::new();
+                }
+                Key::Char(c) => {
+                    self.line.insert(self.cursor, c);
+                    self.cursor += 1;
+                    if self.cursor == self.line.len() {
+                        print!("{}", c);
+                    } else {
+                        self.redraw();
+                    }
+                }
+                Key::Backspace if self.cursor > 0 => {
+                    self.cursor -= 1;
+                    self.line.remove(self.cursor);
+                    self.redraw();
+                }
+                Key::Delete if self.cursor < self.line.len() => {
+                    self.line.remove(self.cursor);
+                    self.redraw();
+                }
+                Key::Left if self.cursor > 0 => {
+                    self.cursor -= 1;
+                    move_left(1);
+                }
+                Key::Right if self.cursor < self.line.len() => {
+                    print!("{}", self.line[self.cursor]);
+                    self.cursor += 1;
+                }
+                Key::Home => {
+                    self.cursor = 0;
+                    self.redraw();
+                }
+                Key::End => {
+                    self.cursor = self.line.len();
+                    self.redraw();
+                }
+                Key::KillToEnd => {
+                    self.line.truncate(self.cursor);
+                    self.redraw();
+                }
+                Key::KillToStart => {
+                    self.line.drain(..self.cursor);
+                    self.cursor = 0;
+                    self.redraw();
+                }
+                Key::KillWord => {
+                    // spaces before the cursor and then the word before them
+                    let mut start = self.cursor;
+                    while start > 0 && self.line[start - 1] == ' ' {
+                        start -= 1;
+                    }
+                    while start > 0 && self.line[start - 1] != ' ' {
+                        start -= 1;
+                    }
+                    self.line.drain(start..self.cursor);
+                    self.cursor = start;
+                    self.redraw();
+                }
+                Key::Up => {
+                    let older = match shown {
+                        None if !self.history.is_empty() => {
+                            draft = self.line.clone();
+                            Some(self.history.len() - 1)
+                        }
+                        Some(idx) if idx > 0 => Some(idx - 1),
+                        _ => None,
+                    };
+                    if let Some(idx) = older {
+                        shown = Some(idx);
+                        self.line = self.history[idx].chars().collect();
+                        self.cursor = self.line.len();
+                        self.redraw();
+                    }
+                }
+                Key::Down => {
+                    if let Some(idx) = shown {
+                        if idx + 1 < self.history.len() {
+                            shown = Some(idx + 1);
+                            self.line = self.history[idx + 1].chars().collect();
+                        } else {
+                            // past the newest line is the draft
+                            shown = None;
+                            self.line = core::mem::take(&mut draft);
+                        }
+                        self.cursor = self.line.len();
+                        self.redraw();
+                    }
+                }
+                Key::Tab => {
+                    if let Some(completer) = self.completer {
+                        let before: String = self.line[..self.cursor].iter().collect();
+                        let (addition, candidates) = completer(before.as_str());
+                        if !addition.is_empty() {
+                            for c in addition.chars() {
+                                self.line.insert(self.cursor, c);
+                                self.cursor += 1;
+                            }
+                            self.redraw();
+                        } else if last_tab && candidates.len() > 1 {
+                            // list the candidates when Tab can not complete more
+                            println!("");
+                            for candidate in candidates.iter() {
+                                print!("{}  ", candidate);
+                            }
+                            println!("");
+                            self.redraw();
+                        }
+                    }
+                }
+                _ => {}
+            }
+            last_tab = tab;
+        }
+    }
+    /// Show the whole line again and put the cursor back
+    fn redraw(&self) {
+        print!("\r{}", self.prompt);
+        for c in self.line.iter() {
+            print!("{}", c);
+        }
+        clear_to_eol();
+        move_left(self.line.len() - self.cursor);
+    }
+}
@@ -1,24 +1,98 @@
 use core::arch::asm;
-use crate::SignalAction;
+use crate::{
+    AioRequest, Dirent, EpollEvent, MqAttr, PollFd, SignalAction, SockAddrIn, StatFs, TaskInfo,
+    ITimerVal, SigEvent, TimeSpec, UtsName,
+};
 
+const SYSCALL_EPOLL_CREATE1: usize = 20;
+const SYSCALL_EPOLL_CTL: usize = 21;
+const SYSCALL_EPOLL_PWAIT: usize = 22;
+const SYSCALL_DUP3: usize = 23;
 const SYSCALL_DUP: usize = 24;
+const SYSCALL_FCNTL: usize = 25;
+const SYSCALL_IOCTL: usize = 29;
+const SYSCALL_MKFIFO: usize = 33;
+const SYSCALL_UMOUNT: usize = 39;
+const SYSCALL_MOUNT: usize = 40;
+const SYSCALL_STATFS: usize = 43;
+const SYSCALL_CHROOT: usize = 51;
 const SYSCALL_OPEN: usize = 56;
 const SYSCALL_CLOSE: usize = 57;
 const SYSCALL_PIPE: usize = 59;
+const SYSCALL_GETDENTS: usize = 61;
 const SYSCALL_READ: usize = 63;
 const SYSCALL_WRITE: usize = 64;
+const SYSCALL_PPOLL: usize = 73;
 const SYSCALL_EXIT: usize = 93;
+const SYSCALL_NANOSLEEP: usize = 101;
+const SYSCALL_GETITIMER: usize = 102;
+const SYSCALL_SETITIMER: usize = 103;
+const SYSCALL_TIMER_CREATE: usize = 107;
+const SYSCALL_TIMER_GETTIME: usize = 108;
+const SYSCALL_TIMER_SETTIME: usize = 110;
+const SYSCALL_TIMER_DELETE: usize = 111;
+const SYSCALL_CLOCK_GETTIME: usize = 113;
+const SYSCALL_SYSLOG: usize = 116;
+const SYSCALL_PTRACE: usize = 117;
+const SYSCALL_SCHED_SETAFFINITY: usize = 122;
+const SYSCALL_SCHED_GETAFFINITY: usize = 123;
 const SYSCALL_YIELD: usize = 124;
 const SYSCALL_KILL: usize = 129;
 const SYSCALL_SHUTDOWN: usize = 130;
 const SYSCALL_SIGACTION: usize = 134;
 const SYSCALL_SIGPROCMASK: usize = 135;
 const SYSCALL_SIGRETURN: usize = 139;
+const SYSCALL_SET_PRIORITY: usize = 140;
+const SYSCALL_REBOOT: usize = 142;
+const SYSCALL_SETPGID: usize = 154;
+const SYSCALL_GETPGID: usize = 155;
+const SYSCALL_SETSID: usize = 157;
+const SYSCALL_UNAME: usize = 160;
 const SYSCALL_GET_TIME: usize = 169;
 const SYSCALL_GETPID: usize = 172;
+const SYSCALL_MQ_OPEN: usize = 180;
+const SYSCALL_MQ_UNLINK: usize = 181;
+const SYSCALL_MQ_SEND: usize = 182;
+const SYSCALL_MQ_RECEIVE: usize = 183;
+const SYSCALL_SHMGET: usize = 194;
+const SYSCALL_SHMCTL: usize = 195;
+const SYSCALL_SHMAT: usize = 196;
+const SYSCALL_SHMDT: usize = 197;
+const SYSCALL_SOCKET: usize = 198;
+const SYSCALL_BIND: usize = 200;
+const SYSCALL_LISTEN: usize = 201;
+const SYSCALL_ACCEPT: usize = 202;
+const SYSCALL_CONNECT: usize = 203;
+const SYSCALL_SENDTO: usize = 206;
+const SYSCALL_RECVFROM: usize = 207;
+const SYSCALL_BRK: usize = 214;
+const SYSCALL_MUNMAP: usize = 215;
 const SYSCALL_FORK: usize = 220;
 const SYSCALL_EXEC: usize = 221;
+const SYSCALL_MMAP: usize = 222;
+const SYSCALL_MPROTECT: usize = 226;
+const SYSCALL_MSYNC: usize = 227;
 const SYSCALL_WAITPID: usize = 260;
+const SYSCALL_GETRANDOM: usize = 278;
+const SYSCALL_COPY_FILE_RANGE: usize = 285;
+const SYSCALL_TASK_INFO: usize = 410;
+const SYSCALL_THREAD_CREATE: usize = 1000;
+const SYSCALL_GETTID: usize = 1001;
+const SYSCALL_WAITTID: usize = 1002;
+const SYSCALL_MUTEX_CREATE: usize = 1010;
+const SYSCALL_MUTEX_LOCK: usize = 1011;
+const SYSCALL_MUTEX_UNLOCK: usize = 1012;
+const SYSCALL_SEMAPHORE_CREATE: usize = 1020;
+const SYSCALL_SEMAPHORE_UP: usize = 1021;
+const SYSCALL_SEMAPHORE_DOWN: usize = 1022;
+const SYSCALL_CONDVAR_CREATE: usize = 1030;
+const SYSCALL_CONDVAR_SIGNAL: usize = 1031;
+const SYSCALL_CONDVAR_WAIT: usize = 1032;
+const SYSCALL_CONDVAR_TIMEDWAIT: usize = 1033;
+const SYSCALL_AIO_SUBMIT: usize = 1040;
+const SYSCALL_AIO_GETEVENTS: usize = 1041;
+const SYSCALL_SET_NICE: usize = 1100;
+const SYSCALL_GET_NICE: usize = 1101;
 
 fn syscall(id: usize, args: [usize; 3]) -> isize {
     let mut ret: isize;
@@ -34,10 +108,231 @@ fn syscall(id: usize, args: [usize; 3]) -> isize {
     ret
 }
 
+fn syscall6(id: usize, args: [usize; 6]) -> isize {
+    let mut ret: isize;
+    unsafe {
+        asm!(
+            "ecall",
+            inlateout("x10") args[0] => ret,
+            in("x11") args[1],
+            in("x12") args[2],
+            in("x13") args[3],
+            in("x14") args[4],
+            in("x15") args[5],
+            in("x17") id,
+        );
+    }
+    ret
+}
+
 pub fn sys_dup(fd: usize) -> isize {
     syscall(SYSCALL_DUP, [fd, 0, 0])
 }
 
+// system call used for duplicating a file descriptor onto a given fd number
+pub fn sys_dup3(oldfd: usize, newfd: usize, flags: u32) -> isize {
+    syscall(SYSCALL_DUP3, [oldfd, newfd, flags as usize])
+}
+
+// system call used for querying and changing a file descriptor
+pub fn sys_fcntl(fd: usize, cmd: usize, arg: usize) -> isize {
+    syscall(SYSCALL_FCNTL, [fd, cmd, arg])
+}
+
+// system call used for controlling a device through a file descriptor
+pub fn sys_ioctl(fd: usize, cmd: usize, arg: usize) -> isize {
+    syscall(SYSCALL_IOCTL, [fd, cmd, arg])
+}
+
+// system call used for moving the program break
+pub fn sys_brk(addr: usize) -> isize {
+    syscall(SYSCALL_BRK, [addr, 0, 0])
+}
+
+// system call used for mapping memory into the address space
+pub fn sys_mmap(addr: usize, len: usize, prot: u32, flags: u32, fd: usize, offset: usize) -> isize {
+    syscall6(SYSCALL_MMAP, [addr, len, prot as usize, flags as usize, fd, offset])
+}
+
+// system call used for changing the permissions of mapped pages
+pub fn sys_mprotect(addr: usize, len: usize, prot: u32) -> isize {
+    syscall(SYSCALL_MPROTECT, [addr, len, prot as usize])
+}
+
+// system call used for removing mappings from the address space
+pub fn sys_munmap(addr: usize, len: usize) -> isize {
+    syscall(SYSCALL_MUNMAP, [addr, len, 0])
+}
+
+// system call used for writing dirty pages of a shared file mapping back
+pub fn sys_msync(addr: usize, len: usize, flags: u32) -> isize {
+    syscall(SYSCALL_MSYNC, [addr, len, flags as usize])
+}
+
+// system call used for creating a socket
+pub fn sys_socket(domain: usize, type_: usize, protocol: usize) -> isize {
+    syscall(SYSCALL_SOCKET, [domain, type_, protocol])
+}
+
+// system call used for giving a socket a local address
+pub fn sys_bind(fd: usize, addr: *const SockAddrIn, addr_len: usize) -> isize {
+    syscall(SYSCALL_BIND, [fd, addr as usize, addr_len])
+}
+
+// system call used for accepting connections on a stream socket
+pub fn sys_listen(fd: usize, backlog: usize) -> isize {
+    syscall(SYSCALL_LISTEN, [fd, backlog, 0])
+}
+
+// system call used for waiting for a connection on a listening socket
+pub fn sys_accept(fd: usize, addr: *mut SockAddrIn, addr_len: *mut usize) -> isize {
+    syscall(SYSCALL_ACCEPT, [fd, addr as usize, addr_len as usize])
+}
+
+// system call used for connecting a socket to a remote address
+pub fn sys_connect(fd: usize, addr: *const SockAddrIn, addr_len: usize) -> isize {
+    syscall(SYSCALL_CONNECT, [fd, addr as usize, addr_len])
+}
+
+// system call used for sending data through a socket
+pub fn sys_sendto(
+    fd: usize,
+    buf: &[u8],
+    flags: u32,
+    addr: *const SockAddrIn,
+    addr_len: usize,
+) -> isize {
+    syscall6(
+        SYSCALL_SENDTO,
+        [fd, buf.as_ptr() as usize, buf.len(), flags as usize, addr as usize, addr_len],
+    )
+}
+
+// system call used for receiving data from a socket
+pub fn sys_recvfrom(
+    fd: usize,
+    buf: &mut [u8],
+    flags: u32,
+    addr: *mut SockAddrIn,
+    addr_len: *mut usize,
+) -> isize {
+    syscall6(
+        SYSCALL_RECVFROM,
+        [
+            fd,
+            buf.as_mut_ptr() as usize,
+            buf.len(),
+            flags as usize,
+            addr as usize,
+            addr_len as usize,
+        ],
+    )
+}
+
+// system call used for opening a message queue by name
+pub fn sys_mq_open(name: &str, flags: u32, attr: *const MqAttr) -> isize {
+    syscall(SYSCALL_MQ_OPEN, [name.as_ptr() as usize, flags as usize, attr as usize])
+}
+
+// system call used for removing the name of a message queue
+pub fn sys_mq_unlink(name: &str) -> isize {
+    syscall(SYSCALL_MQ_UNLINK, [name.as_ptr() as usize, 0, 0])
+}
+
+// system call used for sending a message to a message queue
+pub fn sys_mq_send(mqd: usize, msg: &[u8], priority: u32) -> isize {
+    syscall6(
+        SYSCALL_MQ_SEND,
+        [mqd, msg.as_ptr() as usize, msg.len(), priority as usize, 0, 0],
+    )
+}
+
+// system call used for receiving the message with the highest priority
+pub fn sys_mq_receive(mqd: usize, buf: &mut [u8], priority: *mut u32) -> isize {
+    syscall6(
+        SYSCALL_MQ_RECEIVE,
+        [mqd, buf.as_mut_ptr() as usize, buf.len(), priority as usize, 0, 0],
+    )
+}
+
+// system call used for getting a shared memory segment by key
+pub fn sys_shmget(key: usize, size: usize, flags: u32) -> isize {
+    syscall(SYSCALL_SHMGET, [key, size, flags as usize])
+}
+
+// system call used for controlling a shared memory segment
+pub fn sys_shmctl(id: usize, cmd: usize) -> isize {
+    syscall(SYSCALL_SHMCTL, [id, cmd, 0])
+}
+
+// system call used for mapping a shared memory segment
+pub fn sys_shmat(id: usize, addr: usize) -> isize {
+    syscall(SYSCALL_SHMAT, [id, addr, 0])
+}
+
+// system call used for unmapping a shared memory segment
+pub fn sys_shmdt(addr: usize) -> isize {
+    syscall(SYSCALL_SHMDT, [addr, 0, 0])
+}
+
+// system call used for creating a named pipe
+pub fn sys_mkfifo(path: &str) -> isize {
+    syscall(SYSCALL_MKFIFO, [path.as_ptr() as usize, 0, 0])
+}
+
+// system call used for waiting until one of several file descriptors is ready
+pub fn sys_ppoll(fds: &mut [PollFd], timeout_ms: isize) -> isize {
+    syscall(SYSCALL_PPOLL, [fds.as_mut_ptr() as usize, fds.len(), timeout_ms as usize])
+}
+
+// system call used for creating an epoll instance
+pub fn sys_epoll_create1(flags: u32) -> isize {
+    syscall(SYSCALL_EPOLL_CREATE1, [flags as usize, 0, 0])
+}
+
+// system call used for changing the interest list of an epoll instance
+pub fn sys_epoll_ctl(epfd: usize, op: usize, fd: usize, event: *const EpollEvent) -> isize {
+    syscall6(SYSCALL_EPOLL_CTL, [epfd, op, fd, event as usize, 0, 0])
+}
+
+// system call used for waiting for events of an epoll instance
+pub fn sys_epoll_pwait(epfd: usize, events: &mut [EpollEvent], timeout_ms: isize) -> isize {
+    syscall6(
+        SYSCALL_EPOLL_PWAIT,
+        [epfd, events.as_mut_ptr() as usize, events.len(), timeout_ms as usize, 0, 0],
+    )
+}
+
+// system call used for detaching the filesystem mounted at target
+pub fn sys_umount(target: &str, flags: u32) -> isize {
+    syscall(SYSCALL_UMOUNT, [target.as_ptr() as usize, flags as usize, 0])
+}
+
+// system call used for attaching the filesystem on source at target
+pub fn sys_mount(source: &str, target: &str, fstype: &str, flags: u32) -> isize {
+    syscall6(
+        SYSCALL_MOUNT,
+        [
+            source.as_ptr() as usize,
+            target.as_ptr() as usize,
+            fstype.as_ptr() as usize,
+            flags as usize,
+            0,
+            0,
+        ],
+    )
+}
+
+// system call used for changing the root directory of the current process
+pub fn sys_chroot(path: &str) -> isize {
+    syscall(SYSCALL_CHROOT, [path.as_ptr() as usize, 0, 0])
+}
+
+// system call used for getting the usage of the filesystem containing path
+pub fn sys_statfs(path: &str, st: *mut StatFs) -> isize {
+    syscall(SYSCALL_STATFS, [path.as_ptr() as usize, st as usize, 0])
+}
+
 pub fn sys_open(path: &str, flags: u32) -> isize {
     syscall(SYSCALL_OPEN, [path.as_ptr() as usize, flags as usize, 0])
 }
@@ -50,6 +345,11 @@ pub fn sys_pipe(pipe_fd: &mut [usize; 2]) -> isize {
     syscall(SYSCALL_PIPE, [pipe_fd.as_mut_ptr() as usize, 0, 0])
 }
 
+// system call used for reading the entries of a directory
+pub fn sys_getdents(fd: usize, dirents: &mut [Dirent]) -> isize {
+    syscall(SYSCALL_GETDENTS, [fd, dirents.as_mut_ptr() as usize, dirents.len()])
+}
+
 pub fn sys_read(fd: usize, buffer: &mut [u8]) -> isize {
     syscall(SYSCALL_READ, [fd, buffer.as_ptr() as usize, buffer.len()])
 }
@@ -90,6 +390,21 @@ pub fn sys_shutdown(failure: usize) -> ! {
     unreachable!();
 }
 
+// system call used for tracing and controlling another process
+pub fn sys_ptrace(request: usize, pid: usize, addr: usize, data: usize) -> isize {
+    syscall6(SYSCALL_PTRACE, [request, pid, addr, data, 0, 0])
+}
+
+// system call used for reading the kernel log and changing its level
+pub fn sys_syslog(action: usize, buf: *mut u8, len: usize) -> isize {
+    syscall(SYSCALL_SYSLOG, [action, buf as usize, len])
+}
+
+// system call used for halting, powering off or restarting the machine
+pub fn sys_reboot(cmd: u32) -> isize {
+    syscall(SYSCALL_REBOOT, [cmd as usize, 0, 0])
+}
+
 // system call used for mask/unmask signals
 pub fn sys_sigprocmask(mask: u32) -> isize {
     syscall(SYSCALL_SIGPROCMASK, [mask as usize, 0, 0])
@@ -100,7 +415,161 @@ pub fn sys_sigreturn() -> isize {
     syscall(SYSCALL_SIGRETURN, [0, 0, 0])
 }
 
-// system call used for getting time in milliseconds
+// system call used for setting the scheduling priority of the process
+pub fn sys_set_priority(prio: isize) -> isize {
+    syscall(SYSCALL_SET_PRIORITY, [prio as usize, 0, 0])
+}
+
+// system call used for setting the harts a process may run on
+pub fn sys_sched_setaffinity(pid: usize, mask: &usize) -> isize {
+    syscall(
+        SYSCALL_SCHED_SETAFFINITY,
+        [pid, core::mem::size_of::<usize>(), mask as *const _ as usize],
+    )
+}
+
+// system call used for getting the harts a process may run on
+pub fn sys_sched_getaffinity(pid: usize, mask: &mut usize) -> isize {
+    syscall(
+        SYSCALL_SCHED_GETAFFINITY,
+        [pid, core::mem::size_of::<usize>(), mask as *mut _ as usize],
+    )
+}
+
+// system call used for creating a thread in the current process
+pub fn sys_thread_create(entry: usize, arg: usize) -> isize {
+    syscall(SYSCALL_THREAD_CREATE, [entry, arg, 0])
+}
+
+// system call used for getting the tid of the current thread
+pub fn sys_gettid() -> isize {
+    syscall(SYSCALL_GETTID, [0, 0, 0])
+}
+
+// system call used for waiting for a thread to exit
+pub fn sys_waittid(tid: usize) -> isize {
+    syscall(SYSCALL_WAITTID, [tid, 0, 0])
+}
+
+// system call used for creating a mutex, which blocks or spins when taken
+pub fn sys_mutex_create(blocking: bool) -> isize {
+    syscall(SYSCALL_MUTEX_CREATE, [blocking as usize, 0, 0])
+}
+
+// system call used for locking a mutex
+pub fn sys_mutex_lock(id: usize) -> isize {
+    syscall(SYSCALL_MUTEX_LOCK, [id, 0, 0])
+}
+
+// system call used for unlocking a mutex
+pub fn sys_mutex_unlock(id: usize) -> isize {
+    syscall(SYSCALL_MUTEX_UNLOCK, [id, 0, 0])
+}
+
+// system call used for creating a semaphore
+pub fn sys_semaphore_create(res_count: usize) -> isize {
+    syscall(SYSCALL_SEMAPHORE_CREATE, [res_count, 0, 0])
+}
+
+// system call used for releasing a resource of a semaphore
+pub fn sys_semaphore_up(sem_id: usize) -> isize {
+    syscall(SYSCALL_SEMAPHORE_UP, [sem_id, 0, 0])
+}
+
+// system call used for acquiring a resource of a semaphore
+pub fn sys_semaphore_down(sem_id: usize) -> isize {
+    syscall(SYSCALL_SEMAPHORE_DOWN, [sem_id, 0, 0])
+}
+
+// system call used for creating a condition variable
+pub fn sys_condvar_create() -> isize {
+    syscall(SYSCALL_CONDVAR_CREATE, [0, 0, 0])
+}
+
+// system call used for waking a thread waiting on a condition variable
+pub fn sys_condvar_signal(condvar_id: usize) -> isize {
+    syscall(SYSCALL_CONDVAR_SIGNAL, [condvar_id, 0, 0])
+}
+
+// system call used for waiting on a condition variable
+pub fn sys_condvar_wait(condvar_id: usize, mutex_id: usize) -> isize {
+    syscall(SYSCALL_CONDVAR_WAIT, [condvar_id, mutex_id, 0])
+}
+
+// system call used for waiting on a condition variable with a timeout
+pub fn sys_condvar_timedwait(condvar_id: usize, mutex_id: usize, timeout: &TimeSpec) -> isize {
+    syscall(
+        SYSCALL_CONDVAR_TIMEDWAIT,
+        [condvar_id, mutex_id, timeout as *const _ as usize],
+    )
+}
+
+// system call used for setting the nice value of a process
+pub fn sys_set_nice(pid: usize, nice: isize) -> isize {
+    syscall(SYSCALL_SET_NICE, [pid, nice as usize, 0])
+}
+
+// system call used for getting the nice value of a process
+pub fn sys_get_nice(pid: usize) -> isize {
+    syscall(SYSCALL_GET_NICE, [pid, 0, 0])
+}
+
+// system call used for sleeping without using the CPU
+pub fn sys_nanosleep(req: &TimeSpec, rem: *mut TimeSpec) -> isize {
+    syscall(SYSCALL_NANOSLEEP, [req as *const _ as usize, rem as usize, 0])
+}
+
+// system call used for reading an interval timer
+pub fn sys_getitimer(which: usize, curr_value: &mut ITimerVal) -> isize {
+    syscall(SYSCALL_GETITIMER, [which, curr_value as *mut _ as usize, 0])
+}
+
+// system call used for arming or disarming an interval timer
+pub fn sys_setitimer(which: usize, new_value: &ITimerVal, old_value: *mut ITimerVal) -> isize {
+    syscall(
+        SYSCALL_SETITIMER,
+        [which, new_value as *const _ as usize, old_value as usize],
+    )
+}
+
+// system call used for creating a POSIX timer
+pub fn sys_timer_create(clock_id: usize, sevp: *const SigEvent, timer_id: &mut usize) -> isize {
+    syscall(
+        SYSCALL_TIMER_CREATE,
+        [clock_id, sevp as usize, timer_id as *mut _ as usize],
+    )
+}
+
+// system call used for reading the time left on a POSIX timer
+pub fn sys_timer_gettime(timer_id: usize, curr_value: &mut ITimerVal) -> isize {
+    syscall(SYSCALL_TIMER_GETTIME, [timer_id, curr_value as *mut _ as usize, 0])
+}
+
+// system call used for arming or disarming a POSIX timer
+pub fn sys_timer_settime(
+    timer_id: usize,
+    flags: usize,
+    new_value: &ITimerVal,
+    old_value: *mut ITimerVal,
+) -> isize {
+    syscall6(
+        SYSCALL_TIMER_SETTIME,
+        [timer_id, flags, new_value as *const _ as usize, old_value as usize, 0, 0],
+    )
+}
+
+// system call used for deleting a POSIX timer
+pub fn sys_timer_delete(timer_id: usize) -> isize {
+    syscall(SYSCALL_TIMER_DELETE, [timer_id, 0, 0])
+}
+
+// system call used for reading a clock
+pub fn sys_clock_gettime(clock_id: usize, tp: &mut TimeSpec) -> isize {
+    syscall(SYSCALL_CLOCK_GETTIME, [clock_id, tp as *mut _ as usize, 0])
+}
+
+// system call used for getting the time since boot in milliseconds, kept
+// for kernels without clock_gettime
 pub fn sys_get_time() -> isize {
     syscall(SYSCALL_GET_TIME, [0, 0, 0])
 }
@@ -110,17 +579,74 @@ pub fn sys_getpid() -> isize {
     syscall(SYSCALL_GETPID, [0, 0, 0])
 }
 
+// system call used for moving a process into a process group
+pub fn sys_setpgid(pid: usize, pgid: usize) -> isize {
+    syscall(SYSCALL_SETPGID, [pid, pgid, 0])
+}
+
+// system call used for getting the process group of a process
+pub fn sys_getpgid(pid: usize) -> isize {
+    syscall(SYSCALL_GETPGID, [pid, 0, 0])
+}
+
+// system call used for starting a new session
+pub fn sys_setsid() -> isize {
+    syscall(SYSCALL_SETSID, [0, 0, 0])
+}
+
+// system call used for getting the name and version of the kernel
+pub fn sys_uname(name: &mut UtsName) -> isize {
+    syscall(SYSCALL_UNAME, [name as *mut _ as usize, 0, 0])
+}
+
 // system call used for fork current process
 pub fn sys_fork() -> isize {
     syscall(SYSCALL_FORK, [0, 0, 0])
 }
 
 // system call used for exec a new program
-pub fn sys_exec(path: &str, args: &[*const u8]) -> isize {
-    syscall(SYSCALL_EXEC, [path.as_ptr() as usize, args.as_ptr() as usize, 0])
+pub fn sys_exec(path: &str, args: &[*const u8], envp: &[*const u8]) -> isize {
+    syscall(SYSCALL_EXEC, [path.as_ptr() as usize, args.as_ptr() as usize, envp.as_ptr() as usize])
 }
 
 // system call used for wait child process
-pub fn sys_waitpid(pid: isize, exit_code: *mut i32) -> isize {
-    syscall(SYSCALL_WAITPID, [pid as usize, exit_code as usize, 0])
+pub fn sys_waitpid(pid: isize, exit_code: *mut i32, options: usize) -> isize {
+    syscall(SYSCALL_WAITPID, [pid as usize, exit_code as usize, options])
+}
+
+// system call used for filling a buffer with random bytes from the kernel entropy pool
+pub fn sys_getrandom(buf: &mut [u8], flags: u32) -> isize {
+    syscall(SYSCALL_GETRANDOM, [buf.as_mut_ptr() as usize, buf.len(), flags as usize])
+}
+
+// system call used for submitting an asynchronous read or write, return the request id
+pub fn sys_aio_submit(request: *const AioRequest) -> isize {
+    syscall(SYSCALL_AIO_SUBMIT, [request as usize, 0, 0])
+}
+
+// system call used for getting the result of an asynchronous request,
+// return -2 if `nonblock` is set and the request is still in flight
+pub fn sys_aio_getevents(id: usize, nonblock: bool) -> isize {
+    syscall(SYSCALL_AIO_GETEVENTS, [id, nonblock as usize, 0])
+}
+
+// system call used for copying data between two files inside the kernel,
+// a null offset pointer means using and updating the file offset
+pub fn sys_copy_file_range(
+    fd_in: usize,
+    off_in: *mut usize,
+    fd_out: usize,
+    off_out: *mut usize,
+    len: usize,
+    flags: u32,
+) -> isize {
+    syscall6(
+        SYSCALL_COPY_FILE_RANGE,
+        [fd_in, off_in as usize, fd_out, off_out as usize, len, flags as usize],
+    )
+}
+
+// system call used for getting the information of all tasks
+pub fn sys_task_info(tasks: &mut [TaskInfo]) -> isize {
+    syscall(SYSCALL_TASK_INFO, [tasks.as_mut_ptr() as usize, tasks.len(), 0])
 }
\ No newline at end of file
@@ -0,0 +1,173 @@
+//! Terminal control through ANSI escape sequences, a tiny ncurses-lite
+//! for programs drawing full-screen interfaces on the console.
+
+use crate::{tcgetattr, tcsetattr, Termios};
+
+/// Standard colors, usable wherever a 256-color index is expected
+pub const BLACK: u8 = 0;
+pub const RED: u8 = 1;
+pub const GREEN: u8 = 2;
+pub const YELLOW: u8 = 3;
+pub const BLUE: u8 = 4;
+pub const MAGENTA: u8 = 5;
+pub const CYAN: u8 = 6;
+pub const WHITE: u8 = 7;
+/// Add to a standard color to get its bright version
+pub const BRIGHT: u8 = 8;
+
+/// Clear the whole screen and move the cursor to the top left corner
+pub fn clear_screen() {
+    print!("\x1b[2J\x1b[H");
+}
+
+/// Clear the line under the cursor
+pub fn clear_line() {
+    print!("\x1b[2K\r");
+}
+
+/// Clear from the cursor to the end of the line
+pub fn clear_to_eol() {
+    print!("\x1b[K");
+}
+
+/// Move the cursor to `row` and `col`, both counted from 1
+pub fn move_to(row: usize, col: usize) {
+    print!("\x1b[{};{}H", row, col);
+}
+
+/// Move the cursor up by `n` rows
+pub fn move_up(n: usize) {
+    if n > 0 {
+        print!("\x1b[{}A", n);
+    }
+}
+
+/// Move the cursor down by `n` rows
+pub fn move_down(n: usize) {
+    if n > 0 {
+        print!("\x1b[{}B", n);
+    }
+}
+
+/// Move the cursor right by `n` columns
+pub fn move_right(n: usize) {
+    if n > 0 {
+        print!("\x1b[{}C", n);
+    }
+}
+
+/// Move the cursor left by `n` columns
+pub fn move_left(n: usize) {
+    if n > 0 {
+        print!("\x1b[{}D", n);
+    }
+}
+
+/// Settings of the console restored when raw mode ends
+pub struct RawMode(Option<Termios>);
+
+/// Put the console in raw mode until the returned guard is dropped, so keys
+/// are read one at a time without echo. Nothing changes if stdin is not a
+/// terminal
+pub fn enable_raw_mode() -> RawMode {
+    let mut termios = Termios::default();
+    if tcgetattr(0, &mut termios) != 0 {
+        return RawMode(None);
+    }
+    let saved = termios;
+    termios.make_raw();
+    tcsetattr(0, &termios);
+    RawMode(Some(saved))
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if let Some(termios) = self.0.as_ref() {
+            tcsetattr(0, termios);
+        }
+    }
+}
+
+pub fn hide_cursor() {
+    print!("\x1b[?25l");
+}
+
+pub fn show_cursor() {
+    print!("\x1b[?25h");
+}
+
+pub fn save_cursor() {
+    print!("\x1b7");
+}
+
+pub fn restore_cursor() {
+    print!("\x1b8");
+}
+
+/// Set the foreground color by its index in the 256-color palette
+pub fn set_fg(color: u8) {
+    print!("\x1b[38;5;{}m", color);
+}
+
+/// Set the background color by its index in the 256-color palette
+pub fn set_bg(color: u8) {
+    print!("\x1b[48;5;{}m", color);
+}
+
+/// Index of a color in the 6x6x6 cube of the 256-color palette,
+/// each component ranges from 0 to 5
+pub fn rgb(r: u8, g: u8, b: u8) -> u8 {
+    16 + 36 * r.min(5) + 6 * g.min(5) + b.min(5)
+}
+
+/// Index of a gray in the 256-color palette, `level` ranges from 0 to 23
+pub fn gray(level: u8) -> u8 {
+    232 + level.min(23)
+}
+
+pub fn bold() {
+    print!("\x1b[1m");
+}
+
+pub fn reverse() {
+    print!("\x1b[7m");
+}
+
+/// Reset colors and text attributes
+pub fn reset() {
+    print!("\x1b[0m");
+}
+
+/// Print `text` at `row` and `col` with the given colors, then reset attributes
+pub fn print_at(row: usize, col: usize, fg: u8, bg: u8, text: &str) {
+    move_to(row, col);
+    set_fg(fg);
+    set_bg(bg);
+    print!("{}", text);
+    reset();
+}
+
+/// Draw a box with its top left corner at `row` and `col`
+pub fn draw_box(row: usize, col: usize, height: usize, width: usize) {
+    if height < 2 || width < 2 {
+        return;
+    }
+    move_to(row, col);
+    print!("+");
+    for _ in 0..width - 2 {
+        print!("-");
+    }
+    print!("+");
+    for i in 1..height - 1 {
+        move_to(row + i, col);
+        print!("|");
+        move_to(row + i, col + width - 1);
+        print!("|");
+    }
+    move_to(row + height - 1, col);
+    print!("+");
+    for _ in 0..width - 2 {
+        print!("-");
+    }
+    print!("+");
+}